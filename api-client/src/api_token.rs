@@ -0,0 +1,26 @@
+use common::schema::{
+    api_token::{ApiToken, ApiTokenListResponse, CreateApiTokenResponse, CreateApiTokenSchema},
+    error::ErrorResponse,
+    message::MessageResponse
+};
+use uuid::Uuid;
+
+use crate::{json, ApiClient, Method, Transport};
+
+impl<T: Transport> ApiClient<T> {
+    /// The logged in user's personal API tokens.
+    pub async fn list_api_tokens(&self) -> Result<Vec<ApiToken>, ErrorResponse> {
+        let response: ApiTokenListResponse = self.request(Method::Get, "/api/v1/user/tokens", None).await?;
+
+        Ok(response.tokens)
+    }
+
+    /// Creates a personal API token. The response is the only time the full token is returned.
+    pub async fn create_api_token(&self, token: &CreateApiTokenSchema) -> Result<CreateApiTokenResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/user/tokens", json(token)).await
+    }
+
+    pub async fn revoke_api_token(&self, token_id: Uuid) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Delete, &format!("/api/v1/user/tokens/{}", token_id), None).await
+    }
+}
@@ -0,0 +1,62 @@
+use common::schema::{
+    error::ErrorResponse,
+    message::MessageResponse,
+    user::{
+        EmailRequestSchema, FilteredUser, LoginResponse, LoginUserSchema, ResetPasswordSchema, SignupUserSchema,
+        UserLoginResponse, UserResponse, VerifyEmailSchema
+    }
+};
+
+use crate::{json, ApiClient, Method, Transport};
+
+impl<T: Transport> ApiClient<T> {
+    /// Registers a user. A verification email is sent before they can log in.
+    pub async fn register(&self, user: &SignupUserSchema) -> Result<FilteredUser, ErrorResponse> {
+        let response: UserResponse = self.request(Method::Post, "/api/v1/auth/register", json(user)).await?;
+
+        Ok(response.data.user)
+    }
+
+    /// Logs in, setting the session cookies.
+    /// Accounts with two-factor authentication enabled get a challenge to pass to `verify_two_factor` instead.
+    pub async fn login(&self, credentials: &LoginUserSchema) -> Result<LoginResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/auth/login", json(credentials)).await
+    }
+
+    /// Replaces the access token cookie using the refresh token cookie.
+    /// Other requests do this on their own when the access token expires.
+    pub async fn refresh(&self) -> Result<UserLoginResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/auth/refresh", None).await
+    }
+
+    pub async fn logout(&self) -> Result<(), ErrorResponse> {
+        self.send(Method::Post, "/api/v1/auth/logout", None).await?;
+
+        Ok(())
+    }
+
+    /// Confirms a user's email address using the token from the verification email.
+    pub async fn verify_email(&self, token: &VerifyEmailSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/auth/verify-email", json(token)).await
+    }
+
+    /// Requests a new verification email.
+    pub async fn resend_verification(&self, email: &EmailRequestSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/auth/verify-email/resend", json(email)).await
+    }
+
+    /// Confirms a change of email address using the token from the confirmation email.
+    pub async fn confirm_email_change(&self, token: &VerifyEmailSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/auth/confirm-email-change", json(token)).await
+    }
+
+    /// Requests a password reset email.
+    pub async fn forgot_password(&self, email: &EmailRequestSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/auth/forgot-password", json(email)).await
+    }
+
+    /// Sets a new password using the token from the password reset email.
+    pub async fn reset_password(&self, reset: &ResetPasswordSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/auth/reset-password", json(reset)).await
+    }
+}
@@ -0,0 +1,136 @@
+//! A typed client for the Rusty Melody API, shared by the Yew frontend and Rust tooling.
+//!
+//! Requests go through a `Transport`: `ReqwasmTransport` in the browser (`reqwasm` feature),
+//! `ReqwestTransport` for CLIs and integration tests (`reqwest` feature).
+
+mod api_token;
+mod auth;
+mod profile;
+mod recommendation;
+mod search;
+mod social;
+mod song;
+mod two_factor;
+mod user;
+pub mod transport;
+
+use common::schema::error::{ErrorCode, ErrorResponse};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use transport::{Body, Method, Request, Response, Transport};
+#[cfg(feature = "reqwasm")]
+pub use transport::ReqwasmTransport;
+#[cfg(feature = "reqwest")]
+pub use transport::ReqwestTransport;
+
+/// Every method returns the server's `ErrorResponse` on failure.
+/// Failures that never reached the server, or returned something unexpected, get one with `ErrorCode::Internal`.
+///
+/// When a request fails because the access token expired, the client refreshes it using the
+/// refresh token cookie and retries the request once.
+#[derive(Debug, Clone)]
+pub struct ApiClient<T> {
+    base_url: String,
+    bearer_token: Option<String>,
+    transport: T,
+}
+
+impl<T: Transport> ApiClient<T> {
+    /// ### Arguments
+    ///
+    /// * `base_url` - Where the server is running, e.g. `http://localhost:8000`.
+    /// * `transport` - What sends the requests.
+    pub fn new(base_url: &str, transport: T) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            bearer_token: None,
+            transport,
+        }
+    }
+
+    /// Authenticates with a personal API token, or an access token, instead of the session cookies.
+    /// These can't be refreshed, so failed requests aren't retried.
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.bearer_token = Some(token.to_string());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// The absolute URL of an API path, for links the browser follows itself.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn send_once(&self, method: Method, path: &str, body: Option<Body>) -> Result<Response, ErrorResponse> {
+        let request = Request {
+            method,
+            url: self.url(path),
+            bearer_token: self.bearer_token.clone(),
+            body,
+        };
+
+        self.transport
+            .send(request)
+            .await
+            .map_err(|e| ErrorResponse::new(&format!("Failed to make request: {}", e)))
+    }
+
+    /// Sends a request, refreshing the access token and retrying once if it expired.
+    /// Error statuses are turned into the `ErrorResponse` the server sent.
+    async fn send(&self, method: Method, path: &str, body: Option<Body>) -> Result<Response, ErrorResponse> {
+        let mut response = self.send_once(method, path, body.clone()).await?;
+
+        let refreshable = self.bearer_token.is_none() && !path.starts_with("/api/v1/auth/");
+        if response.status == 401 && refreshable && error_response(&response).code == ErrorCode::Unauthenticated {
+            let refreshed = self.send_once(Method::Post, "/api/v1/auth/refresh", None).await?;
+            if refreshed.is_success() {
+                response = self.send_once(method, path, body).await?;
+            }
+        }
+
+        if !response.is_success() {
+            return Err(error_response(&response));
+        }
+
+        Ok(response)
+    }
+
+    /// Sends a request and parses the JSON response.
+    async fn request<R: DeserializeOwned>(&self, method: Method, path: &str, body: Option<Body>) -> Result<R, ErrorResponse> {
+        let response = self.send(method, path, body).await?;
+
+        serde_json::from_slice(&response.body).map_err(|_| ErrorResponse::new("Failed to parse response"))
+    }
+}
+
+fn json<B: Serialize>(body: &B) -> Option<Body> {
+    // Serializing only fails for maps with non-string keys, which none of the request schemas have
+    Some(Body::Json(serde_json::to_string(body).expect("Failed to serialize request body")))
+}
+
+/// Appends the cursor from a previous page to `path`, if there is one.
+fn with_cursor(path: String, cursor: Option<&str>) -> String {
+    match cursor {
+        Some(cursor) => format!("{}?cursor={}", path, cursor),
+        None => path,
+    }
+}
+
+/// Percent-encodes a query string value, leaving only unreserved characters as they are.
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn error_response(response: &Response) -> ErrorResponse {
+    serde_json::from_slice::<ErrorResponse>(&response.body)
+        .unwrap_or_else(|_| ErrorResponse::new(&format!("API error: {}", response.status)))
+}
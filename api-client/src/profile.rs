@@ -0,0 +1,19 @@
+use common::schema::{
+    error::ErrorResponse,
+    profile::{PublicProfile, PublicProfileResponse}
+};
+
+use crate::{ApiClient, Method, Transport};
+
+impl<T: Transport> ApiClient<T> {
+    /// A user's public profile. Logged in viewers may see more, depending on the user's privacy settings.
+    ///
+    /// ### Arguments
+    ///
+    /// * `username` - The username to look up, in any case.
+    pub async fn public_profile(&self, username: &str) -> Result<PublicProfile, ErrorResponse> {
+        let response: PublicProfileResponse = self.request(Method::Get, &format!("/api/v1/users/{}", username), None).await?;
+
+        Ok(response.profile)
+    }
+}
@@ -0,0 +1,37 @@
+use common::schema::{
+    error::ErrorResponse,
+    message::MessageResponse,
+    recommendation::{Recommendation, RecommendationFeedback, RecommendationFeedbackSchema, RecommendationListResponse}
+};
+use uuid::Uuid;
+
+use crate::{json, ApiClient, Method, Transport};
+
+impl<T: Transport> ApiClient<T> {
+    /// A fresh batch of songs recommended to the logged in user, best match first.
+    ///
+    /// ### Arguments
+    ///
+    /// * `limit` - How many songs to recommend, at most 50.
+    pub async fn recommendations(&self, limit: i64) -> Result<Vec<Recommendation>, ErrorResponse> {
+        let response: RecommendationListResponse =
+            self.request(Method::Get, &format!("/api/v1/user/recommendations?limit={}", limit), None).await?;
+
+        Ok(response.recommendations)
+    }
+
+    /// Tells the server what the logged in user thinks of a recommended song, so later batches take it into account.
+    pub async fn send_recommendation_feedback(
+        &self,
+        song_id: Uuid,
+        feedback: RecommendationFeedback,
+    ) -> Result<MessageResponse, ErrorResponse> {
+        let path = format!("/api/v1/user/recommendations/{}/feedback", song_id);
+        self.request(Method::Put, &path, json(&RecommendationFeedbackSchema { feedback })).await
+    }
+
+    /// Takes back the feedback on a song.
+    pub async fn clear_recommendation_feedback(&self, song_id: Uuid) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Delete, &format!("/api/v1/user/recommendations/{}/feedback", song_id), None).await
+    }
+}
@@ -0,0 +1,29 @@
+use common::schema::{
+    error::ErrorResponse,
+    search::{SearchResponse, SearchResults}
+};
+
+use crate::{encode_query_value, ApiClient, Method, Transport};
+
+impl<T: Transport> ApiClient<T> {
+    /// Songs, artists, albums and public playlists matching `query`, grouped by kind.
+    ///
+    /// ### Arguments
+    ///
+    /// * `query` - What to look for, ignoring case.
+    /// * `limit` - How many matches to return of each kind, at most 50.
+    pub async fn search(&self, query: &str, limit: i64) -> Result<SearchResults, ErrorResponse> {
+        let path = format!("/api/v1/search?q={}&limit={}", encode_query_value(query), limit);
+        let response: SearchResponse = self.request(Method::Get, &path, None).await?;
+
+        Ok(response.results)
+    }
+
+    /// A few of the best matches for `query` of each kind, to suggest while the user types.
+    pub async fn search_suggestions(&self, query: &str) -> Result<SearchResults, ErrorResponse> {
+        let path = format!("/api/v1/search/suggestions?q={}", encode_query_value(query));
+        let response: SearchResponse = self.request(Method::Get, &path, None).await?;
+
+        Ok(response.results)
+    }
+}
@@ -0,0 +1,70 @@
+use common::schema::{
+    error::ErrorResponse,
+    message::MessageResponse,
+    social::{FeedResponse, FollowListResponse, FollowStatusResponse, RecordPlaySchema}
+};
+use uuid::Uuid;
+
+use crate::{json, with_cursor, ApiClient, Method, Transport};
+
+impl<T: Transport> ApiClient<T> {
+    /// Whether the logged in user follows `username`.
+    pub async fn follow_status(&self, username: &str) -> Result<FollowStatusResponse, ErrorResponse> {
+        self.request(Method::Get, &format!("/api/v1/users/{}/follow", username), None).await
+    }
+
+    /// Follows `username`. Following someone already followed is not an error.
+    pub async fn follow(&self, username: &str) -> Result<FollowStatusResponse, ErrorResponse> {
+        self.request(Method::Post, &format!("/api/v1/users/{}/follow", username), None).await
+    }
+
+    /// Stops following `username`. Unfollowing someone not followed is not an error.
+    pub async fn unfollow(&self, username: &str) -> Result<FollowStatusResponse, ErrorResponse> {
+        self.request(Method::Delete, &format!("/api/v1/users/{}/follow", username), None).await
+    }
+
+    /// A page of the users following `username`.
+    ///
+    /// ### Arguments
+    ///
+    /// * `username` - The user whose followers to list.
+    /// * `cursor` - The `next_cursor` of the previous page, or `None` for the first page.
+    pub async fn followers(&self, username: &str, cursor: Option<&str>) -> Result<FollowListResponse, ErrorResponse> {
+        let path = with_cursor(format!("/api/v1/users/{}/followers", username), cursor);
+        self.request(Method::Get, &path, None).await
+    }
+
+    /// A page of the users `username` follows.
+    ///
+    /// ### Arguments
+    ///
+    /// * `username` - The user whose follows to list.
+    /// * `cursor` - The `next_cursor` of the previous page, or `None` for the first page.
+    pub async fn following(&self, username: &str, cursor: Option<&str>) -> Result<FollowListResponse, ErrorResponse> {
+        let path = with_cursor(format!("/api/v1/users/{}/following", username), cursor);
+        self.request(Method::Get, &path, None).await
+    }
+
+    /// A page of the logged in user's activity feed.
+    ///
+    /// ### Arguments
+    ///
+    /// * `cursor` - The `next_cursor` of the previous page, or `None` for the first page.
+    pub async fn feed(&self, cursor: Option<&str>) -> Result<FeedResponse, ErrorResponse> {
+        let path = with_cursor("/api/v1/user/feed".to_string(), cursor);
+        self.request(Method::Get, &path, None).await
+    }
+
+    pub async fn like_song(&self, song_id: Uuid) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Put, &format!("/api/v1/user/likes/{}", song_id), None).await
+    }
+
+    pub async fn unlike_song(&self, song_id: Uuid) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Delete, &format!("/api/v1/user/likes/{}", song_id), None).await
+    }
+
+    /// Adds a song to the logged in user's listening history.
+    pub async fn record_play(&self, play: &RecordPlaySchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/user/history", json(play)).await
+    }
+}
@@ -0,0 +1,71 @@
+use common::schema::{
+    album::{AlbumDetails, AlbumDetailsResponse},
+    artist::{ArtistDetails, ArtistDetailsResponse},
+    error::ErrorResponse,
+    song::{AudioFeatures, AudioFeaturesResponse, SongDetails, SongDetailsResponse}
+};
+use uuid::Uuid;
+
+use crate::{ApiClient, Body, Method, Transport};
+
+impl<T: Transport> ApiClient<T> {
+    /// A song with its features, platform links and similar songs. Needs no login.
+    pub async fn song(&self, song_id: Uuid) -> Result<SongDetails, ErrorResponse> {
+        let response: SongDetailsResponse = self.request(Method::Get, &format!("/api/v1/songs/{}", song_id), None).await?;
+
+        Ok(response.song)
+    }
+
+    /// An artist with their albums and most played songs. Needs no login.
+    pub async fn artist(&self, artist_id: Uuid) -> Result<ArtistDetails, ErrorResponse> {
+        let response: ArtistDetailsResponse =
+            self.request(Method::Get, &format!("/api/v1/artists/{}", artist_id), None).await?;
+
+        Ok(response.artist)
+    }
+
+    /// An album with its tracks, in order. Needs no login.
+    pub async fn album(&self, album_id: Uuid) -> Result<AlbumDetails, ErrorResponse> {
+        let response: AlbumDetailsResponse = self.request(Method::Get, &format!("/api/v1/albums/{}", album_id), None).await?;
+
+        Ok(response.album)
+    }
+
+    /// Uploads the audio of a song, returning the features measured from it.
+    ///
+    /// ### Arguments
+    ///
+    /// * `song_id` - The song the audio belongs to.
+    /// * `file_name` - The name of the uploaded file.
+    /// * `content_type` - The file's MIME type, e.g. `audio/mpeg`.
+    /// * `bytes` - A WAV, FLAC or MP3 file.
+    pub async fn upload_song_audio(
+        &self,
+        song_id: Uuid,
+        file_name: &str,
+        content_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<AudioFeatures, ErrorResponse> {
+        let body = Body::File {
+            field: "audio",
+            file_name: file_name.to_string(),
+            content_type: content_type.to_string(),
+            bytes,
+        };
+        let response: AudioFeaturesResponse =
+            self.request(Method::Post, &format!("/api/v1/songs/{}/audio", song_id), Some(body)).await?;
+
+        Ok(response.data)
+    }
+
+    /// The URL the song's audio is streamed from, for an `HtmlAudioElement`.
+    /// The browser sends the session cookie when fetching it, as the stream needs a login.
+    pub fn song_stream_url(&self, song_id: Uuid) -> String {
+        self.url(&format!("/api/v1/songs/{}/stream", song_id))
+    }
+
+    /// The URL of the song's 30 second preview, which needs no login.
+    pub fn song_preview_url(&self, song_id: Uuid) -> String {
+        self.url(&format!("/api/v1/songs/{}/preview", song_id))
+    }
+}
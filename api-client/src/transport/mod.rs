@@ -0,0 +1,64 @@
+#[cfg(feature = "reqwasm")]
+mod reqwasm;
+#[cfg(feature = "reqwest")]
+mod reqwest;
+
+use async_trait::async_trait;
+
+#[cfg(feature = "reqwasm")]
+pub use self::reqwasm::ReqwasmTransport;
+#[cfg(feature = "reqwest")]
+pub use self::reqwest::ReqwestTransport;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+pub enum Body {
+    /// Sent with `Content-Type: application/json`
+    Json(String),
+    /// A single file sent as `multipart/form-data`
+    File {
+        field: &'static str,
+        file_name: String,
+        content_type: String,
+        bytes: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    /// Sent as `Authorization: Bearer <token>` when set
+    pub bearer_token: Option<String>,
+    pub body: Option<Body>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Sends requests for an `ApiClient`.
+/// Transports are expected to keep the session cookies the server sets, as browsers do,
+/// since that's how logging in and refreshing the access token work.
+/// Not `Send`, as the browser's fetch futures aren't.
+#[async_trait(?Send)]
+pub trait Transport {
+    /// Sends `request`, failing only if no response was received. Error statuses are returned as responses.
+    async fn send(&self, request: Request) -> Result<Response, String>;
+}
@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use js_sys::{Array, Uint8Array};
+use reqwasm::http;
+use web_sys::{Blob, BlobPropertyBag, FormData};
+
+use super::{Body, Method, Request, Response, Transport};
+
+/// Sends requests with the browser's fetch API. The browser keeps the session cookies.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReqwasmTransport;
+
+impl From<Method> for http::Method {
+    fn from(method: Method) -> Self {
+        match method {
+            Method::Get => http::Method::GET,
+            Method::Post => http::Method::POST,
+            Method::Put => http::Method::PUT,
+            Method::Patch => http::Method::PATCH,
+            Method::Delete => http::Method::DELETE,
+        }
+    }
+}
+
+fn form_data(field: &str, file_name: &str, content_type: &str, bytes: &[u8]) -> Result<FormData, String> {
+    let parts = Array::of1(&Uint8Array::from(bytes));
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, BlobPropertyBag::new().type_(content_type))
+        .map_err(|_| "Failed to read the file".to_string())?;
+
+    let form_data = FormData::new().map_err(|_| "Failed to create the form".to_string())?;
+    form_data
+        .append_with_blob_and_filename(field, &blob, file_name)
+        .map_err(|_| "Failed to create the form".to_string())?;
+
+    Ok(form_data)
+}
+
+#[async_trait(?Send)]
+impl Transport for ReqwasmTransport {
+    async fn send(&self, request: Request) -> Result<Response, String> {
+        let mut builder = http::Request::new(&request.url)
+            .method(request.method.into())
+            .credentials(http::RequestCredentials::Include);
+
+        if let Some(token) = &request.bearer_token {
+            builder = builder.header("Authorization", &format!("Bearer {}", token));
+        }
+
+        builder = match &request.body {
+            Some(Body::Json(json)) => builder
+                .header("Content-Type", "application/json")
+                .body(json.as_str()),
+            // The browser sets the multipart Content-Type, boundary included
+            Some(Body::File { field, file_name, content_type, bytes }) => {
+                builder.body(form_data(field, file_name, content_type, bytes)?)
+            }
+            None => builder,
+        };
+
+        let response = builder.send().await.map_err(|e| e.to_string())?;
+        let body = response.binary().await.map_err(|e| e.to_string())?;
+
+        Ok(Response {
+            status: response.status(),
+            body,
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+
+use super::{Body, Method, Request, Response, Transport};
+
+/// Sends requests with `reqwest`, keeping the session cookies in memory like a browser would.
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .cookie_store(true)
+            .build()
+            .expect("Failed to create the HTTP client");
+
+        Self { client }
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Method> for reqwest::Method {
+    fn from(method: Method) -> Self {
+        match method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Patch => reqwest::Method::PATCH,
+            Method::Delete => reqwest::Method::DELETE,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: Request) -> Result<Response, String> {
+        let mut builder = self.client.request(request.method.into(), &request.url);
+
+        if let Some(token) = &request.bearer_token {
+            builder = builder.bearer_auth(token);
+        }
+
+        builder = match request.body {
+            Some(Body::Json(json)) => builder
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(json),
+            Some(Body::File { field, file_name, content_type, bytes }) => {
+                let part = Part::bytes(bytes)
+                    .file_name(file_name)
+                    .mime_str(&content_type)
+                    .map_err(|e| e.to_string())?;
+                builder.multipart(Form::new().part(field, part))
+            }
+            None => builder,
+        };
+
+        let response = builder.send().await.map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        let body = response.bytes().await.map_err(|e| e.to_string())?;
+
+        Ok(Response {
+            status,
+            body: body.to_vec(),
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use common::schema::{
+    error::ErrorResponse,
+    message::MessageResponse,
+    two_factor::{
+        DisableTwoFactorSchema, RecoveryCodesResponse, TwoFactorCodeSchema, TwoFactorEnrollResponse,
+        TwoFactorVerifySchema
+    },
+    user::UserLoginResponse
+};
+
+use crate::{json, ApiClient, Method, Transport};
+
+impl<T: Transport> ApiClient<T> {
+    /// Completes a two-factor login with the challenge from `login` and a TOTP or recovery code.
+    pub async fn verify_two_factor(&self, verify: &TwoFactorVerifySchema) -> Result<UserLoginResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/auth/2fa/verify", json(verify)).await
+    }
+
+    /// Starts enrolling an authenticator app, returning its secret and `otpauth://` URI.
+    pub async fn enroll_two_factor(&self) -> Result<TwoFactorEnrollResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/user/2fa/enroll", None).await
+    }
+
+    /// Turns on two-factor authentication after confirming a code from the authenticator app.
+    pub async fn enable_two_factor(&self, code: &TwoFactorCodeSchema) -> Result<RecoveryCodesResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/user/2fa/enable", json(code)).await
+    }
+
+    pub async fn disable_two_factor(&self, disable: &DisableTwoFactorSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/user/2fa/disable", json(disable)).await
+    }
+
+    /// Replaces the recovery codes, invalidating the old ones.
+    pub async fn regenerate_recovery_codes(&self, code: &TwoFactorCodeSchema) -> Result<RecoveryCodesResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/user/2fa/recovery-codes", json(code)).await
+    }
+}
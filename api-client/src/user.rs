@@ -0,0 +1,95 @@
+use common::schema::{
+    error::ErrorResponse,
+    message::MessageResponse,
+    privacy::UpdatePrivacySchema,
+    user::{ChangeEmailSchema, ChangePasswordSchema, DeleteAccountSchema, FilteredUser, UpdateProfileSchema, UserResponse}
+};
+
+use crate::{json, ApiClient, Body, Method, Transport};
+
+/// Where the logged in user's data export is downloaded from
+const EXPORT_PATH: &str = "/api/v1/user/export";
+
+impl<T: Transport> ApiClient<T> {
+    pub async fn health_check(&self) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Get, "/api/v1/healthchecker", None).await
+    }
+
+    /// The logged in user.
+    pub async fn user_info(&self) -> Result<FilteredUser, ErrorResponse> {
+        let response: UserResponse = self.request(Method::Get, "/api/v1/user/info", None).await?;
+
+        Ok(response.data.user)
+    }
+
+    /// The logged in user's preferences. The server doesn't store any yet and answers with an empty body.
+    pub async fn user_preferences(&self) -> Result<(), ErrorResponse> {
+        self.send(Method::Get, "/api/v1/user/preferences", None).await?;
+
+        Ok(())
+    }
+
+    /// Updates the logged in user's preferences. The server doesn't store any yet and answers with an empty body.
+    pub async fn update_user_preferences(&self) -> Result<(), ErrorResponse> {
+        self.send(Method::Put, "/api/v1/user/preferences", None).await?;
+
+        Ok(())
+    }
+
+    pub async fn update_profile(&self, profile: &UpdateProfileSchema) -> Result<FilteredUser, ErrorResponse> {
+        let response: UserResponse = self.request(Method::Patch, "/api/v1/user/profile", json(profile)).await?;
+
+        Ok(response.data.user)
+    }
+
+    pub async fn update_privacy(&self, privacy: &UpdatePrivacySchema) -> Result<FilteredUser, ErrorResponse> {
+        let response: UserResponse = self.request(Method::Patch, "/api/v1/user/privacy", json(privacy)).await?;
+
+        Ok(response.data.user)
+    }
+
+    pub async fn change_password(&self, passwords: &ChangePasswordSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/user/password", json(passwords)).await
+    }
+
+    /// Starts changing the user's email. The change only applies once it's confirmed from the new address.
+    pub async fn change_email(&self, email: &ChangeEmailSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Post, "/api/v1/user/email", json(email)).await
+    }
+
+    /// Permanently deletes the logged in user's account.
+    pub async fn delete_account(&self, confirmation: &DeleteAccountSchema) -> Result<MessageResponse, ErrorResponse> {
+        self.request(Method::Delete, "/api/v1/user", json(confirmation)).await
+    }
+
+    /// Uploads a new avatar, returning the user with its URL.
+    ///
+    /// ### Arguments
+    ///
+    /// * `file_name` - The name of the uploaded file.
+    /// * `content_type` - The image's MIME type, e.g. `image/png`.
+    /// * `bytes` - The image.
+    pub async fn upload_avatar(&self, file_name: &str, content_type: &str, bytes: Vec<u8>) -> Result<FilteredUser, ErrorResponse> {
+        let body = Body::File {
+            field: "avatar",
+            file_name: file_name.to_string(),
+            content_type: content_type.to_string(),
+            bytes,
+        };
+        let response: UserResponse = self.request(Method::Post, "/api/v1/user/avatar", Some(body)).await?;
+
+        Ok(response.data.user)
+    }
+
+    /// Everything stored about the logged in user, as a ZIP of JSON files.
+    pub async fn export_user_data(&self) -> Result<Vec<u8>, ErrorResponse> {
+        let response = self.send(Method::Get, EXPORT_PATH, None).await?;
+
+        Ok(response.body)
+    }
+
+    /// The URL of the data export. The browser sends the session cookie when following it, so it can be used as a plain link.
+    pub fn export_url(&self) -> String {
+        self.url(EXPORT_PATH)
+    }
+}
@@ -1,3 +1,13 @@
-pub mod user_api;
-pub mod feedback_api;
-pub mod search_api;
\ No newline at end of file
+
+use api_client::{ApiClient, ReqwasmTransport};
+
+/// The client for the server's API. The browser keeps the session cookies, so it can be created wherever it's needed.
+pub fn client() -> ApiClient<ReqwasmTransport> {
+    #[cfg(debug_assertions)]
+    let api_url = "http://localhost:8000";
+
+    #[cfg(not(debug_assertions))]
+    let api_url = std::env!("SERVER_URL");
+
+    ApiClient::new(api_url, ReqwasmTransport)
+}
\ No newline at end of file
@@ -4,7 +4,8 @@ use yew_router::prelude::*;
 use crate::components::{
     alert::{AlertComponent, AlertProps},
     ui::spinner::Spinner,
-    header::Header
+    header::Header,
+    player::Player
 };
 use web_sys::window;
 
@@ -31,6 +32,9 @@ pub fn app() -> Html {
                     <Switch<Route> render={switch} />
                 </main>
             </div>
+
+            // Outside the `Switch` so playback carries on across pages
+            <Player />
             
             if show_alert {
                 <AlertComponent
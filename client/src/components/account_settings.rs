@@ -0,0 +1,195 @@
+use crate::api::client;
+use crate::components::ui::{button::Button, input::Input};
+use crate::router::Route;
+use crate::store::{set_auth_user, set_show_alert, Store};
+use common::schema::user::{ChangeEmailSchema, ChangePasswordSchema, DeleteAccountSchema};
+
+use validator::Validate;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::use_navigator;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct AccountSettingsProps {
+    /// New email address waiting to be confirmed, if any
+    pub pending_email: Option<String>,
+    /// Whether deleting the account needs a code from the authenticator app
+    pub two_factor_enabled: bool,
+}
+
+/// Returns the first validation message, for showing in an alert
+fn first_error_message<T: Validate>(form: &T, fallback: &str) -> Option<String> {
+    form.validate().err().map(|e| {
+        e.field_errors().values()
+            .flat_map(|errors| errors.iter())
+            .find_map(|error| error.message.clone())
+            .map(|message| message.to_string())
+            .unwrap_or_else(|| fallback.to_string())
+    })
+}
+
+/// Lets the user change their password and email, or delete their account
+#[function_component(AccountSettings)]
+pub fn account_settings(props: &AccountSettingsProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let navigator = use_navigator().unwrap();
+    let password_form = use_state(ChangePasswordSchema::default);
+    let email_form = use_state(ChangeEmailSchema::default);
+    let delete_form = use_state(DeleteAccountSchema::default);
+
+    let handle_password_input = |name: &'static str| {
+        let cloned_form = password_form.clone();
+        Callback::from(move |value: String| {
+            let mut data = (*cloned_form).clone();
+            match name {
+                "current_password" => data.current_password = value,
+                "password" => data.password = value,
+                "password_confirm" => data.password_confirm = value,
+                _ => (),
+            }
+            cloned_form.set(data);
+        })
+    };
+
+    let handle_email_input = |name: &'static str| {
+        let cloned_form = email_form.clone();
+        Callback::from(move |value: String| {
+            let mut data = (*cloned_form).clone();
+            match name {
+                "email" => data.email = value,
+                "password" => data.password = value,
+                _ => (),
+            }
+            cloned_form.set(data);
+        })
+    };
+
+    let handle_delete_input = |name: &'static str| {
+        let cloned_form = delete_form.clone();
+        Callback::from(move |value: String| {
+            let mut data = (*cloned_form).clone();
+            match name {
+                "password" => data.password = value,
+                "code" => data.code = Some(value.trim().to_string()).filter(|code| !code.is_empty()),
+                _ => (),
+            }
+            cloned_form.set(data);
+        })
+    };
+
+    let on_change_password = {
+        let cloned_form = password_form.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let form = cloned_form.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                if let Some(message) = first_error_message(&*form, "Invalid password") {
+                    set_show_alert(message, dispatch);
+                    return;
+                }
+
+                match client().change_password(&form).await {
+                    Ok(_) => set_show_alert("Password changed successfully".to_string(), dispatch),
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    let on_change_email = {
+        let cloned_form = email_form.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let form = cloned_form.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                if let Some(message) = first_error_message(&*form, "Invalid email") {
+                    set_show_alert(message, dispatch);
+                    return;
+                }
+
+                match client().change_email(&form).await {
+                    Ok(_) => {
+                        if let Ok(user) = client().user_info().await {
+                            set_auth_user(Some(user), dispatch.clone());
+                        }
+                        set_show_alert("Check your new email to confirm the change".to_string(), dispatch);
+                    }
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    let on_delete_account = {
+        let cloned_form = delete_form.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let confirmed = web_sys::window()
+                .and_then(|window| window.confirm_with_message("Delete your account? This can't be undone.").ok())
+                .unwrap_or(false);
+            if !confirmed {
+                return;
+            }
+
+            let form = cloned_form.clone();
+            let dispatch = store_dispatch.clone();
+            let navigator = navigator.clone();
+            spawn_local(async move {
+                if let Some(message) = first_error_message(&*form, "Invalid password") {
+                    set_show_alert(message, dispatch);
+                    return;
+                }
+
+                match client().delete_account(&form).await {
+                    Ok(_) => {
+                        set_auth_user(None, dispatch.clone());
+                        set_show_alert("Your account has been deleted".to_string(), dispatch);
+                        navigator.push(&Route::HomePage);
+                    }
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <>
+            <form onsubmit={on_change_password} class="mt-8 space-y-4">
+                <p class="text-2xl font-semibold">{"Change Password"}</p>
+                <Input label="Current Password" name="current_password" input_type="password" handle_onchange={handle_password_input("current_password")} />
+                <Input label="New Password" name="password" input_type="password" handle_onchange={handle_password_input("password")} />
+                <Input label="Confirm New Password" name="password_confirm" input_type="password" handle_onchange={handle_password_input("password_confirm")} />
+                <Button btn_type={"submit"} class="px-4 py-2">{"Change Password"}</Button>
+            </form>
+
+            <form onsubmit={on_change_email} class="mt-8 space-y-4">
+                <p class="text-2xl font-semibold">{"Change Email"}</p>
+                if let Some(pending_email) = props.pending_email.clone() {
+                    <p>{format!("Waiting for you to confirm {} from the link we sent to it.", pending_email)}</p>
+                }
+                <Input label="New Email" name="email" input_type="email" handle_onchange={handle_email_input("email")} />
+                <Input label="Password" name="password" input_type="password" handle_onchange={handle_email_input("password")} />
+                <Button btn_type={"submit"} class="px-4 py-2">{"Change Email"}</Button>
+            </form>
+
+            <form onsubmit={on_delete_account} class="mt-8 space-y-4">
+                <p class="text-2xl font-semibold">{"Delete Account"}</p>
+                <p>{"Deleting your account removes your profile, preferences and recommendations for good."}</p>
+                <Input label="Password" name="password" input_type="password" handle_onchange={handle_delete_input("password")} />
+                if props.two_factor_enabled {
+                    <Input label="Code" name="code" handle_onchange={handle_delete_input("code")} />
+                }
+                <Button btn_type={"submit"} class="px-4 py-2">{"Delete Account"}</Button>
+            </form>
+        </>
+    }
+}
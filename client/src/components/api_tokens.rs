@@ -0,0 +1,195 @@
+use crate::api::client;
+use crate::components::ui::{button::Button, input::Input};
+use crate::store::{set_show_alert, Store};
+use common::schema::api_token::{ApiToken, ApiTokenScope, CreateApiTokenSchema};
+
+use validator::Validate;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+/// Lets the user create, list and revoke personal API tokens
+#[function_component(ApiTokens)]
+pub fn api_tokens() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let tokens = use_state(Vec::<ApiToken>::new);
+    let created_token = use_state(|| None::<String>);
+    let form = use_state(|| CreateApiTokenSchema {
+        scopes: vec![ApiTokenScope::Read],
+        expires_in_days: Some(90),
+        ..Default::default()
+    });
+    let name_input_ref = NodeRef::default();
+
+    {
+        let tokens = tokens.clone();
+        let dispatch = dispatch.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                match client().list_api_tokens().await {
+                    Ok(data) => tokens.set(data),
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        });
+    }
+
+    let handle_name_input = {
+        let cloned_form = form.clone();
+        Callback::from(move |value: String| {
+            let mut data = (*cloned_form).clone();
+            data.name = value;
+            cloned_form.set(data);
+        })
+    };
+
+    let toggle_scope = |scope: ApiTokenScope| {
+        let cloned_form = form.clone();
+        Callback::from(move |event: Event| {
+            let checked = event.target().unwrap().unchecked_into::<HtmlInputElement>().checked();
+            let mut data = (*cloned_form).clone();
+            data.scopes.retain(|s| s != &scope);
+            if checked {
+                data.scopes.push(scope);
+            }
+            cloned_form.set(data);
+        })
+    };
+
+    let handle_expiry_change = {
+        let cloned_form = form.clone();
+        Callback::from(move |event: Event| {
+            let value = event.target().unwrap().unchecked_into::<HtmlSelectElement>().value();
+            let mut data = (*cloned_form).clone();
+            data.expires_in_days = value.parse::<i64>().ok();
+            cloned_form.set(data);
+        })
+    };
+
+    let on_create = {
+        let cloned_form = form.clone();
+        let cloned_tokens = tokens.clone();
+        let cloned_created_token = created_token.clone();
+        let cloned_name_input_ref = name_input_ref.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let form = cloned_form.clone();
+            let tokens = cloned_tokens.clone();
+            let created_token = cloned_created_token.clone();
+            let name_input_ref = cloned_name_input_ref.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                if let Err(e) = form.validate() {
+                    let message = e.field_errors().values()
+                        .flat_map(|errors| errors.iter())
+                        .find_map(|error| error.message.clone())
+                        .map(|message| message.to_string())
+                        .unwrap_or_else(|| "Invalid token details".to_string());
+                    set_show_alert(message, dispatch);
+                    return;
+                }
+
+                match client().create_api_token(&form).await {
+                    Ok(data) => {
+                        let mut list = (*tokens).clone();
+                        list.insert(0, data.api_token);
+                        tokens.set(list);
+                        created_token.set(Some(data.token));
+
+                        if let Some(name_input) = name_input_ref.cast::<HtmlInputElement>() {
+                            name_input.set_value("");
+                        }
+                    }
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    let on_revoke = |token_id: Uuid| {
+        let cloned_tokens = tokens.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let tokens = cloned_tokens.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                match client().revoke_api_token(token_id).await {
+                    Ok(_) => {
+                        let mut list = (*tokens).clone();
+                        list.retain(|token| token.id != token_id);
+                        tokens.set(list);
+                    }
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    let expiry_value = form.expires_in_days.map(|days| days.to_string()).unwrap_or_default();
+
+    html! {
+        <div class="mt-8 space-y-4">
+            <p class="text-2xl font-semibold">{"API Tokens"}</p>
+            <p>{"Use a token in the Authorization header to script against the API: "}<code>{"Authorization: Bearer <token>"}</code></p>
+
+            if let Some(token) = (*created_token).clone() {
+                <div>
+                    <p class="mb-2">{"Copy your new token now, it won't be shown again."}</p>
+                    <p class="font-mono break-all">{token}</p>
+                </div>
+            }
+
+            <form onsubmit={on_create} class="space-y-4">
+                <Input label="Name" name="name" input_ref={name_input_ref} handle_onchange={handle_name_input} />
+                <div class="flex gap-4">
+                    <label>
+                        <input type="checkbox" checked={form.scopes.contains(&ApiTokenScope::Read)} onchange={toggle_scope(ApiTokenScope::Read)} />
+                        {" Read"}
+                    </label>
+                    <label>
+                        <input type="checkbox" checked={form.scopes.contains(&ApiTokenScope::Write)} onchange={toggle_scope(ApiTokenScope::Write)} />
+                        {" Write"}
+                    </label>
+                </div>
+                <select class="block px-4 py-2 border rounded-sm" onchange={handle_expiry_change}>
+                    <option value="30" selected={expiry_value == "30"}>{"Expires in 30 days"}</option>
+                    <option value="90" selected={expiry_value == "90"}>{"Expires in 90 days"}</option>
+                    <option value="365" selected={expiry_value == "365"}>{"Expires in 1 year"}</option>
+                    <option value="" selected={expiry_value.is_empty()}>{"Never expires"}</option>
+                </select>
+                <Button btn_type={"submit"} class="px-4 py-2">{"Create Token"}</Button>
+            </form>
+
+            <ul class="space-y-2">
+                { for tokens.iter().map(|token| {
+                    let date_format = "%b %e, %Y";
+                    let last_used = token.last_used_at.map(|date| date.format(date_format).to_string());
+                    let expires = token.expires_at.map(|date| date.format(date_format).to_string());
+
+                    html! {
+                        <li class="flex items-center justify-between gap-4">
+                            <div>
+                                <p class="font-semibold">{format!("{} ({}…)", token.name, token.prefix)}</p>
+                                <p class="text-sm">
+                                    {format!(
+                                        "{} · Created {} · Last used {} · Expires {}",
+                                        token.scopes.iter().map(|scope| scope.to_string()).collect::<Vec<_>>().join(", "),
+                                        token.created_at.format(date_format),
+                                        last_used.unwrap_or_else(|| "never".to_string()),
+                                        expires.unwrap_or_else(|| "never".to_string()),
+                                    )}
+                                </p>
+                            </div>
+                            <Button class="px-4 py-2" onclick={on_revoke(token.id)}>{"Revoke"}</Button>
+                        </li>
+                    }
+                }) }
+            </ul>
+        </div>
+    }
+}
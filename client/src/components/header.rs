@@ -1,7 +1,7 @@
 use crate::{
     router::{self, Route}, 
     store::{set_loading, set_show_alert, Store, set_auth_user},
-    api::user_api::{api_logout_user},
+    api::client,
     components::ui::search::Search,
 };
 use wasm_bindgen_futures::spawn_local;
@@ -36,7 +36,7 @@ pub fn header_component(props: &HeaderProps) -> Html {
             let navigator = cloned_navigator.clone();
             spawn_local(async move {
                 set_loading(true, dispatch.clone());
-                let result = api_logout_user().await;
+                let result = client().logout().await;
                 match result {
                     Ok(_) => {
                         set_loading(false, dispatch.clone());
@@ -72,6 +72,12 @@ pub fn header_component(props: &HeaderProps) -> Html {
                     </li>
                     if user.is_some() {
                         <>
+                            <li>
+                                <Link<Route> to={Route::FeedPage} classes="font-bold">{"Feed"}</Link<Route>>
+                            </li>
+                            <li>
+                                <Link<Route> to={Route::RecommendationsPage} classes="font-bold">{"For you"}</Link<Route>>
+                            </li>
                             <li>
                                 <Link<Route> to={Route::ProfilePage} classes="font-bold">{"Profile"}</Link<Route>>
                             </li>
@@ -2,4 +2,11 @@ pub mod alert;
 pub mod recommendation_list;
 pub mod ui;
 pub mod header;
-pub mod song_card;
\ No newline at end of file
+pub mod song_card;
+pub mod two_factor_settings;
+pub mod api_tokens;
+pub mod profile_settings;
+pub mod account_settings;
+pub mod privacy_settings;
+pub mod player;
+pub mod song_list;
\ No newline at end of file
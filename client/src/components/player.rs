@@ -0,0 +1,316 @@
+use crate::store::{
+    cycle_repeat, jump_to, next_song, previous_song, remove_from_queue, set_playing, set_show_alert, set_volume,
+    toggle_shuffle, Player as PlayerStore, RepeatMode, Store
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{DomException, HtmlAudioElement, HtmlInputElement};
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+/// Going back this far into a song restarts it rather than going to the previous one, in seconds.
+const RESTART_THRESHOLD: f64 = 3.0;
+
+/// `m:ss`
+pub fn format_time(seconds: f64) -> String {
+    let seconds = if seconds.is_finite() { seconds.max(0.0) as u64 } else { 0 };
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// The value of the range input an event came from.
+fn range_value(event: &InputEvent) -> Option<f64> {
+    event.target_dyn_into::<HtmlInputElement>()?.value().parse().ok()
+}
+
+/// The audio player bar, rendered once by `App` outside the router so playback survives navigation.
+/// What plays is kept in the `Player` store, this only drives the `<audio>` element to match it.
+#[function_component(Player)]
+pub fn player_component() -> Html {
+    let (player, dispatch) = use_store::<PlayerStore>();
+    let (_, store_dispatch) = use_store::<Store>();
+    let audio_ref = use_node_ref();
+    let current_time = use_state(|| 0.0);
+    let duration = use_state(|| 0.0);
+    let show_queue = use_state(|| false);
+    let current = player.current().cloned();
+
+    // Play or pause whenever the store asks to, including when the song changes
+    {
+        let audio_ref = audio_ref.clone();
+        let dispatch = dispatch.clone();
+
+        use_effect_with((player.playing, current.as_ref().map(|song| song.url.clone())), move |(playing, url)| {
+            if let Some(audio) = audio_ref.cast::<HtmlAudioElement>() {
+                if *playing && url.is_some() {
+                    if let Ok(promise) = audio.play() {
+                        spawn_local(async move {
+                            // Browsers refuse to play before the user interacted with the page, e.g. after a reload.
+                            // Other rejections come from changing songs before the last one started, and are harmless.
+                            if let Err(e) = JsFuture::from(promise).await {
+                                if e.dyn_into::<DomException>().is_ok_and(|e| e.name() == "NotAllowedError") {
+                                    set_playing(false, dispatch);
+                                }
+                            }
+                        });
+                    }
+                } else {
+                    let _ = audio.pause();
+                }
+            }
+        });
+    }
+
+    {
+        let audio_ref = audio_ref.clone();
+
+        use_effect_with(player.volume, move |volume| {
+            if let Some(audio) = audio_ref.cast::<HtmlAudioElement>() {
+                audio.set_volume(*volume);
+            }
+        });
+    }
+
+    let handle_time_update = {
+        let audio_ref = audio_ref.clone();
+        let current_time = current_time.clone();
+
+        Callback::from(move |_: Event| {
+            if let Some(audio) = audio_ref.cast::<HtmlAudioElement>() {
+                current_time.set(audio.current_time());
+            }
+        })
+    };
+
+    let handle_duration_change = {
+        let audio_ref = audio_ref.clone();
+        let duration = duration.clone();
+
+        Callback::from(move |_: Event| {
+            if let Some(audio) = audio_ref.cast::<HtmlAudioElement>() {
+                duration.set(audio.duration());
+            }
+        })
+    };
+
+    let handle_ended = {
+        let audio_ref = audio_ref.clone();
+        let dispatch = dispatch.clone();
+        let repeat = player.repeat;
+
+        Callback::from(move |_: Event| {
+            match (repeat, audio_ref.cast::<HtmlAudioElement>()) {
+                (RepeatMode::One, Some(audio)) => {
+                    audio.set_current_time(0.0);
+                    let _ = audio.play();
+                }
+                _ => next_song(dispatch.clone()),
+            }
+        })
+    };
+
+    // Keep the store in step when playback is paused or resumed from outside, e.g. with the keyboard's media keys
+    let handle_play_event = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: Event| set_playing(true, dispatch.clone()))
+    };
+
+    let handle_pause_event = {
+        let audio_ref = audio_ref.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |_: Event| {
+            // A song that finished pauses first, `handle_ended` decides what happens next
+            if audio_ref.cast::<HtmlAudioElement>().is_some_and(|audio| !audio.ended()) {
+                set_playing(false, dispatch.clone());
+            }
+        })
+    };
+
+    let handle_error = {
+        let dispatch = dispatch.clone();
+        let store_dispatch = store_dispatch.clone();
+        let title = current.as_ref().map(|song| song.title.clone()).unwrap_or_default();
+
+        Callback::from(move |_: Event| {
+            set_playing(false, dispatch.clone());
+            set_show_alert(format!("Couldn't play {}", title), store_dispatch.clone());
+        })
+    };
+
+    let handle_toggle_play = {
+        let dispatch = dispatch.clone();
+        let playing = player.playing;
+        let has_current = current.is_some();
+
+        Callback::from(move |_: MouseEvent| {
+            if has_current {
+                set_playing(!playing, dispatch.clone());
+            } else {
+                // Nothing played yet, start the queue
+                jump_to(0, dispatch.clone());
+            }
+        })
+    };
+
+    let handle_previous = {
+        let audio_ref = audio_ref.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            match audio_ref.cast::<HtmlAudioElement>() {
+                Some(audio) if audio.current_time() > RESTART_THRESHOLD => audio.set_current_time(0.0),
+                _ => previous_song(dispatch.clone()),
+            }
+        })
+    };
+
+    let handle_next = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| next_song(dispatch.clone()))
+    };
+
+    let handle_seek = {
+        let audio_ref = audio_ref.clone();
+        let current_time = current_time.clone();
+
+        Callback::from(move |event: InputEvent| {
+            if let (Some(time), Some(audio)) = (range_value(&event), audio_ref.cast::<HtmlAudioElement>()) {
+                audio.set_current_time(time);
+                current_time.set(time);
+            }
+        })
+    };
+
+    let handle_volume = {
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |event: InputEvent| {
+            if let Some(volume) = range_value(&event) {
+                set_volume(volume, dispatch.clone());
+            }
+        })
+    };
+
+    let handle_shuffle = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| toggle_shuffle(dispatch.clone()))
+    };
+
+    let handle_repeat = {
+        let dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| cycle_repeat(dispatch.clone()))
+    };
+
+    let handle_toggle_queue = {
+        let show_queue = show_queue.clone();
+        Callback::from(move |_: MouseEvent| show_queue.set(!*show_queue))
+    };
+
+    if player.queue.is_empty() {
+        return html! {};
+    }
+
+    let control_class = "px-2 py-1 rounded-md hover:bg-primary/60";
+    let active_class = |active: bool| classes!(control_class, active.then_some("text-primary"));
+    let (repeat_label, repeat_icon) = match player.repeat {
+        RepeatMode::Off => ("Repeat: off", "🔁"),
+        RepeatMode::All => ("Repeat: all", "🔁"),
+        RepeatMode::One => ("Repeat: one", "🔂"),
+    };
+
+    html! {
+        <div class="fixed bottom-0 left-0 right-0 z-10 border-t border-black shadow-md bg-background">
+            <audio
+                ref={audio_ref}
+                src={current.as_ref().map(|song| song.url.clone())}
+                preload="metadata"
+                ontimeupdate={handle_time_update}
+                ondurationchange={handle_duration_change}
+                onended={handle_ended}
+                onplay={handle_play_event}
+                onpause={handle_pause_event}
+                onerror={handle_error}
+            />
+
+            if *show_queue {
+                <ol class="container overflow-y-auto max-h-64">
+                    {for player.upcoming().map(|(position, song)| {
+                        let handle_jump = {
+                            let dispatch = dispatch.clone();
+                            Callback::from(move |_: MouseEvent| jump_to(position, dispatch.clone()))
+                        };
+                        let handle_remove = {
+                            let dispatch = dispatch.clone();
+                            Callback::from(move |_: MouseEvent| remove_from_queue(position, dispatch.clone()))
+                        };
+                        let is_current = player.position == Some(position);
+
+                        html! {
+                            <li class="flex items-center justify-between gap-2 py-1">
+                                <button class={classes!("text-left", "truncate", is_current.then_some("font-semibold"))} onclick={handle_jump}>
+                                    {format!("{} · {}", song.title, song.artist)}
+                                </button>
+                                <button class={control_class} aria-label="Remove from queue" onclick={handle_remove}>{"✕"}</button>
+                            </li>
+                        }
+                    })}
+                </ol>
+            }
+
+            <div class="container flex items-center gap-4 py-2">
+                if let Some(song) = &current {
+                    <div class="flex items-center min-w-0 gap-2 basis-1/4">
+                        <img src={song.cover.clone()} class="w-12 h-12 rounded-md" />
+                        <div class="min-w-0">
+                            <p class="font-semibold truncate">{&song.title}</p>
+                            <p class="text-sm truncate text-secondary">{&song.artist}</p>
+                        </div>
+                    </div>
+                } else {
+                    <p class="basis-1/4 text-secondary">{format!("{} songs queued", player.queue.len())}</p>
+                }
+
+                <div class="flex flex-col items-center flex-1 gap-1">
+                    <div class="flex items-center gap-2">
+                        <button class={active_class(player.shuffle)} aria-label="Shuffle" aria-pressed={player.shuffle.to_string()} onclick={handle_shuffle}>{"🔀"}</button>
+                        <button class={control_class} aria-label="Previous" onclick={handle_previous}>{"⏮"}</button>
+                        <button class={control_class} aria-label={if player.playing { "Pause" } else { "Play" }} onclick={handle_toggle_play}>
+                            {if player.playing { "⏸" } else { "▶" }}
+                        </button>
+                        <button class={control_class} aria-label="Next" onclick={handle_next}>{"⏭"}</button>
+                        <button class={active_class(player.repeat != RepeatMode::Off)} aria-label={repeat_label} title={repeat_label} onclick={handle_repeat}>{repeat_icon}</button>
+                    </div>
+                    <div class="flex items-center w-full gap-2 text-sm">
+                        <span>{format_time(*current_time)}</span>
+                        <input
+                            type="range"
+                            class="flex-1"
+                            aria-label="Seek"
+                            min="0"
+                            max={if duration.is_finite() { duration.to_string() } else { "0".to_string() }}
+                            step="any"
+                            value={current_time.to_string()}
+                            disabled={current.is_none()}
+                            oninput={handle_seek}
+                        />
+                        <span>{format_time(*duration)}</span>
+                    </div>
+                </div>
+
+                <div class="flex items-center gap-2 basis-1/4">
+                    <input
+                        type="range"
+                        class="flex-1"
+                        aria-label="Volume"
+                        min="0"
+                        max="1"
+                        step="0.01"
+                        value={player.volume.to_string()}
+                        oninput={handle_volume}
+                    />
+                    <button class={active_class(*show_queue)} aria-label="Queue" onclick={handle_toggle_queue}>{"☰"}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
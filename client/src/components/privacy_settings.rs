@@ -0,0 +1,127 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::api::client;
+use crate::components::ui::{button::Button, select::Select};
+use crate::store::{set_auth_user, set_loading, set_show_alert, Store};
+use common::schema::privacy::{PrivacySettings as Privacy, UpdatePrivacySchema};
+use common::schema::social::{get_visibility_select_items, Visibility};
+
+use validator::ValidationErrors;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct PrivacySettingsProps {
+    /// The logged in user's current settings
+    pub privacy: Privacy,
+}
+
+fn get_visibility_callback(name: &'static str, cloned_form: UseStateHandle<Privacy>) -> Callback<String> {
+    Callback::from(move |value: String| {
+        let mut data = *cloned_form;
+        match name {
+            "history_visibility" => data.history_visibility = Visibility::from(value),
+            "profile_visibility" => data.profile_visibility = Visibility::from(value),
+            _ => (),
+        }
+        cloned_form.set(data);
+    })
+}
+
+/// Lets the user choose who sees their profile and listening history, and download their data
+#[function_component(PrivacySettings)]
+pub fn privacy_settings(props: &PrivacySettingsProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let form = use_state(|| props.privacy);
+    // Both selects always hold a valid value, so there is never anything to show here
+    let validation_errors = use_state(|| Rc::new(RefCell::new(ValidationErrors::new())));
+
+    {
+        let form = form.clone();
+        use_effect_with(props.privacy, move |privacy| form.set(*privacy));
+    }
+
+    let handle_history_visibility = get_visibility_callback("history_visibility", form.clone());
+    let handle_profile_visibility = get_visibility_callback("profile_visibility", form.clone());
+
+    let toggle_social_recommendations = {
+        let cloned_form = form.clone();
+        Callback::from(move |event: Event| {
+            let checked = event.target().unwrap().unchecked_into::<HtmlInputElement>().checked();
+            let mut data = *cloned_form;
+            data.allow_social_recommendations = checked;
+            cloned_form.set(data);
+        })
+    };
+
+    let on_submit = {
+        let cloned_form = form.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let form = *cloned_form;
+            let dispatch = dispatch.clone();
+            spawn_local(async move {
+                let payload = UpdatePrivacySchema {
+                    history_visibility: Some(form.history_visibility),
+                    profile_visibility: Some(form.profile_visibility),
+                    allow_social_recommendations: Some(form.allow_social_recommendations),
+                };
+
+                set_loading(true, dispatch.clone());
+                match client().update_privacy(&payload).await {
+                    Ok(user) => {
+                        set_loading(false, dispatch.clone());
+                        set_auth_user(Some(user), dispatch.clone());
+                        set_show_alert("Privacy settings updated".to_string(), dispatch);
+                    }
+                    Err(e) => {
+                        set_loading(false, dispatch.clone());
+                        set_show_alert(e.to_string(), dispatch);
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <form onsubmit={on_submit} class="mt-8 space-y-4">
+            <p class="text-2xl font-semibold">{"Privacy"}</p>
+            <Select
+                label="Who can see my profile"
+                name="profile_visibility"
+                value={form.profile_visibility.as_str()}
+                input_ref={NodeRef::default()}
+                handle_onchange={handle_profile_visibility}
+                handle_on_input_blur={Callback::noop()}
+                errors={&*validation_errors}
+                items={get_visibility_select_items()}
+            />
+            <Select
+                label="Who can see what I listen to and like"
+                name="history_visibility"
+                value={form.history_visibility.as_str()}
+                input_ref={NodeRef::default()}
+                handle_onchange={handle_history_visibility}
+                handle_on_input_blur={Callback::noop()}
+                errors={&*validation_errors}
+                items={get_visibility_select_items()}
+            />
+            <p class="text-sm text-secondary">
+                {"Anyone can follow you without your approval, so “Followers” only hides your activity from people who haven't followed you. Choose “Only me” to keep it private."}
+            </p>
+            <label class="block">
+                <input type="checkbox" checked={form.allow_social_recommendations} onchange={toggle_social_recommendations} />
+                {" Use what people I follow listen to in my recommendations"}
+            </label>
+            <Button btn_type={"submit"} class="px-4 py-2">{"Save Privacy Settings"}</Button>
+            <p>
+                {"Download everything we store about you as a ZIP of JSON files. "}
+                <a href={client().export_url()} download="" class="text-info hover:underline">{"Export my data"}</a>
+            </p>
+        </form>
+    }
+}
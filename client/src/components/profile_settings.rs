@@ -0,0 +1,254 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::api::client;
+use crate::components::ui::{button::Button, input::Input, select::Select};
+use crate::router::Route;
+use crate::store::{set_auth_user, set_loading, set_show_alert, Store};
+use common::schema::error::ErrorCode;
+use common::schema::platform::get_platform_select_items;
+use common::schema::user::{FilteredUser as User, UpdateProfileSchema};
+
+use js_sys::Uint8Array;
+use validator::{Validate, ValidationErrors};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+/// The fields of the form, which server validation errors are shown for
+const FORM_FIELDS: &[&str] = &["name", "username", "photo", "preferred_platform"];
+
+#[derive(Properties, PartialEq)]
+pub struct ProfileSettingsProps {
+    /// The logged in user, used to fill in the form
+    pub user: User,
+}
+
+fn profile_form(user: &User) -> UpdateProfileSchema {
+    UpdateProfileSchema {
+        name: Some(user.name.clone()),
+        username: Some(user.username.clone()),
+        // Users without a photo have an empty one, which isn't a URL
+        photo: Some(user.photo.clone()).filter(|photo| !photo.is_empty()),
+        preferred_platform: Some(user.preferred_platform.clone()),
+        show_top_artists: Some(user.show_top_artists),
+    }
+}
+
+fn get_input_callback(name: &'static str, cloned_form: UseStateHandle<UpdateProfileSchema>) -> Callback<String> {
+    Callback::from(move |value: String| {
+        let mut data = (*cloned_form).clone();
+        match name {
+            "name" => data.name = Some(value),
+            "username" => data.username = Some(value),
+            "photo" => data.photo = Some(value).filter(|photo| !photo.is_empty()),
+            "preferred_platform" => data.preferred_platform = serde_json::from_value(serde_json::Value::String(value)).ok(),
+            _ => (),
+        }
+        cloned_form.set(data);
+    })
+}
+
+/// Lets the user edit their name, username, photo and preferred platform
+#[function_component(ProfileSettings)]
+pub fn profile_settings(props: &ProfileSettingsProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let form = use_state(|| profile_form(&props.user));
+    let validation_errors = use_state(|| Rc::new(RefCell::new(ValidationErrors::new())));
+
+    let name_input_ref = NodeRef::default();
+    let username_input_ref = NodeRef::default();
+    let photo_input_ref = NodeRef::default();
+    let preferred_platform_input_ref = NodeRef::default();
+
+    // The inputs aren't controlled, so fill them in with the current profile
+    {
+        let form = form.clone();
+        let name_input_ref = name_input_ref.clone();
+        let username_input_ref = username_input_ref.clone();
+        let photo_input_ref = photo_input_ref.clone();
+        use_effect_with(props.user.clone(), move |user| {
+            let data = profile_form(user);
+            for (input_ref, value) in [
+                (&name_input_ref, &data.name),
+                (&username_input_ref, &data.username),
+                (&photo_input_ref, &data.photo),
+            ] {
+                if let Some(input) = input_ref.cast::<HtmlInputElement>() {
+                    input.set_value(value.as_deref().unwrap_or_default());
+                }
+            }
+            form.set(data);
+        });
+    }
+
+    let handle_name_input = get_input_callback("name", form.clone());
+    let handle_username_input = get_input_callback("username", form.clone());
+    let handle_photo_input = get_input_callback("photo", form.clone());
+    let handle_preferred_platform_input = get_input_callback("preferred_platform", form.clone());
+
+    let validate_input_on_blur = {
+        let cloned_form = form.clone();
+        let cloned_validation_errors = validation_errors.clone();
+        Callback::from(move |(name, _value): (String, String)| {
+            let validation_errors = cloned_validation_errors.clone();
+            match cloned_form.validate() {
+                Ok(_) => {
+                    validation_errors.borrow_mut().errors_mut().remove(name.as_str());
+                }
+                Err(errors) => {
+                    validation_errors.borrow_mut().errors_mut().remove(name.as_str());
+                    for (field_name, error) in errors.errors() {
+                        if field_name == &name {
+                            validation_errors
+                                .borrow_mut()
+                                .errors_mut()
+                                .insert(field_name, error.clone());
+                        }
+                    }
+                }
+            }
+        })
+    };
+
+    let on_avatar_change = {
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |event: Event| {
+            let input = event.target().unwrap().unchecked_into::<HtmlInputElement>();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // Clear the input so picking the same file again still triggers a change
+            input.set_value("");
+
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                set_loading(true, dispatch.clone());
+                let bytes = match JsFuture::from(file.array_buffer()).await {
+                    Ok(buffer) => Uint8Array::new(&buffer).to_vec(),
+                    Err(_) => {
+                        set_loading(false, dispatch.clone());
+                        set_show_alert("Failed to read the image".to_string(), dispatch);
+                        return;
+                    }
+                };
+
+                match client().upload_avatar(&file.name(), &file.type_(), bytes).await {
+                    Ok(user) => {
+                        set_loading(false, dispatch.clone());
+                        set_auth_user(Some(user), dispatch.clone());
+                        set_show_alert("Avatar updated".to_string(), dispatch);
+                    }
+                    Err(e) => {
+                        set_loading(false, dispatch.clone());
+                        set_show_alert(e.to_string(), dispatch);
+                    }
+                }
+            });
+        })
+    };
+
+    let toggle_show_top_artists = {
+        let cloned_form = form.clone();
+        Callback::from(move |event: Event| {
+            let checked = event.target().unwrap().unchecked_into::<HtmlInputElement>().checked();
+            let mut data = (*cloned_form).clone();
+            data.show_top_artists = Some(checked);
+            cloned_form.set(data);
+        })
+    };
+
+    let on_submit = {
+        let cloned_form = form.clone();
+        let cloned_validation_errors = validation_errors.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let form = cloned_form.clone();
+            let validation_errors = cloned_validation_errors.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                if let Err(e) = form.validate() {
+                    validation_errors.set(Rc::new(RefCell::new(e)));
+                    return;
+                }
+
+                set_loading(true, dispatch.clone());
+                match client().update_profile(&form).await {
+                    Ok(user) => {
+                        set_loading(false, dispatch.clone());
+                        set_auth_user(Some(user), dispatch.clone());
+                        set_show_alert("Profile updated".to_string(), dispatch);
+                    }
+                    Err(e) => {
+                        set_loading(false, dispatch.clone());
+                        if e.code == ErrorCode::ValidationFailed {
+                            validation_errors.set(Rc::new(RefCell::new(e.validation_errors(FORM_FIELDS))));
+                        }
+                        set_show_alert(e.to_string(), dispatch);
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <form onsubmit={on_submit} class="mt-8 space-y-4">
+            <p class="text-2xl font-semibold">{"Profile"}</p>
+            <div class="flex items-center gap-4">
+                if !props.user.photo.is_empty() {
+                    <img src={props.user.photo.clone()} alt="Avatar" class="w-16 h-16 rounded-full" />
+                }
+                <label class="cursor-pointer text-info hover:underline">
+                    {"Upload Avatar"}
+                    <input type="file" accept="image/png,image/jpeg,image/webp,image/gif" class="hidden" onchange={on_avatar_change} />
+                </label>
+            </div>
+            <Input
+                label="Name"
+                name="name"
+                input_ref={name_input_ref}
+                handle_onchange={handle_name_input}
+                handle_on_input_blur={validate_input_on_blur.clone()}
+                errors={&*validation_errors}
+            />
+            <Input
+                label="Username"
+                name="username"
+                input_ref={username_input_ref}
+                handle_onchange={handle_username_input}
+                handle_on_input_blur={validate_input_on_blur.clone()}
+                errors={&*validation_errors}
+            />
+            <Input
+                label="Photo URL"
+                name="photo"
+                input_ref={photo_input_ref}
+                handle_onchange={handle_photo_input}
+                handle_on_input_blur={validate_input_on_blur.clone()}
+                errors={&*validation_errors}
+            />
+            <Select
+                label="Preferred Platform"
+                name="preferred_platform"
+                value={form.preferred_platform.as_ref().map(|platform| platform.as_str().to_string()).unwrap_or_default()}
+                input_ref={preferred_platform_input_ref}
+                handle_onchange={handle_preferred_platform_input}
+                handle_on_input_blur={validate_input_on_blur}
+                errors={&*validation_errors}
+                items={get_platform_select_items()}
+            />
+            <label class="block">
+                <input type="checkbox" checked={form.show_top_artists.unwrap_or_default()} onchange={toggle_show_top_artists} />
+                {" Show my top artists on my public profile"}
+            </label>
+            <Link<Route> to={Route::PublicProfilePage { username: props.user.username.clone() }} classes="block text-info hover:underline">
+                {"View public profile"}
+            </Link<Route>>
+            <Button btn_type={"submit"} class="px-4 py-2">{"Save Profile"}</Button>
+        </form>
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use crate::{
+    api::client,
+    components::song_list::playable_song,
+    router::Route,
+    store::{enqueue_song, play_song, set_show_alert, Player, Store},
+};
+use common::schema::recommendation::{Recommendation, RecommendationFeedback, RecommendationReason};
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct RecommendationListProps {
+    pub recommendations: Vec<Recommendation>,
+}
+
+/// Recommended songs with their match score and why they were picked, and buttons to tell the
+/// server what the user thinks of each. Songs given a thumbs down, hidden or skipped fold away
+/// with a way to undo it.
+#[function_component(RecommendationList)]
+pub fn recommendation_list(props: &RecommendationListProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let player_dispatch = use_dispatch::<Player>();
+    let logged_in = store.auth_user.is_some();
+    // Feedback given on this batch, by song
+    let feedback = use_state(HashMap::<Uuid, RecommendationFeedback>::new);
+
+    // A new batch starts without feedback
+    {
+        let feedback = feedback.clone();
+        use_effect_with(props.recommendations.clone(), move |_| feedback.set(HashMap::new()));
+    }
+
+    // Sends `given` for a song, or takes back what was sent when it's `None`
+    let send_feedback = {
+        let feedback = feedback.clone();
+        Callback::from(move |(song_id, given): (Uuid, Option<RecommendationFeedback>)| {
+            let previous = (*feedback).clone();
+            let mut updated = previous.clone();
+            match given {
+                Some(given) => updated.insert(song_id, given),
+                None => updated.remove(&song_id),
+            };
+            feedback.set(updated);
+
+            let feedback = feedback.clone();
+            let dispatch = dispatch.clone();
+            spawn_local(async move {
+                let response = match given {
+                    Some(given) => client().send_recommendation_feedback(song_id, given).await,
+                    None => client().clear_recommendation_feedback(song_id).await,
+                };
+
+                if let Err(e) = response {
+                    feedback.set(previous);
+                    set_show_alert(e.to_string(), dispatch);
+                }
+            });
+        })
+    };
+
+    html! {
+        <ol class="divide-y divide-secondary/30">
+            {for props.recommendations.iter().map(|recommendation| {
+                let song = &recommendation.song;
+                let song_id = song.song_id;
+                let given = feedback.get(&song_id).copied();
+                let feedback_button = |label: &'static str, icon: &'static str, kind: RecommendationFeedback| {
+                    let active = given == Some(kind);
+                    let send_feedback = send_feedback.clone();
+                    // Pressing the active button again takes the feedback back
+                    let onclick = Callback::from(move |_: MouseEvent| send_feedback.emit((song_id, (!active).then_some(kind))));
+
+                    html! {
+                        <button
+                            class={classes!("px-2", "py-1", "text-sm", "rounded-md", "hover:bg-primary/60", active.then_some("bg-primary"))}
+                            aria-label={label}
+                            aria-pressed={active.to_string()}
+                            title={label}
+                            {onclick}
+                        >
+                            {icon}
+                        </button>
+                    }
+                };
+
+                if let Some(folded @ (RecommendationFeedback::ThumbsDown | RecommendationFeedback::Hide | RecommendationFeedback::NotNow)) = given {
+                    let handle_undo = {
+                        let send_feedback = send_feedback.clone();
+                        Callback::from(move |_: MouseEvent| send_feedback.emit((song_id, None)))
+                    };
+
+                    return html! {
+                        <li key={song_id.to_string()} class="flex items-center justify-between gap-4 py-2 text-sm text-secondary">
+                            <span>{format!("{}: {}", folded, song.title)}</span>
+                            <button class="font-semibold text-info hover:underline" onclick={handle_undo}>{"Undo"}</button>
+                        </li>
+                    };
+                }
+
+                let to_play = playable_song(song, logged_in);
+                let handle_play = {
+                    let player_dispatch = player_dispatch.clone();
+                    let to_play = to_play.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        if let Some(song) = &to_play {
+                            play_song(song.clone(), player_dispatch.clone());
+                        }
+                    })
+                };
+                let handle_queue = {
+                    let player_dispatch = player_dispatch.clone();
+                    let to_play = to_play.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        if let Some(song) = &to_play {
+                            enqueue_song(song.clone(), player_dispatch.clone());
+                        }
+                    })
+                };
+
+                html! {
+                    <li key={song_id.to_string()} class="flex flex-wrap items-center gap-4 py-3">
+                        if let Some(cover) = &song.cover {
+                            <img src={cover.clone()} alt="" class="w-14 h-14 rounded-md" />
+                        }
+                        <div class="flex-1 min-w-0">
+                            <Link<Route> to={Route::SongPage { id: song_id }} classes="block font-semibold truncate hover:underline">
+                                {&song.title}
+                            </Link<Route>>
+                            <p class="text-sm truncate">
+                                <Link<Route> to={Route::ArtistPage { id: song.artist_id }} classes="hover:underline">{&song.artist}</Link<Route>>
+                            </p>
+                            <p class="text-sm text-secondary">
+                                <span class="font-semibold">{format!("{:.0}% match", recommendation.match_score * 100.0)}</span>
+                                {" · "}
+                                if let RecommendationReason::SimilarTo { song_id, title } = &recommendation.reason {
+                                    {"Because you like "}
+                                    <Link<Route> to={Route::SongPage { id: *song_id }} classes="hover:underline">{title}</Link<Route>>
+                                } else {
+                                    {recommendation.reason.to_string()}
+                                }
+                            </p>
+                        </div>
+                        <div class="flex items-center gap-1">
+                            <button
+                                class="px-3 py-1 text-sm font-semibold rounded-md bg-primary disabled:opacity-50"
+                                disabled={to_play.is_none()}
+                                onclick={handle_play}
+                            >
+                                {"Play"}
+                            </button>
+                            <button
+                                class="px-3 py-1 mr-2 text-sm font-semibold rounded-md bg-secondary disabled:opacity-50"
+                                disabled={to_play.is_none()}
+                                onclick={handle_queue}
+                            >
+                                {"Queue"}
+                            </button>
+                            {feedback_button("More like this", "👍", RecommendationFeedback::ThumbsUp)}
+                            {feedback_button("Fewer like this", "👎", RecommendationFeedback::ThumbsDown)}
+                            {feedback_button("Not now", "⏱", RecommendationFeedback::NotNow)}
+                            {feedback_button("Never recommend this", "🚫", RecommendationFeedback::Hide)}
+                        </div>
+                    </li>
+                }
+            })}
+        </ol>
+    }
+}
@@ -1,8 +1,7 @@
 use crate::{
     router::{self, Route}, 
-    store::{set_loading, set_show_alert, Store},
+    store::{enqueue_song, play_song, set_loading, set_show_alert, Player, Store},
 };
-use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yew_router::prelude::*;
 use yewdux::prelude::*;
@@ -17,32 +16,59 @@ pub struct SongProps {
 #[function_component(SongCard)]
 pub fn song_card(props: &SongProps) -> Html {
     let song = props.song.clone();
+    let player_dispatch = use_dispatch::<Player>();
 
     let handle_play = {
-        // let cloned_navigator = navigator.clone();
-        // let cloned_song = song.clone();
-
-        Callback::from(move |_: MouseEvent| {
-            let audio = web_sys::HtmlAudioElement::new_with_src(song.url.as_str()).unwrap();
-            spawn_local(async move {
-                audio.load();
-                let _ = audio.play().unwrap();
-            });
-        })
+        let song = song.clone();
+        let player_dispatch = player_dispatch.clone();
+
+        Callback::from(move |_: MouseEvent| play_song(song.clone(), player_dispatch.clone()))
+    };
+
+    let handle_queue = {
+        let song = song.clone();
+
+        Callback::from(move |_: MouseEvent| enqueue_song(song.clone(), player_dispatch.clone()))
     };
 
     html! {
         <div class="flex items-center flex-1 p-2 m-2 rounded-md shadow-md bg-background">
             <img src={song.cover.clone()} class="w-32 h-32 rounded-md" />
             <div class="flex flex-col items-center mt-2">
-                <h3 class="text-lg font-semibold">{&song.title}</h3>
-                <p class="text-sm text-secondary">{&song.artist}</p>
+                <h3 class="text-lg font-semibold">
+                    if let Some(id) = song.song_id {
+                        <Link<Route> to={Route::SongPage { id }} classes="hover:underline">{&song.title}</Link<Route>>
+                    } else {
+                        {&song.title}
+                    }
+                </h3>
+                <p class="text-sm text-secondary">
+                    if let Some(id) = song.artist_id {
+                        <Link<Route> to={Route::ArtistPage { id }} classes="hover:underline">{&song.artist}</Link<Route>>
+                    } else {
+                        {&song.artist}
+                    }
+                    if !song.album.is_empty() {
+                        {" · "}
+                        if let Some(id) = song.album_id {
+                            <Link<Route> to={Route::AlbumPage { id }} classes="hover:underline">{&song.album}</Link<Route>>
+                        } else {
+                            {&song.album}
+                        }
+                    }
+                </p>
                 <button
                     class="px-4 py-1 mt-2 text-sm font-semibold rounded-md bg-primary"
                     onclick={handle_play}
                 >
                     {"Play"}
                 </button>
+                <button
+                    class="px-4 py-1 mt-2 text-sm font-semibold rounded-md bg-secondary"
+                    onclick={handle_queue}
+                >
+                    {"Queue"}
+                </button>
             </div>
         </div>
     }
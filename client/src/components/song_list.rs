@@ -0,0 +1,124 @@
+use crate::{
+    api::client,
+    components::player::format_time,
+    router::Route,
+    store::{enqueue_song, play_queue, play_song, Player, Store},
+};
+use common::schema::song::{Song, SongSummary};
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+/// The song as the player takes it, or `None` if we don't host its audio.
+/// Logged out users get the preview, as the full song can only be streamed after logging in.
+pub fn playable_song(song: &SongSummary, logged_in: bool) -> Option<Song> {
+    if !song.has_audio {
+        return None;
+    }
+
+    let url = if logged_in {
+        client().song_stream_url(song.song_id)
+    } else {
+        client().song_preview_url(song.song_id)
+    };
+
+    Some(Song {
+        song_id: Some(song.song_id),
+        artist_id: Some(song.artist_id),
+        album_id: Some(song.album_id),
+        title: song.title.clone(),
+        artist: song.artist.clone(),
+        album: song.album.clone(),
+        duration: song.duration,
+        cover: song.cover.clone().unwrap_or_default(),
+        url,
+    })
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SongListProps {
+    pub songs: Vec<SongSummary>,
+    /// Hidden on an album's own page
+    #[prop_or(true)]
+    pub show_album: bool,
+}
+
+/// Songs in rows linking to their song, artist and album pages. Playing one queues the
+/// playable songs of the list from it onwards.
+#[function_component(SongList)]
+pub fn song_list(props: &SongListProps) -> Html {
+    let (store, _) = use_store::<Store>();
+    let player_dispatch = use_dispatch::<Player>();
+    let logged_in = store.auth_user.is_some();
+    let playable = props.songs.iter()
+        .filter_map(|song| playable_song(song, logged_in))
+        .collect::<Vec<_>>();
+
+    html! {
+        <ol class="divide-y divide-secondary/30">
+            {for props.songs.iter().map(|song| {
+                let song_to_play = playable_song(song, logged_in);
+                let handle_play = {
+                    let player_dispatch = player_dispatch.clone();
+                    let playable = playable.clone();
+                    let song_to_play = song_to_play.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        let Some(song) = &song_to_play else {
+                            return;
+                        };
+                        match playable.iter().position(|queued| queued == song) {
+                            Some(start) => play_queue(playable.clone(), start, player_dispatch.clone()),
+                            None => play_song(song.clone(), player_dispatch.clone()),
+                        }
+                    })
+                };
+                let handle_queue = {
+                    let player_dispatch = player_dispatch.clone();
+                    let song_to_play = song_to_play.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        if let Some(song) = &song_to_play {
+                            enqueue_song(song.clone(), player_dispatch.clone());
+                        }
+                    })
+                };
+                let unavailable = song_to_play.is_none();
+
+                html! {
+                    <li key={song.song_id.to_string()} class="flex items-center gap-4 py-2">
+                        if let Some(cover) = &song.cover {
+                            <img src={cover.clone()} alt="" class="w-10 h-10 rounded-md" />
+                        }
+                        <div class="flex-1 min-w-0">
+                            <Link<Route> to={Route::SongPage { id: song.song_id }} classes="block font-semibold truncate hover:underline">
+                                {&song.title}
+                            </Link<Route>>
+                            <p class="text-sm truncate text-secondary">
+                                <Link<Route> to={Route::ArtistPage { id: song.artist_id }} classes="hover:underline">{&song.artist}</Link<Route>>
+                                if props.show_album {
+                                    {" · "}
+                                    <Link<Route> to={Route::AlbumPage { id: song.album_id }} classes="hover:underline">{&song.album}</Link<Route>>
+                                }
+                            </p>
+                        </div>
+                        <span class="text-sm">{format_time(song.duration as f64)}</span>
+                        <button
+                            class="px-3 py-1 text-sm font-semibold rounded-md bg-primary disabled:opacity-50"
+                            title={if unavailable { "Not available to play" } else { "Play" }}
+                            disabled={unavailable}
+                            onclick={handle_play}
+                        >
+                            {"Play"}
+                        </button>
+                        <button
+                            class="px-3 py-1 text-sm font-semibold rounded-md bg-secondary disabled:opacity-50"
+                            disabled={unavailable}
+                            onclick={handle_queue}
+                        >
+                            {"Queue"}
+                        </button>
+                    </li>
+                }
+            })}
+        </ol>
+    }
+}
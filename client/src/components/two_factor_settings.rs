@@ -0,0 +1,162 @@
+use crate::api::client;
+use crate::components::ui::{button::Button, input::Input};
+use crate::store::{set_auth_user, set_show_alert, Store};
+use common::schema::two_factor::{DisableTwoFactorSchema, TwoFactorCodeSchema, TwoFactorEnrollResponse};
+
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct TwoFactorSettingsProps {
+    /// Whether the logged in user already has 2FA enabled
+    pub enabled: bool,
+}
+
+#[function_component(TwoFactorSettings)]
+pub fn two_factor_settings(props: &TwoFactorSettingsProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let enrollment = use_state(|| None::<TwoFactorEnrollResponse>);
+    let recovery_codes = use_state(Vec::<String>::new);
+    let code = use_state(String::new);
+    let password = use_state(String::new);
+
+    let handle_code_input = {
+        let cloned_code = code.clone();
+        Callback::from(move |value: String| cloned_code.set(value))
+    };
+
+    let handle_password_input = {
+        let cloned_password = password.clone();
+        Callback::from(move |value: String| cloned_password.set(value))
+    };
+
+    let on_enroll = {
+        let cloned_enrollment = enrollment.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let enrollment = cloned_enrollment.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                match client().enroll_two_factor().await {
+                    Ok(data) => enrollment.set(Some(data)),
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    let on_enable = {
+        let cloned_code = code.clone();
+        let cloned_enrollment = enrollment.clone();
+        let cloned_recovery_codes = recovery_codes.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let code = cloned_code.clone();
+            let enrollment = cloned_enrollment.clone();
+            let recovery_codes = cloned_recovery_codes.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                let code_data = TwoFactorCodeSchema {
+                    code: code.trim().to_string(),
+                };
+
+                match client().enable_two_factor(&code_data).await {
+                    Ok(response) => {
+                        enrollment.set(None);
+                        recovery_codes.set(response.recovery_codes);
+                        if let Ok(user) = client().user_info().await {
+                            set_auth_user(Some(user), dispatch);
+                        }
+                    }
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    let on_regenerate = {
+        let cloned_code = code.clone();
+        let cloned_recovery_codes = recovery_codes.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let code = cloned_code.clone();
+            let recovery_codes = cloned_recovery_codes.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                let code_data = TwoFactorCodeSchema {
+                    code: code.trim().to_string(),
+                };
+
+                match client().regenerate_recovery_codes(&code_data).await {
+                    Ok(response) => recovery_codes.set(response.recovery_codes),
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    let on_disable = {
+        let cloned_code = code.clone();
+        let cloned_password = password.clone();
+        let cloned_recovery_codes = recovery_codes.clone();
+        let store_dispatch = dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let code = cloned_code.clone();
+            let password = cloned_password.clone();
+            let recovery_codes = cloned_recovery_codes.clone();
+            let dispatch = store_dispatch.clone();
+            spawn_local(async move {
+                let disable = DisableTwoFactorSchema {
+                    password: (*password).clone(),
+                    code: code.trim().to_string(),
+                };
+
+                match client().disable_two_factor(&disable).await {
+                    Ok(_) => {
+                        recovery_codes.set(vec![]);
+                        if let Ok(user) = client().user_info().await {
+                            set_auth_user(Some(user), dispatch.clone());
+                        }
+                        set_show_alert("Two-factor authentication disabled".to_string(), dispatch);
+                    }
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="mt-8 space-y-4">
+            <p class="text-2xl font-semibold">{"Two-Factor Authentication"}</p>
+
+            if !recovery_codes.is_empty() {
+                <div>
+                    <p class="mb-2">{"Save these recovery codes somewhere safe. Each can be used once if you lose access to your authenticator app, and they won't be shown again."}</p>
+                    <ul class="font-mono">
+                        { for recovery_codes.iter().map(|code| html! { <li>{code}</li> }) }
+                    </ul>
+                </div>
+            }
+
+            if props.enabled {
+                <p>{"Two-factor authentication is enabled."}</p>
+                <Input label="Code" name="code" handle_onchange={handle_code_input} />
+                <Input label="Password" name="password" input_type="password" handle_onchange={handle_password_input} />
+                <div class="flex gap-4">
+                    <Button class="px-4 py-2" onclick={on_regenerate}>{"New Recovery Codes"}</Button>
+                    <Button class="px-4 py-2" onclick={on_disable}>{"Disable"}</Button>
+                </div>
+            } else if let Some(enrollment) = (*enrollment).clone() {
+                <p>{"Add this account to your authenticator app, then enter the code it shows."}</p>
+                <p class="font-mono break-all">{enrollment.otpauth_uri}</p>
+                <p>{format!("Setup key: {}", enrollment.secret)}</p>
+                <Input label="Code" name="code" handle_onchange={handle_code_input} />
+                <Button class="px-4 py-2" onclick={on_enable}>{"Enable"}</Button>
+            } else {
+                <p>{"Protect your account with a code from an authenticator app when you log in."}</p>
+                <Button class="px-4 py-2" onclick={on_enroll}>{"Set Up"}</Button>
+            }
+        </div>
+    }
+}
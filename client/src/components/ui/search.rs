@@ -1,13 +1,23 @@
-use web_sys::{HtmlInputElement, InputEvent};
-use yew::{html, Callback, Component, Html, NodeRef, Properties, Context};
+use common::schema::{
+    album::AlbumSummary,
+    artist::ArtistSummary,
+    search::{PlaylistSummary, SearchResults},
+    song::SongSummary
+};
+use gloo::timers::callback::Timeout;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::prelude::*;
 use yewdux::prelude::*;
-use wasm_bindgen_futures::{spawn_local};
-use crate::store::{Store, set_search_input, set_search_results, clear_search_results, set_show_alert};
-use crate::api::search_api::api_search_songs;
-use super::input::Input;
+use wasm_bindgen_futures::spawn_local;
+use crate::api::client;
+use crate::pages::search_page::SearchQuery;
 use crate::router::Route;
+use crate::store::{add_recent_search, clear_recent_searches, set_search_input, Store};
+use super::button::Button;
+
+/// How long typing has to pause before suggestions are fetched, in milliseconds
+const DEBOUNCE_MS: u32 = 250;
 
 #[derive(Clone, Debug, Properties, PartialEq)]
 pub struct SearchProps {
@@ -15,82 +25,326 @@ pub struct SearchProps {
     pub class: String,
     #[prop_or_default]
     pub placeholder: String,
+    /// Called after a search is submitted or a suggestion picked
     #[prop_or_default]
     pub onsearch: Callback<()>,
 }
 
+/// An entry in the suggestions dropdown
+#[derive(Clone, PartialEq)]
+enum Suggestion {
+    Recent(String),
+    Song(SongSummary),
+    Artist(ArtistSummary),
+    Album(AlbumSummary),
+    Playlist(PlaylistSummary),
+}
+
+impl Suggestion {
+    /// The heading of the suggestion's group
+    fn group(&self) -> &'static str {
+        match self {
+            Suggestion::Recent(_) => "Recent searches",
+            Suggestion::Song(_) => "Songs",
+            Suggestion::Artist(_) => "Artists",
+            Suggestion::Album(_) => "Albums",
+            Suggestion::Playlist(_) => "Playlists",
+        }
+    }
+
+    /// Where picking the suggestion goes, recent searches are searched for again instead
+    fn route(&self) -> Option<Route> {
+        match self {
+            Suggestion::Recent(_) => None,
+            Suggestion::Song(song) => Some(Route::SongPage { id: song.song_id }),
+            Suggestion::Artist(artist) => Some(Route::ArtistPage { id: artist.artist_id }),
+            Suggestion::Album(album) => Some(Route::AlbumPage { id: album.album_id }),
+            Suggestion::Playlist(playlist) => Some(Route::PublicProfilePage { username: playlist.owner.username.clone() }),
+        }
+    }
+
+    fn view(&self) -> Html {
+        let (title, detail) = match self {
+            Suggestion::Recent(query) => (query.clone(), None),
+            Suggestion::Song(song) => (song.title.clone(), Some(song.artist.clone())),
+            Suggestion::Artist(artist) => (artist.name.clone(), None),
+            Suggestion::Album(album) => (album.title.clone(), Some(album.artist.clone())),
+            Suggestion::Playlist(playlist) => (playlist.name.clone(), Some(format!("by @{}", playlist.owner.username))),
+        };
+
+        html! {
+            <>
+                <span class="truncate">{title}</span>
+                if let Some(detail) = detail {
+                    <span class="text-sm truncate text-secondary">{format!(" · {}", detail)}</span>
+                }
+            </>
+        }
+    }
+}
+
+/// The recent searches when nothing is typed, otherwise the suggestions for what is
+fn suggestion_items(query: &str, suggestions: &SearchResults, recent_searches: &[String]) -> Vec<Suggestion> {
+    if query.trim().is_empty() {
+        return recent_searches.iter().cloned().map(Suggestion::Recent).collect();
+    }
+
+    suggestions.songs.iter().cloned().map(Suggestion::Song)
+        .chain(suggestions.artists.iter().cloned().map(Suggestion::Artist))
+        .chain(suggestions.albums.iter().cloned().map(Suggestion::Album))
+        .chain(suggestions.playlists.iter().cloned().map(Suggestion::Playlist))
+        .collect()
+}
+
+/// The search box, suggesting songs, artists, albums and playlists while the user types and
+/// their recent searches before they do. The arrow keys move through the suggestions, Enter picks
+/// the highlighted one or searches for what was typed on the search page, and Escape closes them.
 #[function_component(Search)]
 pub fn search(props: &SearchProps) -> Html {
     let (store, dispatch) = use_store::<Store>();
     let navigator = use_navigator().unwrap();
-    let search_input = store.search_input.clone();
-    let search_input_ref = NodeRef::default();
-
-    let handle_search = {
-        let store_dispatch = dispatch.clone();
-        let cloned_navigator = navigator.clone();
-        println!("searching");
-
-        Callback::from(move |_: MouseEvent| {
-            let dispatch = store_dispatch.clone();
-            let navigator = cloned_navigator.clone();
-            let search_input = search_input.clone();
-            spawn_local(async move {
-                // Call the search API
-                let result = api_search_songs(search_input).await;
-                match result {
-                    Ok(songs) => {
-                        set_search_results(songs, dispatch.clone());
-                        // navigator.push(&Route::ProfilePage);
-                    }
-                    Err(e) => {
-                        set_search_results(vec![], dispatch.clone());
-                        set_show_alert(e.message.to_string(), dispatch.clone());
-                    }
-                };
-            });
+    let query = use_state(|| store.search_input.clone());
+    let suggestions = use_state(SearchResults::default);
+    let open = use_state(|| false);
+    let active = use_state(|| None::<usize>);
+    // Dropping the pending timeout cancels it
+    let debounce = use_mut_ref(|| None::<Timeout>);
+    // Counts the suggestion requests, so that slow responses to earlier ones are ignored
+    let latest_request = use_mut_ref(|| 0u32);
+
+    // Show the query of the search page when it's opened from a link
+    {
+        let query = query.clone();
+        use_effect_with(store.search_input.clone(), move |search_input| query.set(search_input.clone()));
+    }
+
+    let items = suggestion_items(&query, &suggestions, &store.recent_searches);
+
+    let close = {
+        let open = open.clone();
+        let active = active.clone();
+        Callback::from(move |_: ()| {
+            open.set(false);
+            active.set(None);
+        })
+    };
+
+    let submit = {
+        let dispatch = dispatch.clone();
+        let navigator = navigator.clone();
+        let close = close.clone();
+        let onsearch = props.onsearch.clone();
+        Callback::from(move |query: String| {
+            let query = query.trim().to_string();
+            if query.is_empty() {
+                return;
+            }
+
+            add_recent_search(query.clone(), dispatch.clone());
+            set_search_input(query.clone(), dispatch.clone());
+            let _ = navigator.push_with_query(&Route::SearchPage, &SearchQuery { q: query });
+            close.emit(());
+            onsearch.emit(());
+        })
+    };
+
+    let pick = {
+        let items = items.clone();
+        let query = query.clone();
+        let dispatch = dispatch.clone();
+        let navigator = navigator.clone();
+        let submit = submit.clone();
+        let close = close.clone();
+        let onsearch = props.onsearch.clone();
+        Callback::from(move |index: usize| {
+            let Some(item) = items.get(index) else { return };
+            match (item, item.route()) {
+                (Suggestion::Recent(recent), _) => submit.emit(recent.clone()),
+                (_, Some(route)) => {
+                    // What was typed to find it is worth remembering too
+                    add_recent_search(query.trim().to_string(), dispatch.clone());
+                    navigator.push(&route);
+                    close.emit(());
+                    onsearch.emit(());
+                }
+                (_, None) => (),
+            }
         })
     };
 
     let handle_input = {
-        let store_dispatch = dispatch.clone();
-
-        Callback::from(move |value: String| {
-            let dispatch = store_dispatch.clone();
-            dispatch.reduce_mut(move |store| {
-                store.search_input = value;
-                store.search_results.clear();
-            });
+        let query = query.clone();
+        let suggestions = suggestions.clone();
+        let open = open.clone();
+        let active = active.clone();
+        let debounce = debounce.clone();
+        let latest_request = latest_request.clone();
+        Callback::from(move |event: InputEvent| {
+            let Some(input) = event.target_dyn_into::<HtmlInputElement>() else { return };
+            let value = input.value();
+            query.set(value.clone());
+            open.set(true);
+            active.set(None);
+
+            let suggestions = suggestions.clone();
+            let latest_request = latest_request.clone();
+            *latest_request.borrow_mut() += 1;
+            if value.trim().is_empty() {
+                debounce.borrow_mut().take();
+                suggestions.set(SearchResults::default());
+                return;
+            }
+
+            *debounce.borrow_mut() = Some(Timeout::new(DEBOUNCE_MS, move || {
+                let request = *latest_request.borrow();
+                spawn_local(async move {
+                    // Suggestions that fail to load just aren't shown, the search page reports errors
+                    let results = client().search_suggestions(value.trim()).await.unwrap_or_default();
+                    if *latest_request.borrow() == request {
+                        suggestions.set(results);
+                    }
+                });
+            }));
+        })
+    };
+
+    let handle_keydown = {
+        let open = open.clone();
+        let active = active.clone();
+        let close = close.clone();
+        let pick = pick.clone();
+        let count = items.len();
+        Callback::from(move |event: KeyboardEvent| {
+            match event.key().as_str() {
+                "ArrowDown" | "ArrowUp" if count > 0 => {
+                    event.prevent_default();
+                    let next = match (event.key().as_str(), *active) {
+                        ("ArrowDown", Some(index)) => (index + 1) % count,
+                        ("ArrowDown", None) => 0,
+                        (_, Some(index)) => (index + count - 1) % count,
+                        (_, None) => count - 1,
+                    };
+                    open.set(true);
+                    active.set(Some(next));
+                }
+                // Without a highlighted suggestion the form is submitted
+                "Enter" if *open && active.is_some() => {
+                    event.prevent_default();
+                    pick.emit(active.unwrap());
+                }
+                "Escape" => close.emit(()),
+                _ => (),
+            }
         })
     };
 
-    let handle_on_input_blur = {
-        let store_dispatch = dispatch.clone();
+    let handle_submit = {
+        let query = query.clone();
+        let submit = submit.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            submit.emit((*query).clone());
+        })
+    };
+
+    let handle_focus = {
+        let open = open.clone();
+        Callback::from(move |_: FocusEvent| open.set(true))
+    };
+
+    let handle_blur = {
+        let close = close.clone();
+        Callback::from(move |_: FocusEvent| close.emit(()))
+    };
 
-        Callback::from(move |(name, value): (String, String)| {
-            let dispatch = store_dispatch.clone();
-            dispatch.reduce_mut(move |store| {
-                store.search_input = value;
-                store.search_results.clear();
-            });
+    let handle_clear_recent = {
+        let dispatch = dispatch.clone();
+        // Keeps the focus in the search box, which would close the suggestions
+        Callback::from(move |event: MouseEvent| {
+            event.prevent_default();
+            clear_recent_searches(dispatch.clone());
         })
     };
 
-    let class = classes!(
-        "bg-input",
-        props.class.clone()
-    );
+    let show_suggestions = *open && !items.is_empty();
+    let active_id = active.filter(|_| show_suggestions).map(|index| format!("search-suggestion-{}", index));
 
     html! {
-        <Input
-            class={class.to_string()}
-            name="search"
-            input_type="search"
-            input_ref={search_input_ref.clone()}
-            placeholder={props.placeholder.clone()}
-            handle_on_input_blur={handle_on_input_blur}
-            handle_onchange={handle_input}
-            onsearch={handle_search}
-        />
+        <form class={classes!("relative", props.class.clone())} role="search" onsubmit={handle_submit}>
+            <div class="relative flex items-center">
+                <span class="absolute top-4 left-2">
+                    <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke-width="1.5" stroke="currentColor" class="w-6 h-6">
+                        <path stroke-linecap="round" stroke-linejoin="round" d="m21 21-5.197-5.197m0 0A7.5 7.5 0 1 0 5.196 5.196a7.5 7.5 0 0 0 10.607 10.607Z" />
+                    </svg>
+                </span>
+
+                <input
+                    type="search"
+                    name="search"
+                    class="block w-full px-6 pl-8 pr-[6rem] py-4 border rounded-sm appearance-none border-1 focus:outline-none bg-input"
+                    placeholder={props.placeholder.clone()}
+                    autocomplete="off"
+                    role="combobox"
+                    aria-autocomplete="list"
+                    aria-controls="search-suggestions"
+                    aria-expanded={show_suggestions.to_string()}
+                    aria-activedescendant={active_id}
+                    value={(*query).clone()}
+                    oninput={handle_input}
+                    onkeydown={handle_keydown}
+                    onfocus={handle_focus}
+                    onblur={handle_blur}
+                />
+
+                <Button class="absolute px-4 py-2 right-2" btn_type="submit">
+                    {"Search"}
+                </Button>
+            </div>
+
+            if show_suggestions {
+                <ul
+                    id="search-suggestions"
+                    role="listbox"
+                    class="absolute left-0 right-0 z-20 py-2 overflow-y-auto border border-black rounded-sm shadow-md top-full max-h-96 bg-background"
+                >
+                    {for items.iter().enumerate().map(|(index, item)| {
+                        let heading = (index == 0 || items[index - 1].group() != item.group()).then(|| item.group());
+                        let is_active = *active == Some(index);
+                        let handle_pick = {
+                            let pick = pick.clone();
+                            // On mouse down, before the search box loses focus and closes the suggestions
+                            Callback::from(move |event: MouseEvent| {
+                                event.prevent_default();
+                                pick.emit(index);
+                            })
+                        };
+
+                        html! {
+                            <>
+                                if let Some(heading) = heading {
+                                    <li role="presentation" class="flex items-center justify-between px-4 pt-2 text-xs font-semibold uppercase text-secondary">
+                                        {heading}
+                                        if matches!(item, Suggestion::Recent(_)) {
+                                            <button type="button" class="normal-case hover:underline" onmousedown={handle_clear_recent.clone()}>
+                                                {"Clear"}
+                                            </button>
+                                        }
+                                    </li>
+                                }
+                                <li
+                                    id={format!("search-suggestion-{}", index)}
+                                    role="option"
+                                    aria-selected={is_active.to_string()}
+                                    class={classes!("flex", "items-baseline", "gap-1", "px-4", "py-1", "cursor-pointer", "hover:bg-primary/60", is_active.then_some("bg-primary"))}
+                                    onmousedown={handle_pick}
+                                >
+                                    {item.view()}
+                                </li>
+                            </>
+                        }
+                    })}
+                </ul>
+            }
+        </form>
     }
-}
\ No newline at end of file
+}
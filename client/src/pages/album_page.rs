@@ -0,0 +1,114 @@
+use crate::api::client;
+use crate::components::{player::format_time, song_list::{playable_song, SongList}};
+use crate::router::Route;
+use crate::store::{play_queue, set_loading, Player, Store};
+use common::schema::album::AlbumDetails;
+
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct AlbumPageProps {
+    pub id: Uuid,
+}
+
+#[derive(Clone, PartialEq)]
+enum AlbumStatus {
+    Loading,
+    Loaded(AlbumDetails),
+    Failed(String),
+}
+
+/// An album at `/album/{id}`, with its tracks
+#[function_component(AlbumPage)]
+pub fn album_page(props: &AlbumPageProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let player_dispatch = use_dispatch::<Player>();
+    let status = use_state(|| AlbumStatus::Loading);
+    let logged_in = store.auth_user.is_some();
+
+    {
+        let status = status.clone();
+        use_effect_with(props.id, move |id| {
+            let id = *id;
+            status.set(AlbumStatus::Loading);
+            spawn_local(async move {
+                set_loading(true, dispatch.clone());
+                match client().album(id).await {
+                    Ok(album) => status.set(AlbumStatus::Loaded(album)),
+                    Err(e) => status.set(AlbumStatus::Failed(e.to_string())),
+                }
+                set_loading(false, dispatch);
+            });
+        });
+    }
+
+    html! {
+        <section class="min-h-screen pt-20 bg-ct-blue-600">
+            <div class="max-w-4xl mx-auto bg-ct-dark-100 rounded-md min-h-[20rem] p-8">
+                {match &*status {
+                    AlbumStatus::Loading => html! {
+                        <p>{"Loading..."}</p>
+                    },
+                    AlbumStatus::Failed(message) => html! {
+                        <p>
+                            {format!("{}. ", message)}
+                            <Link<Route> to={Route::HomePage} classes="text-info hover:underline">{ "Back to Home" }</Link<Route>>
+                        </p>
+                    },
+                    AlbumStatus::Loaded(details) => {
+                        let album = &details.album;
+                        let playable = details.tracks.iter()
+                            .filter_map(|song| playable_song(song, logged_in))
+                            .collect::<Vec<_>>();
+                        let total_seconds = details.tracks.iter().map(|song| song.duration as f64).sum::<f64>();
+                        let handle_play_all = {
+                            let player_dispatch = player_dispatch.clone();
+                            let playable = playable.clone();
+                            Callback::from(move |_: MouseEvent| play_queue(playable.clone(), 0, player_dispatch.clone()))
+                        };
+
+                        html! {
+                            <div>
+                                <div class="flex items-center gap-6">
+                                    if let Some(cover) = &album.cover {
+                                        <img src={cover.clone()} alt="Album cover" class="w-40 h-40 rounded-md" />
+                                    }
+                                    <div>
+                                        <p class="text-5xl font-semibold">{&album.title}</p>
+                                        <p class="mt-2">
+                                            <Link<Route> to={Route::ArtistPage { id: album.artist_id }} classes="font-semibold text-info hover:underline">
+                                                {&album.artist}
+                                            </Link<Route>>
+                                        </p>
+                                        <p class="text-sm">
+                                            if let Some(genre) = &details.genre {
+                                                {format!("{} · ", genre)}
+                                            }
+                                            if let Some(release_date) = album.release_date {
+                                                {format!("{} · ", release_date.format("%B %e, %Y"))}
+                                            }
+                                            {format!("{} songs, {}", album.track_count, format_time(total_seconds))}
+                                        </p>
+                                        if !playable.is_empty() {
+                                            <button class="px-4 py-1 mt-4 text-sm font-semibold rounded-md bg-primary" onclick={handle_play_all}>
+                                                {"Play all"}
+                                            </button>
+                                        }
+                                    </div>
+                                </div>
+
+                                <div class="mt-8">
+                                    <SongList songs={details.tracks.clone()} show_album={false} />
+                                </div>
+                            </div>
+                        }
+                    }
+                }}
+            </div>
+        </section>
+    }
+}
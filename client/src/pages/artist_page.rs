@@ -0,0 +1,100 @@
+use crate::api::client;
+use crate::components::song_list::SongList;
+use crate::router::Route;
+use crate::store::{set_loading, Store};
+use common::schema::artist::ArtistDetails;
+
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ArtistPageProps {
+    pub id: Uuid,
+}
+
+#[derive(Clone, PartialEq)]
+enum ArtistStatus {
+    Loading,
+    Loaded(ArtistDetails),
+    Failed(String),
+}
+
+/// An artist at `/artist/{id}`, with their most played songs and discography
+#[function_component(ArtistPage)]
+pub fn artist_page(props: &ArtistPageProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let status = use_state(|| ArtistStatus::Loading);
+
+    {
+        let status = status.clone();
+        use_effect_with(props.id, move |id| {
+            let id = *id;
+            status.set(ArtistStatus::Loading);
+            spawn_local(async move {
+                set_loading(true, dispatch.clone());
+                match client().artist(id).await {
+                    Ok(artist) => status.set(ArtistStatus::Loaded(artist)),
+                    Err(e) => status.set(ArtistStatus::Failed(e.to_string())),
+                }
+                set_loading(false, dispatch);
+            });
+        });
+    }
+
+    html! {
+        <section class="min-h-screen pt-20 bg-ct-blue-600">
+            <div class="max-w-4xl mx-auto bg-ct-dark-100 rounded-md min-h-[20rem] p-8">
+                {match &*status {
+                    ArtistStatus::Loading => html! {
+                        <p>{"Loading..."}</p>
+                    },
+                    ArtistStatus::Failed(message) => html! {
+                        <p>
+                            {format!("{}. ", message)}
+                            <Link<Route> to={Route::HomePage} classes="text-info hover:underline">{ "Back to Home" }</Link<Route>>
+                        </p>
+                    },
+                    ArtistStatus::Loaded(details) => html! {
+                        <div>
+                            <p class="text-5xl font-semibold">{&details.artist.name}</p>
+                            if !details.genres.is_empty() {
+                                <p class="mt-2 text-sm">{details.genres.join(", ")}</p>
+                            }
+
+                            if !details.top_songs.is_empty() {
+                                <h3 class="mt-8 text-2xl font-semibold">{"Popular"}</h3>
+                                <SongList songs={details.top_songs.clone()} />
+                            }
+
+                            <h3 class="mt-8 text-2xl font-semibold">{"Discography"}</h3>
+                            if details.albums.is_empty() {
+                                <p class="mt-2">{"No albums yet."}</p>
+                            }
+                            <ul class="grid grid-cols-2 gap-4 mt-2 md:grid-cols-4">
+                                { for details.albums.iter().map(|album| html! {
+                                    <li key={album.album_id.to_string()}>
+                                        <Link<Route> to={Route::AlbumPage { id: album.album_id }} classes="block hover:underline">
+                                            if let Some(cover) = &album.cover {
+                                                <img src={cover.clone()} alt="" class="w-full rounded-md aspect-square" />
+                                            }
+                                            <p class="mt-1 font-semibold truncate">{&album.title}</p>
+                                        </Link<Route>>
+                                        <p class="text-sm text-secondary">
+                                            if let Some(release_date) = album.release_date {
+                                                {format!("{} · ", release_date.format("%Y"))}
+                                            }
+                                            {format!("{} songs", album.track_count)}
+                                        </p>
+                                    </li>
+                                }) }
+                            </ul>
+                        </div>
+                    },
+                }}
+            </div>
+        </section>
+    }
+}
@@ -0,0 +1,74 @@
+use crate::api::client;
+use crate::pages::reset_password_page::TokenQuery;
+use crate::router::Route;
+use crate::store::{set_loading, Store};
+use common::schema::user::VerifyEmailSchema;
+
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Clone, PartialEq)]
+enum ConfirmationStatus {
+    Pending,
+    Confirmed,
+    Failed(String),
+}
+
+#[function_component(ConfirmEmailChangePage)]
+pub fn confirm_email_change_page() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let status = use_state(|| ConfirmationStatus::Pending);
+    let token = use_location()
+        .and_then(|location| location.query::<TokenQuery>().ok())
+        .map(|query| query.token)
+        .unwrap_or_default();
+
+    {
+        let status = status.clone();
+        use_effect_with(token, move |token| {
+            let token = token.clone();
+            spawn_local(async move {
+                if token.is_empty() {
+                    status.set(ConfirmationStatus::Failed("This confirmation link is invalid".to_string()));
+                    return;
+                }
+
+                set_loading(true, dispatch.clone());
+                match client().confirm_email_change(&VerifyEmailSchema { token }).await {
+                    Ok(_) => status.set(ConfirmationStatus::Confirmed),
+                    Err(e) => status.set(ConfirmationStatus::Failed(e.to_string())),
+                }
+                set_loading(false, dispatch);
+            });
+        });
+    }
+
+    html! {
+        <section class="grid h-full place-items-center">
+            <div class="w-full text-center">
+                <h1 class="text-4xl xl:text-6xl text-center font-[600] text-primary mb-4">
+                    {"Confirm Email Change"}
+                </h1>
+                {match &*status {
+                    ConfirmationStatus::Pending => html! {
+                        <p class="mb-4">{"Confirming your new email..."}</p>
+                    },
+                    ConfirmationStatus::Confirmed => html! {
+                        <p class="mb-4">
+                            {"Your email has been changed. "}
+                            <Link<Route> to={Route::ProfilePage} classes="text-info hover:underline">{ "Back to Profile" }</Link<Route>>
+                        </p>
+                    },
+                    ConfirmationStatus::Failed(message) => html! {
+                        <p class="mb-4">
+                            {format!("{}. ", message)}
+                            <Link<Route> to={Route::LoginPage} classes="text-info hover:underline">{ "Back to Login" }</Link<Route>>
+                        </p>
+                    },
+                }}
+            </div>
+        </section>
+    }
+}
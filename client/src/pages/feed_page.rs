@@ -0,0 +1,105 @@
+use crate::api::client;
+use crate::components::ui::button::Button;
+use crate::router::Route;
+use crate::store::{set_loading, set_show_alert, Store};
+use common::schema::social::{FeedItem, FeedItemKind};
+
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+fn feed_item_text(item: &FeedItem) -> String {
+    match item.kind {
+        FeedItemKind::Like => format!("liked {}", item.song.as_ref().map(|song| song.title.as_str()).unwrap_or("a song")),
+        FeedItemKind::Play => format!("listened to {}", item.song.as_ref().map(|song| song.title.as_str()).unwrap_or("a song")),
+        FeedItemKind::Playlist => format!("created the playlist {}", item.playlist.as_ref().map(|playlist| playlist.name.as_str()).unwrap_or("")),
+    }
+}
+
+/// Recent activity of the users the logged in user follows
+#[function_component(FeedPage)]
+pub fn feed_page() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let navigator = use_navigator().unwrap();
+    let items = use_state(Vec::<FeedItem>::new);
+    let next_cursor = use_state(|| None::<String>);
+    let loaded = use_state(|| false);
+
+    let load_page = {
+        let items = items.clone();
+        let next_cursor = next_cursor.clone();
+        let loaded = loaded.clone();
+        Callback::from(move |cursor: Option<String>| {
+            let items = items.clone();
+            let next_cursor = next_cursor.clone();
+            let loaded = loaded.clone();
+            let dispatch = dispatch.clone();
+            let navigator = navigator.clone();
+            spawn_local(async move {
+                set_loading(true, dispatch.clone());
+                match client().feed(cursor.as_deref()).await {
+                    Ok(page) => {
+                        set_loading(false, dispatch);
+                        let mut all_items = (*items).clone();
+                        all_items.extend(page.items);
+                        items.set(all_items);
+                        next_cursor.set(page.next_cursor);
+                        loaded.set(true);
+                    }
+                    Err(e) => {
+                        set_loading(false, dispatch.clone());
+                        set_show_alert(e.to_string(), dispatch);
+                        if !*loaded {
+                            navigator.push(&Route::LoginPage);
+                        }
+                    }
+                }
+            });
+        })
+    };
+
+    {
+        let load_page = load_page.clone();
+        use_effect_with((), move |_| load_page.emit(None));
+    }
+
+    let on_load_more = {
+        let next_cursor = next_cursor.clone();
+        Callback::from(move |_: MouseEvent| load_page.emit((*next_cursor).clone()))
+    };
+
+    html! {
+        <section class="min-h-screen pt-20 bg-ct-blue-600">
+            <div class="max-w-4xl mx-auto bg-ct-dark-100 rounded-md min-h-[20rem] p-8">
+                <p class="text-5xl font-semibold">{"Feed"}</p>
+                if !*loaded {
+                    <p class="mt-8">{"Loading..."}</p>
+                } else if items.is_empty() {
+                    <p class="mt-8">{"Nothing here yet. Follow people to see what they're listening to."}</p>
+                }
+                <ul class="mt-8 space-y-4">
+                    { for items.iter().map(|item| html! {
+                        <li key={item.id.to_string()} class="flex items-center gap-4">
+                            if !item.user.photo.is_empty() {
+                                <img src={item.user.photo.clone()} alt="Avatar" class="w-10 h-10 rounded-full" />
+                            }
+                            <div>
+                                <p>
+                                    <Link<Route> to={Route::PublicProfilePage { username: item.user.username.clone() }} classes="font-semibold text-info hover:underline">
+                                        {&item.user.name}
+                                    </Link<Route>>
+                                    {format!(" {}", feed_item_text(item))}
+                                </p>
+                                <p class="text-sm">{item.occurred_at.format("%b %e, %Y %H:%M").to_string()}</p>
+                            </div>
+                        </li>
+                    }) }
+                </ul>
+                if next_cursor.is_some() {
+                    <Button onclick={on_load_more} class="mt-8 px-4 py-2">{"Load more"}</Button>
+                }
+            </div>
+        </section>
+    }
+}
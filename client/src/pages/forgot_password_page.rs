@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use crate::api::client;
+use crate::components::ui::{button::Button, input::Input};
+use crate::router::{self, Route};
+use crate::store::{set_loading, set_show_alert, Store};
+use common::schema::user::EmailRequestSchema;
+
+use validator::{Validate, ValidationErrors};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+#[function_component(ForgotPasswordPage)]
+pub fn forgot_password_page() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let form = use_state(|| EmailRequestSchema::default());
+    let validation_errors = use_state(|| Rc::new(RefCell::new(ValidationErrors::new())));
+    let navigator = use_navigator().unwrap();
+
+    let email_input_ref = NodeRef::default();
+
+    let validate_input_on_blur = {
+        let cloned_form = form.clone();
+        let cloned_validation_errors = validation_errors.clone();
+        Callback::from(move |(name, value): (String, String)| {
+            let mut data = cloned_form.deref().clone();
+            data.email = value;
+            cloned_form.set(data);
+
+            match cloned_form.validate() {
+                Ok(_) => {
+                    cloned_validation_errors
+                        .borrow_mut()
+                        .errors_mut()
+                        .remove(name.as_str());
+                }
+                Err(errors) => {
+                    cloned_validation_errors
+                        .borrow_mut()
+                        .errors_mut()
+                        .retain(|key, _| key != &name);
+                    for (field_name, error) in errors.errors() {
+                        if field_name == &name {
+                            cloned_validation_errors
+                                .borrow_mut()
+                                .errors_mut()
+                                .insert(field_name.clone(), error.clone());
+                        }
+                    }
+                }
+            }
+        })
+    };
+
+    let handle_email_input = {
+        let cloned_form = form.clone();
+        Callback::from(move |value: String| {
+            let mut data = cloned_form.deref().clone();
+            data.email = value;
+            cloned_form.set(data);
+        })
+    };
+
+    let on_submit = {
+        let cloned_form = form.clone();
+        let cloned_validation_errors = validation_errors.clone();
+        let store_dispatch = dispatch.clone();
+        let cloned_navigator = navigator.clone();
+        let cloned_email_input_ref = email_input_ref.clone();
+
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let dispatch = store_dispatch.clone();
+            let form = cloned_form.clone();
+            let validation_errors = cloned_validation_errors.clone();
+            let navigator = cloned_navigator.clone();
+            let email_input_ref = cloned_email_input_ref.clone();
+
+            spawn_local(async move {
+                match form.validate() {
+                    Ok(_) => {
+                        let form_data = form.deref().clone();
+                        set_loading(true, dispatch.clone());
+
+                        let email_input = email_input_ref.cast::<HtmlInputElement>().unwrap();
+                        email_input.set_value("");
+
+                        let res = client().forgot_password(&form_data).await;
+                        match res {
+                            Ok(_) => {
+                                set_loading(false, dispatch.clone());
+                                set_show_alert(
+                                    "If an account exists for that email, a password reset link is on its way".to_string(),
+                                    dispatch,
+                                );
+                                navigator.push(&router::Route::LoginPage);
+                            }
+                            Err(e) => {
+                                set_loading(false, dispatch.clone());
+                                set_show_alert(e.to_string(), dispatch);
+                            }
+                        };
+                    }
+                    Err(e) => {
+                        validation_errors.set(Rc::new(RefCell::new(e)));
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <section class="grid h-full place-items-center">
+            <div class="w-full">
+                <h1 class="text-4xl xl:text-6xl text-center font-[600] text-primary mb-4">
+                    {"Forgot Password"}
+                </h1>
+                <h2 class="mb-4 text-lg text-center">
+                    {"Enter your email and we'll send you a reset link"}
+                </h2>
+
+                <form
+                    onsubmit={on_submit}
+                    class="w-full max-w-md p-8 mx-auto space-y-5 overflow-hidden shadow-lg rounded-2xl"
+                >
+                    <Input
+                        label="Email"
+                        name="email"
+                        input_type="email"
+                        input_ref={email_input_ref}
+                        handle_onchange={handle_email_input}
+                        errors={&*validation_errors}
+                        handle_on_input_blur={validate_input_on_blur.clone()}
+                    />
+                    <Button
+                        loading={store.loading}
+                        btn_type={"submit"}
+                        class="px-8 py-4"
+                    >
+                        {"Send Reset Link"}
+                    </Button>
+                    <span class="block">
+                        {"Remembered it?"} {" "}
+                        <Link<Route> to={Route::LoginPage} classes="text-info hover:underline">{ "Back to Login" }</Link<Route>>
+                    </span>
+                </form>
+            </div>
+        </section>
+    }
+}
@@ -1,29 +1,41 @@
 use yew::prelude::*;
+use yew_router::prelude::*;
 use yewdux::prelude::use_store;
-use crate::components::{song_card::SongCard};
+use crate::pages::search_page::SearchQuery;
+use crate::router::Route;
 use crate::store::Store;
 
 #[function_component(HomePage)]
 pub fn home_page() -> Html {
-    // Get the search results from the store
+    // Get the recent searches from the store
     let (store, _) = use_store::<Store>();
-    let search_results = store.search_results.clone();
+    let recent_searches = store.recent_searches.clone();
 
     html! {
         <section class="h-full">
             <div>
-                <h3 class="my-0 mb-2">{"Similar Songs"}</h3>
-                <small>{"Based on your search"}</small>
+                <h3 class="my-0 mb-2">{"Recent Searches"}</h3>
+                if recent_searches.is_empty() {
+                    <small>{"Search for songs, artists, albums and playlists above"}</small>
+                }
             </div>
 
-            <div class="grid grid-cols-1 md:grid-cols-2 xl:grid-cols-3 2xl:grid-cols-4 auto-cols-auto">
-                // Loop through results and display a SongCard for each
-                {for search_results.iter().map(|song| {
+            <ul class="flex flex-wrap gap-2 mt-2">
+                // Link each search to its results page
+                {for recent_searches.iter().map(|query| {
                     html! {
-                        <SongCard song={song.clone()} />
+                        <li key={query.clone()}>
+                            <Link<Route, SearchQuery>
+                                to={Route::SearchPage}
+                                query={Some(SearchQuery { q: query.clone() })}
+                                classes="block px-3 py-1 rounded-md bg-secondary hover:bg-primary/60"
+                            >
+                                {query}
+                            </Link<Route, SearchQuery>>
+                        </li>
                     }
                 })}
-            </div>
+            </ul>
         </section>
     }
-}
\ No newline at end of file
+}
@@ -2,11 +2,13 @@ use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
 
-use crate::api::user_api::api_login_user;
+use crate::api::client;
 use crate::components::ui::{button::Button, input::Input};
 use crate::router::{self, Route};
 use crate::store::{set_loading, set_show_alert, Store};
-use common::schema::user::LoginUserSchema;
+use common::schema::error::ErrorCode;
+use common::schema::two_factor::TwoFactorVerifySchema;
+use common::schema::user::{EmailRequestSchema, LoginResponse, LoginUserSchema};
 
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationErrors};
@@ -16,6 +18,9 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 use yewdux::prelude::*;
 
+/// The fields of the form, which server validation errors are shown for
+const FORM_FIELDS: &[&str] = &["email", "password"];
+
 fn get_input_callback(
     name: &'static str,
     cloned_form: UseStateHandle<LoginUserSchema>,
@@ -37,9 +42,13 @@ pub fn login_page() -> Html {
     let form = use_state(|| LoginUserSchema::default());
     let validation_errors = use_state(|| Rc::new(RefCell::new(ValidationErrors::new())));
     let navigator = use_navigator().unwrap();
+    // Set when the account has 2FA enabled and the password step succeeded
+    let challenge_token = use_state(|| None::<String>);
+    let code = use_state(String::new);
 
     let email_input_ref = NodeRef::default();
     let password_input_ref = NodeRef::default();
+    let code_input_ref = NodeRef::default();
 
     let validate_input_on_blur = {
         let cloned_form = form.clone();
@@ -86,6 +95,7 @@ pub fn login_page() -> Html {
         let cloned_validation_errors = validation_errors.clone();
         let store_dispatch = dispatch.clone();
         let cloned_navigator = navigator.clone();
+        let cloned_challenge_token = challenge_token.clone();
 
         let cloned_email_input_ref = email_input_ref.clone();
         let cloned_password_input_ref = password_input_ref.clone();
@@ -97,6 +107,7 @@ pub fn login_page() -> Html {
             let form = cloned_form.clone();
             let validation_errors = cloned_validation_errors.clone();
             let navigator = cloned_navigator.clone();
+            let challenge_token = cloned_challenge_token.clone();
 
             let email_input_ref = cloned_email_input_ref.clone();
             let password_input_ref = cloned_password_input_ref.clone();
@@ -113,15 +124,32 @@ pub fn login_page() -> Html {
                         email_input.set_value("");
                         password_input.set_value("");
 
-                        let form_json = serde_json::to_string(&form_data).unwrap();
-                        let res = api_login_user(&form_json).await;
+                        let res = client().login(&form_data).await;
                         match res {
-                            Ok(_) => {
+                            Ok(LoginResponse::Success(_)) => {
                                 set_loading(false, dispatch);
                                 navigator.push(&router::Route::ProfilePage);
                             }
+                            Ok(LoginResponse::TwoFactorRequired(challenge)) => {
+                                set_loading(false, dispatch);
+                                challenge_token.set(Some(challenge.challenge_token));
+                            }
+                            Err(e) if e.code == ErrorCode::EmailNotVerified => {
+                                // Send a fresh link in case the original one expired
+                                let _ = client()
+                                    .resend_verification(&EmailRequestSchema {
+                                        email: form_data.email.clone(),
+                                    })
+                                    .await;
+
+                                set_loading(false, dispatch.clone());
+                                set_show_alert(format!("{}. We've sent you a new verification link", e), dispatch);
+                            }
                             Err(e) => {
                                 set_loading(false, dispatch.clone());
+                                if e.code == ErrorCode::ValidationFailed {
+                                    validation_errors.set(Rc::new(RefCell::new(e.validation_errors(FORM_FIELDS))));
+                                }
                                 set_show_alert(e.to_string(), dispatch);
                             }
                         };
@@ -134,6 +162,96 @@ pub fn login_page() -> Html {
         })
     };
 
+    let handle_code_input = {
+        let cloned_code = code.clone();
+        Callback::from(move |value: String| cloned_code.set(value))
+    };
+
+    let on_submit_code = {
+        let cloned_code = code.clone();
+        let cloned_challenge_token = challenge_token.clone();
+        let store_dispatch = dispatch.clone();
+        let cloned_navigator = navigator.clone();
+        let cloned_code_input_ref = code_input_ref.clone();
+
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let dispatch = store_dispatch.clone();
+            let code = cloned_code.clone();
+            let challenge_token = cloned_challenge_token.clone();
+            let navigator = cloned_navigator.clone();
+            let code_input_ref = cloned_code_input_ref.clone();
+
+            spawn_local(async move {
+                let Some(token) = (*challenge_token).clone() else {
+                    return;
+                };
+
+                set_loading(true, dispatch.clone());
+
+                let verify = TwoFactorVerifySchema {
+                    challenge_token: token,
+                    code: code.trim().to_string(),
+                };
+
+                if let Some(code_input) = code_input_ref.cast::<HtmlInputElement>() {
+                    code_input.set_value("");
+                }
+
+                match client().verify_two_factor(&verify).await {
+                    Ok(_) => {
+                        set_loading(false, dispatch);
+                        navigator.push(&router::Route::ProfilePage);
+                    }
+                    Err(e) => {
+                        // An expired challenge means starting over from the password step
+                        if e.code == ErrorCode::ChallengeExpired {
+                            challenge_token.set(None);
+                        }
+
+                        set_loading(false, dispatch.clone());
+                        set_show_alert(e.to_string(), dispatch);
+                    }
+                }
+            });
+        })
+    };
+
+    if challenge_token.is_some() {
+        return html! {
+            <section class="grid h-full place-items-center">
+                <div class="w-full">
+                    <h1 class="text-4xl xl:text-6xl text-center font-[600] text-primary mb-4">
+                        {"Two-Factor Authentication"}
+                    </h1>
+                    <h2 class="mb-4 text-lg text-center">
+                        {"Enter the code from your authenticator app, or one of your recovery codes"}
+                    </h2>
+
+                    <form
+                        onsubmit={on_submit_code}
+                        class="w-full max-w-md p-8 mx-auto space-y-5 overflow-hidden shadow-lg rounded-2xl"
+                    >
+                        <Input
+                            label="Code"
+                            name="code"
+                            input_ref={code_input_ref}
+                            handle_onchange={handle_code_input}
+                        />
+                        <Button
+                            loading={store.loading}
+                            btn_type={"submit"}
+                            class="px-8 py-4"
+                        >
+                            {"Verify"}
+                        </Button>
+                    </form>
+                </div>
+            </section>
+        };
+    }
+
     html! {
         <section class="grid h-full place-items-center">
             <div class="w-full">
@@ -168,9 +286,9 @@ pub fn login_page() -> Html {
                     />
 
                     <div class="text-right">
-                        <a href="#">
+                        <Link<Route> to={Route::ForgotPasswordPage} classes="hover:underline">
                             {"Forgot Password?"}
-                        </a>
+                        </Link<Route>>
                     </div>
                     <Button
                         loading={store.loading}
@@ -1,4 +1,15 @@
 pub mod home_page;
 pub mod login_page;
 pub mod profile_page;
-pub mod register_page;
\ No newline at end of file
+pub mod register_page;
+pub mod verify_email_page;
+pub mod forgot_password_page;
+pub mod reset_password_page;
+pub mod confirm_email_change_page;
+pub mod public_profile_page;
+pub mod feed_page;
+pub mod song_page;
+pub mod artist_page;
+pub mod album_page;
+pub mod recommendations_page;
+pub mod search_page;
\ No newline at end of file
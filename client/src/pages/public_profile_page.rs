@@ -0,0 +1,182 @@
+use crate::api::client;
+use crate::components::ui::button::Button;
+use crate::router::Route;
+use crate::store::{set_loading, set_show_alert, Store};
+use common::schema::profile::PublicProfile;
+
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct PublicProfilePageProps {
+    pub username: String,
+}
+
+#[derive(Clone, PartialEq)]
+enum ProfileStatus {
+    Loading,
+    Loaded(PublicProfile),
+    Failed(String),
+}
+
+/// Anyone's public profile at `/u/{username}`, viewable without logging in
+#[function_component(PublicProfilePage)]
+pub fn public_profile_page(props: &PublicProfilePageProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let status = use_state(|| ProfileStatus::Loading);
+    // `None` when logged out, or while the follow status is loading
+    let following = use_state(|| None::<bool>);
+    let logged_in = store.auth_user.is_some();
+    let is_own_profile = store.auth_user.as_ref()
+        .is_some_and(|user| user.username.eq_ignore_ascii_case(&props.username));
+
+    {
+        let status = status.clone();
+        let following = following.clone();
+        let dispatch = dispatch.clone();
+        use_effect_with((props.username.clone(), logged_in), move |(username, logged_in)| {
+            let username = username.clone();
+            let logged_in = *logged_in;
+            following.set(None);
+            spawn_local(async move {
+                set_loading(true, dispatch.clone());
+                match client().public_profile(&username).await {
+                    Ok(profile) => status.set(ProfileStatus::Loaded(profile)),
+                    Err(e) => status.set(ProfileStatus::Failed(e.to_string())),
+                }
+                if logged_in {
+                    if let Ok(follow_status) = client().follow_status(&username).await {
+                        following.set(Some(follow_status.following));
+                    }
+                }
+                set_loading(false, dispatch);
+            });
+        });
+    }
+
+    let toggle_follow = {
+        let status = status.clone();
+        let following = following.clone();
+        let username = props.username.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(is_following) = *following else {
+                return;
+            };
+
+            let status = status.clone();
+            let following = following.clone();
+            let username = username.clone();
+            let dispatch = dispatch.clone();
+            spawn_local(async move {
+                let response = if is_following {
+                    client().unfollow(&username).await
+                } else {
+                    client().follow(&username).await
+                };
+
+                match response {
+                    Ok(follow_status) => {
+                        following.set(Some(follow_status.following));
+                        if let ProfileStatus::Loaded(profile) = &*status {
+                            let mut profile = profile.clone();
+                            profile.follower_count = follow_status.follower_count;
+                            status.set(ProfileStatus::Loaded(profile));
+                        }
+                    }
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <section class="min-h-screen pt-20 bg-ct-blue-600">
+            <div class="max-w-4xl mx-auto bg-ct-dark-100 rounded-md min-h-[20rem] flex justify-center items-center">
+                {match &*status {
+                    ProfileStatus::Loading => html! {
+                        <p class="mb-4">{"Loading..."}</p>
+                    },
+                    ProfileStatus::Failed(message) => html! {
+                        <p class="mb-4">
+                            {format!("{}. ", message)}
+                            <Link<Route> to={Route::HomePage} classes="text-info hover:underline">{ "Back to Home" }</Link<Route>>
+                        </p>
+                    },
+                    ProfileStatus::Loaded(profile) => html! {
+                        <div>
+                            <div class="flex items-center gap-4">
+                                if !profile.photo.is_empty() {
+                                    <img src={profile.photo.clone()} alt="Avatar" class="w-24 h-24 rounded-full" />
+                                }
+                                <div>
+                                    <p class="text-5xl font-semibold">{&profile.name}</p>
+                                    <p>{format!("@{}", profile.username)}</p>
+                                    <p class="text-sm">{format!("Member since {}", profile.member_since.format("%B %Y"))}</p>
+                                    <p class="text-sm">{format!("{} followers · {} following", profile.follower_count, profile.following_count)}</p>
+                                </div>
+                                if !is_own_profile {
+                                    if let Some(is_following) = *following {
+                                        <Button onclick={toggle_follow.clone()} class="px-4 py-2">
+                                            {if is_following { "Unfollow" } else { "Follow" }}
+                                        </Button>
+                                    }
+                                }
+                            </div>
+
+                            if profile.restricted {
+                                <p class="mt-8">{"This profile is private."}</p>
+                            }
+
+                            if let Some(top_artists) = &profile.top_artists {
+                                <div class="mt-8">
+                                    <p class="text-2xl font-semibold">{"Top Artists"}</p>
+                                    if top_artists.is_empty() {
+                                        <p>{"No artists yet."}</p>
+                                    }
+                                    <ol class="list-decimal list-inside">
+                                        { for top_artists.iter().map(|artist| html! { <li>{&artist.name}</li> }) }
+                                    </ol>
+                                </div>
+                            }
+
+                            if let Some(recent_plays) = &profile.recent_plays {
+                                <div class="mt-8">
+                                    <p class="text-2xl font-semibold">{"Recently Played"}</p>
+                                    if recent_plays.is_empty() {
+                                        <p>{"Nothing played yet."}</p>
+                                    }
+                                    <ul>
+                                        { for recent_plays.iter().map(|play| html! {
+                                            <li>{format!("{} · {}", play.title, play.played_at.format("%b %e, %Y"))}</li>
+                                        }) }
+                                    </ul>
+                                </div>
+                            }
+
+                            if !profile.restricted {
+                                <div class="mt-8">
+                                    <p class="text-2xl font-semibold">{"Playlists"}</p>
+                                    if profile.playlists.is_empty() {
+                                        <p>{"No public playlists yet."}</p>
+                                    }
+                                    <ul class="space-y-2">
+                                        { for profile.playlists.iter().map(|playlist| html! {
+                                            <li>
+                                                <p class="font-semibold">{format!("{} ({} songs)", playlist.name, playlist.song_count)}</p>
+                                                if let Some(description) = &playlist.description {
+                                                    <p class="text-sm">{description}</p>
+                                                }
+                                            </li>
+                                        }) }
+                                    </ul>
+                                </div>
+                            }
+                        </div>
+                    },
+                }}
+            </div>
+        </section>
+    }
+}
@@ -0,0 +1,79 @@
+use crate::api::client;
+use crate::components::{recommendation_list::RecommendationList, ui::button::Button};
+use crate::router::Route;
+use crate::store::{set_loading, set_show_alert, Store};
+use common::schema::recommendation::Recommendation;
+
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+/// How many songs are recommended at a time
+const BATCH_SIZE: i64 = 20;
+
+/// Songs recommended to the logged in user. Each new batch takes the feedback given on the last one into account.
+#[function_component(RecommendationsPage)]
+pub fn recommendations_page() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let navigator = use_navigator().unwrap();
+    let recommendations = use_state(Vec::<Recommendation>::new);
+    let loaded = use_state(|| false);
+
+    let load_batch = {
+        let recommendations = recommendations.clone();
+        let loaded = loaded.clone();
+        Callback::from(move |_: ()| {
+            let recommendations = recommendations.clone();
+            let loaded = loaded.clone();
+            let dispatch = dispatch.clone();
+            let navigator = navigator.clone();
+            spawn_local(async move {
+                set_loading(true, dispatch.clone());
+                match client().recommendations(BATCH_SIZE).await {
+                    Ok(batch) => {
+                        set_loading(false, dispatch);
+                        recommendations.set(batch);
+                        loaded.set(true);
+                    }
+                    Err(e) => {
+                        set_loading(false, dispatch.clone());
+                        set_show_alert(e.to_string(), dispatch);
+                        if !*loaded {
+                            navigator.push(&Route::LoginPage);
+                        }
+                    }
+                }
+            });
+        })
+    };
+
+    {
+        let load_batch = load_batch.clone();
+        use_effect_with((), move |_| load_batch.emit(()));
+    }
+
+    let on_refresh = Callback::from(move |_: MouseEvent| load_batch.emit(()));
+
+    html! {
+        <section class="min-h-screen pt-20 bg-ct-blue-600">
+            <div class="max-w-4xl mx-auto bg-ct-dark-100 rounded-md min-h-[20rem] p-8">
+                <div class="flex items-center justify-between">
+                    <p class="text-5xl font-semibold">{"For you"}</p>
+                    if *loaded {
+                        <Button onclick={on_refresh} class="px-4 py-2">{"New recommendations"}</Button>
+                    }
+                </div>
+                if !*loaded {
+                    <p class="mt-8">{"Loading..."}</p>
+                } else if recommendations.is_empty() {
+                    <p class="mt-8">{"Nothing to recommend yet. Like and play some songs to get recommendations."}</p>
+                } else {
+                    <div class="mt-8">
+                        <RecommendationList recommendations={(*recommendations).clone()} />
+                    </div>
+                }
+            </div>
+        </section>
+    }
+}
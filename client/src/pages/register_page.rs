@@ -2,10 +2,11 @@ use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
 
-use crate::api::user_api::api_register_user;
+use crate::api::client;
 use crate::components::ui::{input::Input, button::Button, select::Select};
 use crate::router::{self, Route};
 use crate::store::{set_loading, set_show_alert, Store};
+use common::schema::error::ErrorCode;
 use common::schema::platform::get_platform_select_items;
 use common::schema::user::SignupUserSchema;
 
@@ -16,6 +17,9 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 use yewdux::prelude::*;
 
+/// The fields of the form, which server validation errors are shown for
+const FORM_FIELDS: &[&str] = &["name", "username", "email", "preferred_platform", "password", "password_confirm"];
+
 fn get_input_callback(
     name: &'static str,
     cloned_form: UseStateHandle<SignupUserSchema>,
@@ -126,7 +130,6 @@ pub fn register_page() -> Html {
                 match form.validate() {
                     Ok(_) => {
                         let form_data = form.deref().clone();
-                        let form_json = serde_json::to_string(&form_data).unwrap();
                         set_loading(true, dispatch.clone());
 
                         let name_input = name_input_ref.cast::<HtmlInputElement>().unwrap();
@@ -147,7 +150,7 @@ pub fn register_page() -> Html {
                         password_input.set_value("");
                         password_confirm_input.set_value("");
 
-                        let res = api_register_user(&form_json).await;
+                        let res = client().register(&form_data).await;
                         match res {
                             Ok(_) => {
                                 set_loading(false, dispatch.clone());
@@ -159,6 +162,9 @@ pub fn register_page() -> Html {
                             }
                             Err(e) => {
                                 set_loading(false, dispatch.clone());
+                                if e.code == ErrorCode::ValidationFailed {
+                                    validation_errors.set(Rc::new(RefCell::new(e.validation_errors(FORM_FIELDS))));
+                                }
                                 set_show_alert(e.to_string(), dispatch);
                             }
                         };
@@ -0,0 +1,205 @@
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use crate::api::client;
+use crate::components::ui::{button::Button, input::Input};
+use crate::router::{self, Route};
+use crate::store::{set_loading, set_show_alert, Store};
+use common::schema::user::ResetPasswordSchema;
+
+use serde::Deserialize;
+use validator::{Validate, ValidationErrors};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+/// The `?token=` query parameter sent in account emails
+#[derive(Debug, Deserialize)]
+pub struct TokenQuery {
+    pub token: String,
+}
+
+fn get_input_callback(
+    name: &'static str,
+    cloned_form: UseStateHandle<ResetPasswordSchema>,
+) -> Callback<String> {
+    Callback::from(move |value| {
+        let mut data = cloned_form.deref().clone();
+        match name {
+            "password" => data.password = value,
+            "password_confirm" => data.password_confirm = value,
+            _ => (),
+        }
+        cloned_form.set(data);
+    })
+}
+
+#[function_component(ResetPasswordPage)]
+pub fn reset_password_page() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let token = use_location()
+        .and_then(|location| location.query::<TokenQuery>().ok())
+        .map(|query| query.token)
+        .unwrap_or_default();
+    let form = use_state(|| ResetPasswordSchema {
+        token,
+        ..ResetPasswordSchema::default()
+    });
+    let validation_errors = use_state(|| Rc::new(RefCell::new(ValidationErrors::new())));
+    let navigator = use_navigator().unwrap();
+
+    let password_input_ref = NodeRef::default();
+    let password_confirm_input_ref = NodeRef::default();
+
+    let validate_input_on_blur = {
+        let cloned_form = form.clone();
+        let cloned_validation_errors = validation_errors.clone();
+        Callback::from(move |(name, value): (String, String)| {
+            let mut data = cloned_form.deref().clone();
+            match name.as_str() {
+                "password" => data.password = value,
+                "password_confirm" => data.password_confirm = value,
+                _ => (),
+            }
+            cloned_form.set(data);
+
+            match cloned_form.validate() {
+                Ok(_) => {
+                    cloned_validation_errors
+                        .borrow_mut()
+                        .errors_mut()
+                        .remove(name.as_str());
+                }
+                Err(errors) => {
+                    cloned_validation_errors
+                        .borrow_mut()
+                        .errors_mut()
+                        .retain(|key, _| key != &name);
+                    for (field_name, error) in errors.errors() {
+                        if field_name == &name {
+                            cloned_validation_errors
+                                .borrow_mut()
+                                .errors_mut()
+                                .insert(field_name.clone(), error.clone());
+                        }
+                    }
+                }
+            }
+        })
+    };
+
+    let handle_password_input = get_input_callback("password", form.clone());
+    let handle_password_confirm_input = get_input_callback("password_confirm", form.clone());
+
+    let on_submit = {
+        let cloned_form = form.clone();
+        let cloned_validation_errors = validation_errors.clone();
+        let store_dispatch = dispatch.clone();
+        let cloned_navigator = navigator.clone();
+
+        let cloned_password_input_ref = password_input_ref.clone();
+        let cloned_password_confirm_input_ref = password_confirm_input_ref.clone();
+
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+
+            let dispatch = store_dispatch.clone();
+            let form = cloned_form.clone();
+            let validation_errors = cloned_validation_errors.clone();
+            let navigator = cloned_navigator.clone();
+
+            let password_input_ref = cloned_password_input_ref.clone();
+            let password_confirm_input_ref = cloned_password_confirm_input_ref.clone();
+
+            spawn_local(async move {
+                match form.validate() {
+                    Ok(_) => {
+                        let form_data = form.deref().clone();
+                        set_loading(true, dispatch.clone());
+
+                        let password_input = password_input_ref.cast::<HtmlInputElement>().unwrap();
+                        let password_confirm_input = password_confirm_input_ref
+                            .cast::<HtmlInputElement>()
+                            .unwrap();
+
+                        password_input.set_value("");
+                        password_confirm_input.set_value("");
+
+                        let res = client().reset_password(&form_data).await;
+                        match res {
+                            Ok(_) => {
+                                set_loading(false, dispatch.clone());
+                                set_show_alert(
+                                    "Password reset successfully, you can now log in".to_string(),
+                                    dispatch,
+                                );
+                                navigator.push(&router::Route::LoginPage);
+                            }
+                            Err(e) => {
+                                set_loading(false, dispatch.clone());
+                                set_show_alert(e.to_string(), dispatch);
+                            }
+                        };
+                    }
+                    Err(e) => {
+                        validation_errors.set(Rc::new(RefCell::new(e)));
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <section class="grid h-full place-items-center">
+            <div class="w-full">
+                <h1 class="text-4xl xl:text-6xl text-center font-[600] text-primary mb-4">
+                    {"Reset Password"}
+                </h1>
+                <h2 class="mb-4 text-lg text-center">
+                    {"Choose a new password for your account"}
+                </h2>
+
+                if form.token.is_empty() {
+                    <p class="text-center">
+                        {"This reset link is invalid. "}
+                        <Link<Route> to={Route::ForgotPasswordPage} classes="text-info hover:underline">{ "Request a new one" }</Link<Route>>
+                    </p>
+                } else {
+                    <form
+                        onsubmit={on_submit}
+                        class="w-full max-w-md p-8 mx-auto space-y-5 overflow-hidden shadow-lg rounded-2xl"
+                    >
+                        <Input
+                            label="New Password"
+                            name="password"
+                            input_type="password"
+                            input_ref={password_input_ref}
+                            handle_onchange={handle_password_input}
+                            errors={&*validation_errors}
+                            handle_on_input_blur={validate_input_on_blur.clone()}
+                        />
+                        <Input
+                            label="Confirm New Password"
+                            name="password_confirm"
+                            input_type="password"
+                            input_ref={password_confirm_input_ref}
+                            handle_onchange={handle_password_confirm_input}
+                            errors={&*validation_errors}
+                            handle_on_input_blur={validate_input_on_blur.clone()}
+                        />
+                        <Button
+                            loading={store.loading}
+                            btn_type={"submit"}
+                            class="px-8 py-4"
+                        >
+                            {"Reset Password"}
+                        </Button>
+                    </form>
+                }
+            </div>
+        </section>
+    }
+}
@@ -0,0 +1,149 @@
+use crate::api::client;
+use crate::components::song_list::SongList;
+use crate::router::Route;
+use crate::store::{set_loading, set_search_input, Store};
+use common::schema::search::SearchResults;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+/// How many matches of each kind are shown
+const RESULTS_LIMIT: i64 = 20;
+
+/// The `?q=` query parameter of the search page
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+}
+
+#[derive(Clone, PartialEq)]
+enum SearchStatus {
+    Loading,
+    Loaded(SearchResults),
+    Failed(String),
+}
+
+/// Search results at `/search?q=`, grouped into songs, artists, albums and playlists
+#[function_component(SearchPage)]
+pub fn search_page() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let query = use_location()
+        .and_then(|location| location.query::<SearchQuery>().ok())
+        .map(|query| query.q.trim().to_string())
+        .unwrap_or_default();
+    let status = use_state(|| SearchStatus::Loading);
+
+    {
+        let status = status.clone();
+        use_effect_with(query.clone(), move |query| {
+            let query = query.clone();
+            // Followed links show what they searched for in the header too
+            set_search_input(query.clone(), dispatch.clone());
+
+            if query.is_empty() {
+                status.set(SearchStatus::Loaded(SearchResults::default()));
+            } else {
+                status.set(SearchStatus::Loading);
+                spawn_local(async move {
+                    set_loading(true, dispatch.clone());
+                    match client().search(&query, RESULTS_LIMIT).await {
+                        Ok(results) => status.set(SearchStatus::Loaded(results)),
+                        Err(e) => status.set(SearchStatus::Failed(e.to_string())),
+                    }
+                    set_loading(false, dispatch);
+                });
+            }
+        });
+    }
+
+    html! {
+        <section class="min-h-screen pt-20 bg-ct-blue-600">
+            <div class="max-w-4xl mx-auto bg-ct-dark-100 rounded-md min-h-[20rem] p-8">
+                if query.is_empty() {
+                    <p>{"Search for songs, artists, albums and playlists."}</p>
+                } else {
+                    <h2 class="text-3xl font-semibold">{format!("Results for “{}”", query)}</h2>
+                    {match &*status {
+                        SearchStatus::Loading => html! {
+                            <p class="mt-4">{"Loading..."}</p>
+                        },
+                        SearchStatus::Failed(message) => html! {
+                            <p class="mt-4">
+                                {format!("{}. ", message)}
+                                <Link<Route> to={Route::HomePage} classes="text-info hover:underline">{ "Back to Home" }</Link<Route>>
+                            </p>
+                        },
+                        SearchStatus::Loaded(results) if results.is_empty() => html! {
+                            <p class="mt-4">{"Nothing matched your search."}</p>
+                        },
+                        SearchStatus::Loaded(results) => html! {
+                            <div>
+                                if !results.songs.is_empty() {
+                                    <h3 class="mt-8 text-2xl font-semibold">{"Songs"}</h3>
+                                    <SongList songs={results.songs.clone()} />
+                                }
+
+                                if !results.artists.is_empty() {
+                                    <h3 class="mt-8 text-2xl font-semibold">{"Artists"}</h3>
+                                    <ul class="mt-2 space-y-1">
+                                        { for results.artists.iter().map(|artist| html! {
+                                            <li key={artist.artist_id.to_string()}>
+                                                <Link<Route> to={Route::ArtistPage { id: artist.artist_id }} classes="font-semibold hover:underline">
+                                                    {&artist.name}
+                                                </Link<Route>>
+                                            </li>
+                                        }) }
+                                    </ul>
+                                }
+
+                                if !results.albums.is_empty() {
+                                    <h3 class="mt-8 text-2xl font-semibold">{"Albums"}</h3>
+                                    <ul class="grid grid-cols-2 gap-4 mt-2 md:grid-cols-4">
+                                        { for results.albums.iter().map(|album| html! {
+                                            <li key={album.album_id.to_string()}>
+                                                <Link<Route> to={Route::AlbumPage { id: album.album_id }} classes="block hover:underline">
+                                                    if let Some(cover) = &album.cover {
+                                                        <img src={cover.clone()} alt="" class="w-full rounded-md aspect-square" />
+                                                    }
+                                                    <p class="mt-1 font-semibold truncate">{&album.title}</p>
+                                                </Link<Route>>
+                                                <p class="text-sm truncate text-secondary">
+                                                    <Link<Route> to={Route::ArtistPage { id: album.artist_id }} classes="hover:underline">{&album.artist}</Link<Route>>
+                                                </p>
+                                            </li>
+                                        }) }
+                                    </ul>
+                                }
+
+                                if !results.playlists.is_empty() {
+                                    <h3 class="mt-8 text-2xl font-semibold">{"Playlists"}</h3>
+                                    <ul class="mt-2 space-y-1">
+                                        { for results.playlists.iter().map(|playlist| html! {
+                                            <li key={playlist.playlist_id.to_string()}>
+                                                <span class="font-semibold">{&playlist.name}</span>
+                                                <span class="text-sm text-secondary">
+                                                    {" by "}
+                                                    <Link<Route>
+                                                        to={Route::PublicProfilePage { username: playlist.owner.username.clone() }}
+                                                        classes="hover:underline"
+                                                    >
+                                                        {format!("@{}", playlist.owner.username)}
+                                                    </Link<Route>>
+                                                    {format!(" · {} songs", playlist.song_count)}
+                                                </span>
+                                            </li>
+                                        }) }
+                                    </ul>
+                                }
+                            </div>
+                        },
+                    }}
+                }
+            </div>
+        </section>
+    }
+}
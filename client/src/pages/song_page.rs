@@ -0,0 +1,174 @@
+use crate::api::client;
+use crate::components::{player::format_time, song_list::{playable_song, SongList}};
+use crate::router::Route;
+use crate::store::{enqueue_song, play_song, set_loading, Player, Store};
+use common::schema::song::{SongDetails, SongFeatures};
+
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+const PITCH_CLASSES: [&str; 12] = ["C", "C♯", "D", "D♯", "E", "F", "F♯", "G", "G♯", "A", "A♯", "B"];
+
+#[derive(Properties, PartialEq)]
+pub struct SongPageProps {
+    pub id: Uuid,
+}
+
+#[derive(Clone, PartialEq)]
+enum SongStatus {
+    Loading,
+    Loaded(SongDetails),
+    Failed(String),
+}
+
+/// The features we know of, as label and value
+fn feature_rows(features: &SongFeatures) -> Vec<(&'static str, String)> {
+    [
+        ("Tempo", features.tempo.map(|tempo| format!("{:.0} BPM", tempo))),
+        ("Key", features.key.and_then(|key| PITCH_CLASSES.get(key as usize)).map(|key| key.to_string())),
+        ("Time signature", features.time_signature.map(|beats| format!("{}/4", beats))),
+        ("Loudness", features.loudness.map(|loudness| format!("{:.1} dB", loudness))),
+        ("Speechiness", features.speechiness.map(|speechiness| format!("{:.0}%", speechiness * 100.0))),
+        ("Danceability", features.danceability.map(|danceability| format!("{:.0}%", danceability * 100.0))),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| value.map(|value| (label, value)))
+    .collect()
+}
+
+/// A song at `/song/{id}`, with its features, where to listen to it and similar songs
+#[function_component(SongPage)]
+pub fn song_page(props: &SongPageProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let player_dispatch = use_dispatch::<Player>();
+    let status = use_state(|| SongStatus::Loading);
+    let logged_in = store.auth_user.is_some();
+
+    {
+        let status = status.clone();
+        use_effect_with(props.id, move |id| {
+            let id = *id;
+            status.set(SongStatus::Loading);
+            spawn_local(async move {
+                set_loading(true, dispatch.clone());
+                match client().song(id).await {
+                    Ok(song) => status.set(SongStatus::Loaded(song)),
+                    Err(e) => status.set(SongStatus::Failed(e.to_string())),
+                }
+                set_loading(false, dispatch);
+            });
+        });
+    }
+
+    html! {
+        <section class="min-h-screen pt-20 bg-ct-blue-600">
+            <div class="max-w-4xl mx-auto bg-ct-dark-100 rounded-md min-h-[20rem] p-8">
+                {match &*status {
+                    SongStatus::Loading => html! {
+                        <p>{"Loading..."}</p>
+                    },
+                    SongStatus::Failed(message) => html! {
+                        <p>
+                            {format!("{}. ", message)}
+                            <Link<Route> to={Route::HomePage} classes="text-info hover:underline">{ "Back to Home" }</Link<Route>>
+                        </p>
+                    },
+                    SongStatus::Loaded(details) => {
+                        let song = &details.song;
+                        let to_play = playable_song(song, logged_in);
+                        let handle_play = {
+                            let player_dispatch = player_dispatch.clone();
+                            let to_play = to_play.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                if let Some(song) = &to_play {
+                                    play_song(song.clone(), player_dispatch.clone());
+                                }
+                            })
+                        };
+                        let handle_queue = {
+                            let player_dispatch = player_dispatch.clone();
+                            let to_play = to_play.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                if let Some(song) = &to_play {
+                                    enqueue_song(song.clone(), player_dispatch.clone());
+                                }
+                            })
+                        };
+                        let features = feature_rows(&details.features);
+
+                        html! {
+                            <div>
+                                <div class="flex items-center gap-6">
+                                    if let Some(cover) = &song.cover {
+                                        <img src={cover.clone()} alt="Album cover" class="w-40 h-40 rounded-md" />
+                                    }
+                                    <div>
+                                        <p class="text-5xl font-semibold">{&song.title}</p>
+                                        <p class="mt-2">
+                                            <Link<Route> to={Route::ArtistPage { id: song.artist_id }} classes="font-semibold text-info hover:underline">
+                                                {&song.artist}
+                                            </Link<Route>>
+                                            {" · "}
+                                            <Link<Route> to={Route::AlbumPage { id: song.album_id }} classes="text-info hover:underline">
+                                                {&song.album}
+                                            </Link<Route>>
+                                        </p>
+                                        <p class="text-sm">
+                                            {format!("{} · {}", details.genre, format_time(song.duration as f64))}
+                                            if let Some(release_date) = details.release_date {
+                                                {format!(" · {}", release_date.format("%Y"))}
+                                            }
+                                        </p>
+                                        if to_play.is_some() {
+                                            <div class="flex gap-2 mt-4">
+                                                <button class="px-4 py-1 text-sm font-semibold rounded-md bg-primary" onclick={handle_play}>
+                                                    {if logged_in { "Play" } else { "Play preview" }}
+                                                </button>
+                                                <button class="px-4 py-1 text-sm font-semibold rounded-md bg-secondary" onclick={handle_queue}>
+                                                    {"Queue"}
+                                                </button>
+                                            </div>
+                                        }
+                                    </div>
+                                </div>
+
+                                if !features.is_empty() {
+                                    <h3 class="mt-8 text-2xl font-semibold">{"Audio features"}</h3>
+                                    <dl class="grid grid-cols-2 mt-2 md:grid-cols-3 gap-x-8 gap-y-2">
+                                        { for features.into_iter().map(|(label, value)| html! {
+                                            <div>
+                                                <dt class="text-sm text-secondary">{label}</dt>
+                                                <dd class="font-semibold">{value}</dd>
+                                            </div>
+                                        }) }
+                                    </dl>
+                                }
+
+                                if !details.links.is_empty() {
+                                    <h3 class="mt-8 text-2xl font-semibold">{"Listen on"}</h3>
+                                    <ul class="flex flex-wrap gap-4 mt-2">
+                                        { for details.links.iter().map(|link| html! {
+                                            <li>
+                                                <a href={link.url.clone()} target="_blank" rel="noopener noreferrer" class="text-info hover:underline">
+                                                    {link.platform.as_ref().map_or_else(|| link.url.clone(), |platform| platform.to_string())}
+                                                </a>
+                                            </li>
+                                        }) }
+                                    </ul>
+                                }
+
+                                if !details.similar.is_empty() {
+                                    <h3 class="mt-8 text-2xl font-semibold">{"Similar songs"}</h3>
+                                    <SongList songs={details.similar.clone()} />
+                                }
+                            </div>
+                        }
+                    }
+                }}
+            </div>
+        </section>
+    }
+}
@@ -0,0 +1,74 @@
+use crate::api::client;
+use crate::pages::reset_password_page::TokenQuery;
+use crate::router::Route;
+use crate::store::{set_loading, Store};
+use common::schema::user::VerifyEmailSchema;
+
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+#[derive(Clone, PartialEq)]
+enum VerificationStatus {
+    Pending,
+    Verified,
+    Failed(String),
+}
+
+#[function_component(VerifyEmailPage)]
+pub fn verify_email_page() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let status = use_state(|| VerificationStatus::Pending);
+    let token = use_location()
+        .and_then(|location| location.query::<TokenQuery>().ok())
+        .map(|query| query.token)
+        .unwrap_or_default();
+
+    {
+        let status = status.clone();
+        use_effect_with(token, move |token| {
+            let token = token.clone();
+            spawn_local(async move {
+                if token.is_empty() {
+                    status.set(VerificationStatus::Failed("This verification link is invalid".to_string()));
+                    return;
+                }
+
+                set_loading(true, dispatch.clone());
+                match client().verify_email(&VerifyEmailSchema { token }).await {
+                    Ok(_) => status.set(VerificationStatus::Verified),
+                    Err(e) => status.set(VerificationStatus::Failed(e.to_string())),
+                }
+                set_loading(false, dispatch);
+            });
+        });
+    }
+
+    html! {
+        <section class="grid h-full place-items-center">
+            <div class="w-full text-center">
+                <h1 class="text-4xl xl:text-6xl text-center font-[600] text-primary mb-4">
+                    {"Email Verification"}
+                </h1>
+                {match &*status {
+                    VerificationStatus::Pending => html! {
+                        <p class="mb-4">{"Verifying your email..."}</p>
+                    },
+                    VerificationStatus::Verified => html! {
+                        <p class="mb-4">
+                            {"Your email has been verified. "}
+                            <Link<Route> to={Route::LoginPage} classes="text-info hover:underline">{ "Login Here" }</Link<Route>>
+                        </p>
+                    },
+                    VerificationStatus::Failed(message) => html! {
+                        <p class="mb-4">
+                            {format!("{}. ", message)}
+                            <Link<Route> to={Route::LoginPage} classes="text-info hover:underline">{ "Back to Login" }</Link<Route>>
+                        </p>
+                    },
+                }}
+            </div>
+        </section>
+    }
+}
@@ -2,27 +2,65 @@ use yew::prelude::*;
 use yew_router::prelude::*;
 
 use super::pages::{
-    home_page::HomePage, login_page::LoginPage, profile_page::ProfilePage,
-    register_page::RegisterPage,
+    forgot_password_page::ForgotPasswordPage, home_page::HomePage, login_page::LoginPage,
+    profile_page::ProfilePage, register_page::RegisterPage,
+    reset_password_page::ResetPasswordPage, verify_email_page::VerifyEmailPage,
+    confirm_email_change_page::ConfirmEmailChangePage, public_profile_page::PublicProfilePage,
+    feed_page::FeedPage, song_page::SongPage, artist_page::ArtistPage, album_page::AlbumPage,
+    recommendations_page::RecommendationsPage, search_page::SearchPage,
 };
+use uuid::Uuid;
 
 #[derive(Clone, Routable, PartialEq)]
 pub enum Route {
     #[at("/")]
     HomePage,
+    #[at("/feed")]
+    FeedPage,
+    #[at("/recommendations")]
+    RecommendationsPage,
+    #[at("/search")]
+    SearchPage,
     #[at("/register")]
     RegisterPage,
     #[at("/login")]
     LoginPage,
     #[at("/profile")]
     ProfilePage,
+    #[at("/verify-email")]
+    VerifyEmailPage,
+    #[at("/forgot-password")]
+    ForgotPasswordPage,
+    #[at("/reset-password")]
+    ResetPasswordPage,
+    #[at("/confirm-email-change")]
+    ConfirmEmailChangePage,
+    #[at("/u/:username")]
+    PublicProfilePage { username: String },
+    #[at("/song/:id")]
+    SongPage { id: Uuid },
+    #[at("/artist/:id")]
+    ArtistPage { id: Uuid },
+    #[at("/album/:id")]
+    AlbumPage { id: Uuid },
 }
 
 pub fn switch(routes: Route) -> Html {
     match routes {
         Route::HomePage => html! {<HomePage/> },
+        Route::FeedPage => html! {<FeedPage/> },
+        Route::RecommendationsPage => html! {<RecommendationsPage/> },
+        Route::SearchPage => html! {<SearchPage/> },
         Route::RegisterPage => html! {<RegisterPage/> },
         Route::LoginPage => html! {<LoginPage/> },
         Route::ProfilePage => html! {<ProfilePage/> },
+        Route::VerifyEmailPage => html! {<VerifyEmailPage/> },
+        Route::ForgotPasswordPage => html! {<ForgotPasswordPage/> },
+        Route::ResetPasswordPage => html! {<ResetPasswordPage/> },
+        Route::ConfirmEmailChangePage => html! {<ConfirmEmailChangePage/> },
+        Route::PublicProfilePage { username } => html! {<PublicProfilePage username={username} /> },
+        Route::SongPage { id } => html! {<SongPage id={id} /> },
+        Route::ArtistPage { id } => html! {<ArtistPage id={id} /> },
+        Route::AlbumPage { id } => html! {<AlbumPage id={id} /> },
     }
 }
\ No newline at end of file
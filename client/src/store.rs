@@ -18,9 +18,14 @@ pub struct Store {
     pub access_token: Option<String>,
     pub refresh_token: Option<String>,
     pub search_input: String,
-    pub search_results: Vec<Song>
+    /// What the user searched for, most recent first and without repeats
+    #[serde(default)]
+    pub recent_searches: Vec<String>,
 }
 
+/// How many searches are remembered in `recent_searches`
+const MAX_RECENT_SEARCHES: usize = 10;
+
 pub fn set_feedback(feedback: Feedback, dispatch: Dispatch<Store>) {
     dispatch.reduce_mut(move |store| {
         store.feedbacks.insert(0, feedback);
@@ -84,20 +89,252 @@ pub fn set_search_input(input: String, dispatch: Dispatch<Store>) {
     })
 }
 
-pub fn set_search_results(results: Vec<Song>, dispatch: Dispatch<Store>) {
+pub fn clear_search_input(dispatch: Dispatch<Store>) {
     dispatch.reduce_mut(move |store| {
-        store.search_results = results;
+        store.search_input.clear();
     })
 }
 
-pub fn clear_search_results(dispatch: Dispatch<Store>) {
+/// Moves `query` to the top of the recent searches, ignoring case when looking for repeats.
+pub fn add_recent_search(query: String, dispatch: Dispatch<Store>) {
     dispatch.reduce_mut(move |store| {
-        store.search_results.clear();
+        let lowercase = query.to_lowercase();
+        store.recent_searches.retain(|recent| recent.to_lowercase() != lowercase);
+        store.recent_searches.insert(0, query);
+        store.recent_searches.truncate(MAX_RECENT_SEARCHES);
     })
 }
 
-pub fn clear_search_input(dispatch: Dispatch<Store>) {
+pub fn clear_recent_searches(dispatch: Dispatch<Store>) {
     dispatch.reduce_mut(move |store| {
-        store.search_input.clear();
+        store.recent_searches.clear();
+    })
+}
+
+/// What happens when the end of the queue is reached.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default, Clone, Copy)]
+pub enum RepeatMode {
+    #[default]
+    Off,
+    /// Start the queue over
+    All,
+    /// Play the current song again
+    One,
+}
+
+/// The audio player, shared by every page so playback carries on across route changes.
+/// Kept apart from `Store` in session storage, so each tab has a player of its own.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Store, Clone)]
+#[store(storage = "session")]
+pub struct Player {
+    pub queue: Vec<Song>,
+    /// Indices into `queue` in the order they play, shuffled while `shuffle` is on
+    pub order: Vec<usize>,
+    /// Position in `order` of the current song, `None` before anything is played
+    pub position: Option<usize>,
+    pub playing: bool,
+    /// From 0 to 1
+    pub volume: f64,
+    pub shuffle: bool,
+    pub repeat: RepeatMode,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            queue: Vec::new(),
+            order: Vec::new(),
+            position: None,
+            playing: false,
+            volume: 1.0,
+            shuffle: false,
+            repeat: RepeatMode::Off,
+        }
+    }
+}
+
+impl Player {
+    pub fn current(&self) -> Option<&Song> {
+        self.position.and_then(|position| self.order.get(position)).and_then(|&index| self.queue.get(index))
+    }
+
+    /// The queue in the order it plays, with each song's position in `order`.
+    pub fn upcoming(&self) -> impl Iterator<Item = (usize, &Song)> {
+        self.order.iter().enumerate().map(|(position, &index)| (position, &self.queue[index]))
+    }
+
+    /// Rebuilds `order` for the current `shuffle`, keeping the current song playing.
+    /// Shuffled, the current song comes first and the rest follow in random order.
+    fn reorder(&mut self) {
+        let current = self.position.and_then(|position| self.order.get(position).copied());
+        let mut order = (0..self.queue.len()).collect::<Vec<_>>();
+
+        if self.shuffle {
+            // Fisher-Yates
+            for i in (1..order.len()).rev() {
+                let j = (js_sys::Math::random() * (i + 1) as f64) as usize;
+                order.swap(i, j);
+            }
+            if let Some(current) = current {
+                order.retain(|&index| index != current);
+                order.insert(0, current);
+            }
+        }
+
+        self.position = current.and_then(|current| order.iter().position(|&index| index == current));
+        self.order = order;
+    }
+
+    /// Moves `step` songs along `order`, wrapping around at either end.
+    fn advance(&mut self, step: isize) {
+        if self.order.is_empty() {
+            return;
+        }
+
+        let length = self.order.len() as isize;
+        let position = self.position.map_or(0, |position| (position as isize + step).rem_euclid(length));
+        self.position = Some(position as usize);
+    }
+
+    fn at_end(&self) -> bool {
+        self.position.map_or(true, |position| position + 1 >= self.order.len())
+    }
+}
+
+/// Plays `song` now. A song already in the queue is jumped to, others are added after the current song.
+pub fn play_song(song: Song, dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        let index = match player.queue.iter().position(|queued| queued.url == song.url) {
+            Some(index) => index,
+            None => {
+                player.queue.push(song);
+                let index = player.queue.len() - 1;
+                let after = player.position.map_or(player.order.len(), |position| position + 1);
+                player.order.insert(after, index);
+                index
+            }
+        };
+
+        player.position = player.order.iter().position(|&queued| queued == index);
+        player.playing = true;
+    })
+}
+
+/// Adds `song` to the end of the queue, unless it's queued already.
+pub fn enqueue_song(song: Song, dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        if player.queue.iter().any(|queued| queued.url == song.url) {
+            return;
+        }
+
+        player.queue.push(song);
+        player.order.push(player.queue.len() - 1);
+    })
+}
+
+/// Replaces the queue with `songs` and plays the one at `start`.
+pub fn play_queue(songs: Vec<Song>, start: usize, dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        player.queue = songs;
+        player.order = (0..player.queue.len()).collect();
+        player.position = (start < player.queue.len()).then_some(start);
+        player.playing = player.position.is_some();
+        player.reorder();
+    })
+}
+
+/// Skips to the next song. Past the end of the queue it starts over, paused unless repeating.
+pub fn next_song(dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        if player.at_end() && player.repeat == RepeatMode::Off {
+            player.playing = false;
+        }
+        player.advance(1);
+    })
+}
+
+/// Goes back a song. At the start of the queue it wraps around when repeating, otherwise stays put.
+pub fn previous_song(dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        if player.position == Some(0) && player.repeat == RepeatMode::Off {
+            return;
+        }
+        player.advance(-1);
+    })
+}
+
+/// Plays the song at `position` in the play order.
+pub fn jump_to(position: usize, dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        if position < player.order.len() {
+            player.position = Some(position);
+            player.playing = true;
+        }
+    })
+}
+
+/// Removes the song at `position` in the play order. Removing the current song moves on to the next one.
+pub fn remove_from_queue(position: usize, dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        if position >= player.order.len() {
+            return;
+        }
+
+        let index = player.order.remove(position);
+        player.queue.remove(index);
+        for queued in player.order.iter_mut() {
+            if *queued > index {
+                *queued -= 1;
+            }
+        }
+
+        player.position = match player.position {
+            _ if player.order.is_empty() => None,
+            Some(current) if position < current => Some(current - 1),
+            Some(current) if current >= player.order.len() => Some(0),
+            current => current,
+        };
+        if player.position.is_none() {
+            player.playing = false;
+        }
+    })
+}
+
+pub fn clear_queue(dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        player.queue.clear();
+        player.order.clear();
+        player.position = None;
+        player.playing = false;
+    })
+}
+
+pub fn set_playing(playing: bool, dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        player.playing = playing && player.current().is_some();
+    })
+}
+
+pub fn set_volume(volume: f64, dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        player.volume = volume.clamp(0.0, 1.0);
+    })
+}
+
+pub fn toggle_shuffle(dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        player.shuffle = !player.shuffle;
+        player.reorder();
+    })
+}
+
+/// Off, then all, then one, then off again.
+pub fn cycle_repeat(dispatch: Dispatch<Player>) {
+    dispatch.reduce_mut(move |player| {
+        player.repeat = match player.repeat {
+            RepeatMode::Off => RepeatMode::All,
+            RepeatMode::All => RepeatMode::One,
+            RepeatMode::One => RepeatMode::Off,
+        };
     })
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use chrono::prelude::*;
+use uuid::Uuid;
+
+use crate::schema::song::SongSummary;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Album {
@@ -7,4 +10,34 @@ pub struct Album {
     title: String,
     artist_id: uuid::Uuid,
     release_date: DateTime<Utc>,
+}
+
+/// An album as listed on other pages
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AlbumSummary {
+    pub album_id: Uuid,
+    pub title: String,
+    pub artist_id: Uuid,
+    pub artist: String,
+    /// URL of the cover
+    pub cover: Option<String>,
+    pub release_date: Option<DateTime<Utc>>,
+    pub track_count: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AlbumDetails {
+    pub album: AlbumSummary,
+    pub genre: Option<String>,
+    /// In the album's order
+    pub tracks: Vec<SongSummary>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AlbumDetailsResponse {
+    pub status: String,
+    pub album: AlbumDetails,
 }
\ No newline at end of file
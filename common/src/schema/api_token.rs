@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use chrono::prelude::*;
+use validator::Validate;
+
+/// What a personal API token may be used for
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApiTokenScope {
+    /// `GET` requests
+    Read,
+    /// Every other request
+    Write,
+}
+
+impl fmt::Display for ApiTokenScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiTokenScope::Read => write!(f, "Read"),
+            ApiTokenScope::Write => write!(f, "Write"),
+        }
+    }
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateApiTokenSchema {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "Select at least one scope"))]
+    pub scopes: Vec<ApiTokenScope>,
+    /// Days until the token expires, or `None` for a token that never expires
+    #[validate(range(min = 1, max = 365, message = "Expiry must be between 1 and 365 days"))]
+    pub expires_in_days: Option<i64>,
+}
+
+/// A personal API token as listed to its owner. The token itself is never returned after creation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiToken {
+    pub id: uuid::Uuid,
+    pub name: String,
+    /// The first characters of the token, to tell tokens apart
+    pub prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiTokenListResponse {
+    pub status: String,
+    pub tokens: Vec<ApiToken>,
+}
+
+/// Returned once when a token is created, the only time the full token is shown
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateApiTokenResponse {
+    pub status: String,
+    pub token: String,
+    pub api_token: ApiToken,
+}
\ No newline at end of file
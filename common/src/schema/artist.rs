@@ -1,8 +1,36 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::{album::AlbumSummary, song::SongSummary};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Artist {
     artist_id: uuid::Uuid,
     name: String,
     genre: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ArtistSummary {
+    pub artist_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ArtistDetails {
+    pub artist: ArtistSummary,
+    pub genres: Vec<String>,
+    /// The artist's albums, newest first
+    pub albums: Vec<AlbumSummary>,
+    /// The artist's most played songs
+    pub top_songs: Vec<SongSummary>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ArtistDetailsResponse {
+    pub status: String,
+    pub artist: ArtistDetails,
 }
\ No newline at end of file
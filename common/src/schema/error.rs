@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, collections::BTreeMap, fmt};
+use validator::{ValidationError, ValidationErrors};
+
+/// Validation messages keyed by the name of the field they belong to
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+/// Stable, machine-readable reason for a failed request.
+/// Clients should branch on these rather than on messages, which may be reworded.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The request is malformed or not allowed in the current state
+    BadRequest,
+    /// One or more fields failed validation
+    ValidationFailed,
+    /// Wrong email or password
+    InvalidCredentials,
+    /// Wrong two-factor authentication or recovery code
+    InvalidCode,
+    /// An emailed verification, reset or confirmation link is invalid or expired
+    InvalidLink,
+    /// No valid access token, the client should refresh it or log in
+    Unauthenticated,
+    /// The two-factor login challenge expired, the login has to start over
+    ChallengeExpired,
+    /// The account's email address hasn't been verified yet
+    EmailNotVerified,
+    /// Logged in, but not allowed to do this
+    Forbidden,
+    NotFound,
+    /// Clashes with existing data, like a taken username
+    Conflict,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    /// Too many requests, see the `Retry-After` header
+    RateLimited,
+    /// A service the server relies on, like email, failed
+    ServiceUnavailable,
+    /// Anything unexpected on the server
+    #[default]
+    Internal,
+}
+
+/// The body of every error response from the API
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ErrorResponse {
+    /// Always `"fail"` for errors caused by the request, `"error"` for errors on the server
+    pub status: String,
+    #[serde(default)]
+    pub code: ErrorCode,
+    /// Human readable, for showing to the user
+    pub message: String,
+    /// Set when `code` is `ValidationFailed`, so forms can show each message next to its field
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[cfg_attr(feature = "openapi", schema(value_type = BTreeMap<String, Vec<String>>))]
+    pub fields: FieldErrors,
+}
+
+impl ErrorResponse {
+    pub fn new(message: &str) -> Self {
+        Self {
+            status: "error".to_string(),
+            code: ErrorCode::Internal,
+            message: message.to_string(),
+            fields: FieldErrors::new(),
+        }
+    }
+
+    /// The errors of `fields` in the form the `Input` components display. `ValidationErrors` only
+    /// takes static field names, so the form passes the names of the fields it shows and errors
+    /// for any others are left out.
+    pub fn validation_errors(&self, fields: &[&'static str]) -> ValidationErrors {
+        let mut errors = ValidationErrors::new();
+        for field in fields {
+            let Some(messages) = self.fields.get(*field) else { continue };
+            for message in messages {
+                let mut error = ValidationError::new("server");
+                error.message = Some(Cow::Owned(message.clone()));
+                errors.add(field, error);
+            }
+        }
+
+        errors
+    }
+}
+
+impl fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
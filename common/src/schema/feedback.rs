@@ -25,18 +25,3 @@ pub struct FeedbackListResponse {
     pub results: i32,
     pub feedbacks: Vec<Feedback>,
 }
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ErrorResponse {
-    pub status: String,
-    pub message: String,
-}
-
-impl ErrorResponse {
-    pub fn new(message: &str) -> Self {
-        Self {
-            status: "error".to_string(),
-            message: message.to_string(),
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// Response of endpoints that have nothing to return but a confirmation
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MessageResponse {
+    pub status: String,
+    pub message: String,
+}
+
+impl MessageResponse {
+    pub fn success(message: &str) -> Self {
+        Self {
+            status: "success".to_string(),
+            message: message.to_string(),
+        }
+    }
+}
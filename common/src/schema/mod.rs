@@ -1,7 +1,16 @@
 pub mod user;
 pub mod feedback;
+pub mod error;
 pub mod song;
 pub mod artist;
 pub mod album;
 pub mod platform;
-pub mod select;
\ No newline at end of file
+pub mod select;
+pub mod two_factor;
+pub mod api_token;
+pub mod profile;
+pub mod social;
+pub mod privacy;
+pub mod message;
+pub mod recommendation;
+pub mod search;
\ No newline at end of file
@@ -6,6 +6,7 @@ use chrono::prelude::*;
 use crate::schema::select::SelectItem;
 
 #[derive(Debug, Deserialize, Serialize, Clone, EnumIter, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Platform {
     AppleMusic,
@@ -46,6 +47,43 @@ pub fn get_platform_select_items() -> Vec<SelectItem> {
     }).collect()
 }
 
+impl Platform {
+    /// The value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Platform::AppleMusic => "APPLE_MUSIC",
+            Platform::Spotify => "SPOTIFY",
+            Platform::Soundcloud => "SOUNDCLOUD",
+            Platform::YoutubeMusic => "YOUTUBE_MUSIC",
+            Platform::AmazonMusic => "AMAZON_MUSIC",
+            Platform::Tidal => "TIDAL",
+        }
+    }
+
+    /// The platform a link points to, from its host. `None` for any other site.
+    pub fn from_url(url: &str) -> Option<Platform> {
+        let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let host = without_scheme.split(['/', '?', '#']).next()?.to_lowercase();
+        let is = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+
+        if is("music.apple.com") || is("itunes.apple.com") {
+            Some(Platform::AppleMusic)
+        } else if is("spotify.com") {
+            Some(Platform::Spotify)
+        } else if is("soundcloud.com") {
+            Some(Platform::Soundcloud)
+        } else if is("music.youtube.com") {
+            Some(Platform::YoutubeMusic)
+        } else if host.starts_with("music.amazon.") || host.starts_with("www.music.amazon.") {
+            Some(Platform::AmazonMusic)
+        } else if is("tidal.com") {
+            Some(Platform::Tidal)
+        } else {
+            None
+        }
+    }
+}
+
 impl fmt::Display for Platform {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
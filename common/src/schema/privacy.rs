@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use super::social::Visibility;
+
+/// Who can see what of the user's activity
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PrivacySettings {
+    /// Who can see likes and plays, in the activity feed and on the public profile
+    pub history_visibility: Visibility,
+    /// Who can see the public profile, playlists and follows
+    pub profile_visibility: Visibility,
+    /// Whether recommendations may use what followed users listen to
+    pub allow_social_recommendations: bool,
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        PrivacySettings {
+            history_visibility: Visibility::Followers,
+            profile_visibility: Visibility::Public,
+            allow_social_recommendations: true,
+        }
+    }
+}
+
+/// Fields left out are not changed
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdatePrivacySchema {
+    pub history_visibility: Option<Visibility>,
+    pub profile_visibility: Option<Visibility>,
+    pub allow_social_recommendations: Option<bool>,
+}
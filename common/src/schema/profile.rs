@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use chrono::prelude::*;
+use uuid::Uuid;
+
+/// An artist the user listens to most, by how many of their preferences feature the artist
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TopArtist {
+    pub artist_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PublicPlaylist {
+    pub playlist_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub song_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A song the user listened to recently
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecentPlay {
+    pub song_id: Uuid,
+    pub title: String,
+    pub played_at: DateTime<Utc>,
+}
+
+/// What anyone can see about a user. Only includes what the user opted in to sharing.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PublicProfile {
+    pub username: String,
+    pub name: String,
+    pub photo: String,
+    /// `None` unless the user chose to show their top artists
+    pub top_artists: Option<Vec<TopArtist>>,
+    /// Only the playlists the user made public
+    pub playlists: Vec<PublicPlaylist>,
+    /// `None` unless the user's listening history is visible to the viewer
+    #[serde(default)]
+    pub recent_plays: Option<Vec<RecentPlay>>,
+    /// Set when the user's privacy settings hide their profile from the viewer.
+    /// Only the name, photo and follow counts are filled in then.
+    #[serde(default)]
+    pub restricted: bool,
+    pub follower_count: i64,
+    pub following_count: i64,
+    pub member_since: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PublicProfileResponse {
+    pub status: String,
+    pub profile: PublicProfile,
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::song::SongSummary;
+
+/// What the user thinks of a recommended song
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RecommendationFeedback {
+    /// More like this. The song counts like a liked one.
+    ThumbsUp,
+    /// Fewer like this. Songs similar to it are recommended less.
+    ThumbsDown,
+    /// Never recommend this song again, without judging it
+    Hide,
+    /// Skip the song for a week
+    NotNow,
+}
+
+impl RecommendationFeedback {
+    /// The value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationFeedback::ThumbsUp => "THUMBS_UP",
+            RecommendationFeedback::ThumbsDown => "THUMBS_DOWN",
+            RecommendationFeedback::Hide => "HIDE",
+            RecommendationFeedback::NotNow => "NOT_NOW",
+        }
+    }
+}
+
+impl fmt::Display for RecommendationFeedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecommendationFeedback::ThumbsUp => write!(f, "More like this"),
+            RecommendationFeedback::ThumbsDown => write!(f, "Fewer like this"),
+            RecommendationFeedback::Hide => write!(f, "Hidden"),
+            RecommendationFeedback::NotNow => write!(f, "Not now"),
+        }
+    }
+}
+
+/// Why a song was recommended
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RecommendationReason {
+    /// Like a song the user liked or listened to, the one it's most like
+    SimilarTo { song_id: Uuid, title: String },
+    /// Liked by `count` of the users the user follows
+    LikedByFollowing { count: i64 },
+    /// Nothing to go on yet, the song is liked and played a lot
+    Popular,
+}
+
+impl fmt::Display for RecommendationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecommendationReason::SimilarTo { title, .. } => write!(f, "Because you like {}", title),
+            RecommendationReason::LikedByFollowing { count: 1 } => write!(f, "Liked by someone you follow"),
+            RecommendationReason::LikedByFollowing { count } => write!(f, "Liked by {} people you follow", count),
+            RecommendationReason::Popular => write!(f, "Popular right now"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Recommendation {
+    pub song: SongSummary,
+    /// From 0 to 1, how well the song matches the user's taste
+    pub match_score: f32,
+    pub reason: RecommendationReason,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecommendationListResponse {
+    pub status: String,
+    /// Best match first
+    pub recommendations: Vec<Recommendation>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecommendationFeedbackSchema {
+    pub feedback: RecommendationFeedback,
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::{album::AlbumSummary, artist::ArtistSummary, social::UserSummary, song::SongSummary};
+
+/// A public playlist found by name
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PlaylistSummary {
+    pub playlist_id: Uuid,
+    pub name: String,
+    pub owner: UserSummary,
+    pub song_count: i64,
+}
+
+/// What matched a search, grouped by kind, best match first in each group
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SearchResults {
+    pub songs: Vec<SongSummary>,
+    pub artists: Vec<ArtistSummary>,
+    pub albums: Vec<AlbumSummary>,
+    pub playlists: Vec<PlaylistSummary>,
+}
+
+impl SearchResults {
+    pub fn is_empty(&self) -> bool {
+        self.songs.is_empty() && self.artists.is_empty() && self.albums.is_empty() && self.playlists.is_empty()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SearchResponse {
+    pub status: String,
+    /// The query as it was searched for, trimmed
+    pub query: String,
+    pub results: SearchResults,
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use chrono::prelude::*;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::select::SelectItem;
+
+/// Who can see a part of a user's activity
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Visibility {
+    /// Anyone
+    Public,
+    /// Only the user's followers. Following needs no approval, so this only hides activity from
+    /// people who haven't followed the user, it doesn't keep it private.
+    #[default]
+    Followers,
+    /// Only the user
+    Private,
+}
+
+impl Visibility {
+    /// The value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "PUBLIC",
+            Visibility::Followers => "FOLLOWERS",
+            Visibility::Private => "PRIVATE",
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Visibility::Public => write!(f, "Everyone"),
+            Visibility::Followers => write!(f, "Followers (anyone can follow me)"),
+            Visibility::Private => write!(f, "Only me"),
+        }
+    }
+}
+
+impl From<String> for Visibility {
+    fn from(s: String) -> Self {
+        match s.to_uppercase().as_str() {
+            "PUBLIC" => Visibility::Public,
+            "PRIVATE" => Visibility::Private,
+            _ => Visibility::Followers,
+        }
+    }
+}
+
+pub fn get_visibility_select_items() -> Vec<SelectItem> {
+    [Visibility::Public, Visibility::Followers, Visibility::Private]
+        .iter()
+        .map(|visibility| SelectItem::new(&visibility.to_string(), visibility.as_str()))
+        .collect()
+}
+
+/// Just enough about a user to link to their profile
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserSummary {
+    pub username: String,
+    pub name: String,
+    pub photo: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FollowListResponse {
+    pub status: String,
+    pub users: Vec<UserSummary>,
+    /// Pass back as `cursor` to get the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FollowStatusResponse {
+    pub status: String,
+    /// Whether the logged in user follows this user
+    pub following: bool,
+    pub follower_count: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FeedItemKind {
+    /// The user liked a song
+    Like,
+    /// The user listened to a song
+    Play,
+    /// The user published a playlist
+    Playlist,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeedSong {
+    pub song_id: Uuid,
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeedPlaylist {
+    pub playlist_id: Uuid,
+    pub name: String,
+}
+
+/// Something a followed user did
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeedItem {
+    pub id: Uuid,
+    pub kind: FeedItemKind,
+    pub user: UserSummary,
+    /// Set for likes and plays
+    pub song: Option<FeedSong>,
+    /// Set for playlists
+    pub playlist: Option<FeedPlaylist>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeedResponse {
+    pub status: String,
+    pub items: Vec<FeedItem>,
+    /// Pass back as `cursor` to get the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecordPlaySchema {
+    pub song_id: Uuid,
+}
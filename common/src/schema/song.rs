@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use chrono::prelude::*;
+use uuid::Uuid;
+
+use crate::schema::platform::Platform;
 
 #[derive(Debug, Deserialize, Clone, Serialize)]
 #[serde(rename_all = "lowercase")]
@@ -44,6 +48,13 @@ pub struct Songs {
 /// A song object (for the client)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Song {
+    /// `None` for songs that aren't in our catalog
+    #[serde(default)]
+    pub song_id: Option<Uuid>,
+    #[serde(default)]
+    pub artist_id: Option<Uuid>,
+    #[serde(default)]
+    pub album_id: Option<Uuid>,
     /// The song title
     pub title: String,
     /// The artist name
@@ -56,4 +67,94 @@ pub struct Song {
     pub cover: String,
     /// The song URL
     pub url: String,
+}
+
+/// Features estimated from a song's audio
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AudioFeatures {
+    /// In seconds
+    pub duration: u16,
+    /// In beats per minute, `None` if no beat was found
+    pub tempo: Option<f32>,
+    /// Pitch class of the tonic, 0 is C, `None` if no pitch was found
+    pub key: Option<i16>,
+    /// RMS level in dBFS
+    pub loudness: f32,
+    /// From 0 for music to 1 for spoken word
+    pub speechiness: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AudioFeaturesResponse {
+    pub status: String,
+    pub data: AudioFeatures,
+}
+
+
+/// A song as listed on other pages, with what is needed to link to it and play it
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SongSummary {
+    pub song_id: Uuid,
+    pub title: String,
+    pub artist_id: Uuid,
+    pub artist: String,
+    pub album_id: Uuid,
+    pub album: String,
+    /// URL of the album cover
+    pub cover: Option<String>,
+    /// In seconds
+    pub duration: u16,
+    /// Whether we host the song's audio, and so can stream it and its preview
+    pub has_audio: bool,
+}
+
+/// A song's stored features. Any of them can be missing, for songs imported without them and
+/// never analyzed.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SongFeatures {
+    /// In beats per minute
+    pub tempo: Option<f32>,
+    /// Pitch class of the tonic, 0 is C
+    pub key: Option<i16>,
+    /// Beats per bar
+    pub time_signature: Option<i16>,
+    /// RMS level in dBFS
+    pub loudness: Option<f32>,
+    /// From 0 for music to 1 for spoken word
+    pub speechiness: Option<f32>,
+    /// From 0 to 1
+    pub danceability: Option<f32>,
+}
+
+/// Where a song can be listened to
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PlatformLink {
+    /// `None` for sites other than the streaming platforms we know
+    pub platform: Option<Platform>,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SongDetails {
+    pub song: SongSummary,
+    pub genre: String,
+    /// Release date of the song's album
+    pub release_date: Option<DateTime<Utc>>,
+    pub features: SongFeatures,
+    pub links: Vec<PlatformLink>,
+    /// The songs most like this one, most similar first
+    pub similar: Vec<SongSummary>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SongDetailsResponse {
+    pub status: String,
+    pub song: SongDetails,
 }
\ No newline at end of file
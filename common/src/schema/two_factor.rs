@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Returned when a user starts enrolling an authenticator app
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TwoFactorEnrollResponse {
+    pub status: String,
+    /// `otpauth://` URI to render as a QR code
+    pub otpauth_uri: String,
+    /// The base32 secret, for entering into the app by hand
+    pub secret: String,
+}
+
+/// Recovery codes are only ever shown once, when they are generated
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecoveryCodesResponse {
+    pub status: String,
+    pub recovery_codes: Vec<String>,
+}
+
+/// Returned by login instead of the session tokens when the account has 2FA enabled
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TwoFactorChallengeResponse {
+    pub status: String,
+    pub challenge_token: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TwoFactorCodeSchema {
+    #[validate(length(min = 1, message = "Code is required"))]
+    pub code: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TwoFactorVerifySchema {
+    #[validate(length(min = 1, message = "Challenge token is required"))]
+    pub challenge_token: String,
+    /// Either a code from the authenticator app or one of the recovery codes
+    #[validate(length(min = 1, message = "Code is required"))]
+    pub code: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DisableTwoFactorSchema {
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+    #[validate(length(min = 1, message = "Code is required"))]
+    pub code: String,
+}
\ No newline at end of file
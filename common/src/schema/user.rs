@@ -1,10 +1,74 @@
 use serde::{ Deserialize, Serialize };
 use chrono::prelude::*;
-use validator::Validate;
+use std::borrow::Cow;
+use validator::{Validate, ValidationError};
 
 use super::platform::Platform;
+use super::privacy::PrivacySettings;
+use super::two_factor::TwoFactorChallengeResponse;
+
+/// Minimum length accepted by the password policy
+pub const PASSWORD_MIN_LENGTH: usize = 8;
+/// Maximum length accepted by the password policy, bounding the hashing cost
+pub const PASSWORD_MAX_LENGTH: usize = 128;
+
+/// Password policy for new passwords: 8 to 128 characters with at least one
+/// lowercase letter, one uppercase letter and one digit.
+pub fn validate_password_strength(password: &str) -> Result<(), ValidationError> {
+    let length = password.chars().count();
+    let message = if length < PASSWORD_MIN_LENGTH {
+        Some(format!("Password must be at least {} characters", PASSWORD_MIN_LENGTH))
+    } else if length > PASSWORD_MAX_LENGTH {
+        Some(format!("Password must be at most {} characters", PASSWORD_MAX_LENGTH))
+    } else if !password.chars().any(|c| c.is_lowercase()) {
+        Some("Password must contain a lowercase letter".to_string())
+    } else if !password.chars().any(|c| c.is_uppercase()) {
+        Some("Password must contain an uppercase letter".to_string())
+    } else if !password.chars().any(|c| c.is_ascii_digit()) {
+        Some("Password must contain a number".to_string())
+    } else {
+        None
+    };
+
+    match message {
+        Some(message) => {
+            let mut error = ValidationError::new("password_strength");
+            error.message = Some(Cow::from(message));
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Shortest username accepted
+pub const USERNAME_MIN_LENGTH: usize = 3;
+/// Longest username accepted
+pub const USERNAME_MAX_LENGTH: usize = 30;
+
+/// Usernames appear in profile URLs, so they are limited to 3 to 30 letters, digits and
+/// underscores. They are unique regardless of case.
+pub fn validate_username(username: &str) -> Result<(), ValidationError> {
+    let length = username.chars().count();
+    let message = if !(USERNAME_MIN_LENGTH..=USERNAME_MAX_LENGTH).contains(&length) {
+        Some(format!("Username must be between {} and {} characters", USERNAME_MIN_LENGTH, USERNAME_MAX_LENGTH))
+    } else if !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Some("Username can only contain letters, numbers and underscores".to_string())
+    } else {
+        None
+    };
+
+    match message {
+        Some(message) => {
+            let mut error = ValidationError::new("username");
+            error.message = Some(Cow::from(message));
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
 
 #[derive(Debug, Deserialize, Validate, Clone, Default, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SignupUserSchema {
     #[validate(length(min = 1, message = "Name is required"))]
     pub name: String,
@@ -15,21 +79,22 @@ pub struct SignupUserSchema {
     pub email: String,
     #[validate(
         length(min = 1, message = "Password is required"),
-        length(min = 6, message = "Password must be at least 6 characters")
+        custom = "validate_password_strength"
     )]
     pub password: String,
     #[validate(
         length(min = 1, message = "Password confirmation is required"),
-        length(min = 6, message = "Password confirmation must be at least 6 characters"),
         must_match(other = "password", message = "Passwords do not match")
     )]
     pub password_confirm: String,
+    #[validate(custom = "validate_username")]
     pub username: String,
     pub preferred_platform: Option<String>,
     pub photo: Option<String>,
 }
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct LoginUserSchema {
     #[validate(
         length(min = 1, message = "Email is required"),
@@ -43,8 +108,99 @@ pub struct LoginUserSchema {
     pub password: String,
 }
 
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EmailRequestSchema {
+    #[validate(
+        length(min = 1, message = "Email is required"),
+        email(message = "Email is invalid")
+    )]
+    pub email: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VerifyEmailSchema {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResetPasswordSchema {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+    #[validate(
+        length(min = 1, message = "Password is required"),
+        custom = "validate_password_strength"
+    )]
+    pub password: String,
+    #[validate(
+        length(min = 1, message = "Password confirmation is required"),
+        must_match(other = "password", message = "Passwords do not match")
+    )]
+    pub password_confirm: String,
+}
+
+/// Fields left out are not changed
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateProfileSchema {
+    #[validate(length(min = 1, max = 255, message = "Name must be between 1 and 255 characters"))]
+    pub name: Option<String>,
+    #[validate(custom = "validate_username")]
+    pub username: Option<String>,
+    #[validate(
+        length(max = 2048, message = "Photo URL is too long"),
+        url(message = "Photo must be a URL")
+    )]
+    pub photo: Option<String>,
+    pub preferred_platform: Option<Platform>,
+    pub show_top_artists: Option<bool>,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChangePasswordSchema {
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+    #[validate(
+        length(min = 1, message = "Password is required"),
+        custom = "validate_password_strength"
+    )]
+    pub password: String,
+    #[validate(
+        length(min = 1, message = "Password confirmation is required"),
+        must_match(other = "password", message = "Passwords do not match")
+    )]
+    pub password_confirm: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChangeEmailSchema {
+    #[validate(
+        length(min = 1, message = "Email is required"),
+        email(message = "Email is invalid")
+    )]
+    pub email: String,
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DeleteAccountSchema {
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+    /// Required when two-factor authentication is enabled
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
 #[allow(non_snake_case)]
 #[derive(Debug, Serialize, Clone, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct FilteredUser {
     pub user_id: uuid::Uuid,
     pub name: String,
@@ -52,16 +208,28 @@ pub struct FilteredUser {
     pub email: String,
     pub preferred_platform: Platform,
     pub photo: String,
+    #[serde(default)]
+    pub two_factor_enabled: bool,
+    /// New email address waiting to be confirmed
+    #[serde(default)]
+    pub pending_email: Option<String>,
+    /// Whether top artists are shown on the public profile
+    #[serde(default)]
+    pub show_top_artists: bool,
+    #[serde(default)]
+    pub privacy: PrivacySettings,
     pub createdAt: DateTime<Utc>,
     pub updatedAt: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UserData {
     pub user: FilteredUser,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UserResponse {
     pub status: String,
     pub data: UserData,
@@ -69,9 +237,19 @@ pub struct UserResponse {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UserLoginResponse {
     pub status: String,
     pub access_token: String,
     pub refresh_token: String,
 }
 
+/// The response to a login: either the session tokens, or a challenge when the
+/// account has two-factor authentication enabled.
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Success(UserLoginResponse),
+    TwoFactorRequired(TwoFactorChallengeResponse),
+}
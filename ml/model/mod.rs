@@ -3,7 +3,7 @@ use candle_nn::VarBuilder;
 use hf_hub::{api::tokio::Api, Repo, RepoType};
 use candle_transformers::models::bert::{BertModel, Config, DTYPE};
 use tokenizers::Tokenizer;
-use common::schema::feedback::ErrorResponse;
+use common::schema::error::ErrorResponse;
 use linfa::prelude::*;
 use linfa_clustering::{KMeans, KMeansParams};
 use ndarray::*;
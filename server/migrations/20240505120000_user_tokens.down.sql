@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "user_tokens";
+DROP TYPE IF EXISTS "token_purpose";
+ALTER TABLE "users" DROP COLUMN "verified";
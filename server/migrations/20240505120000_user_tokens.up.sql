@@ -0,0 +1,23 @@
+-- Add up migration script here
+ALTER TABLE "users" ADD COLUMN "verified" BOOLEAN NOT NULL DEFAULT FALSE; --> statement-breakpoint
+-- Accounts created before verification existed are trusted as-is
+UPDATE "users" SET "verified" = TRUE; --> statement-breakpoint
+
+DO $$ BEGIN
+ CREATE TYPE "token_purpose" AS ENUM('EMAIL_VERIFICATION', 'PASSWORD_RESET');
+EXCEPTION
+ WHEN duplicate_object THEN null;
+END $$;
+--> statement-breakpoint
+
+CREATE TABLE "user_tokens" (
+    token_id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    purpose token_purpose NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+); --> statement-breakpoint
+
+CREATE INDEX "user_tokens_user_id_idx" ON "user_tokens" (user_id, purpose);
@@ -0,0 +1,14 @@
+-- Add up migration script here
+-- Only used when RATE_LIMIT_STORE=postgres so limits are shared between server instances
+CREATE UNLOGGED TABLE "rate_limit_buckets" (
+    key TEXT NOT NULL PRIMARY KEY,
+    tokens DOUBLE PRECISION NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+); --> statement-breakpoint
+
+CREATE TABLE "login_failures" (
+    account TEXT NOT NULL PRIMARY KEY,
+    failures INTEGER NOT NULL DEFAULT 0,
+    locked_until TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
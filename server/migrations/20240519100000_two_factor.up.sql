@@ -0,0 +1,15 @@
+-- Add up migration script here
+ALTER TABLE "users" ADD COLUMN "totp_secret" TEXT; --> statement-breakpoint
+ALTER TABLE "users" ADD COLUMN "totp_enabled" BOOLEAN NOT NULL DEFAULT FALSE; --> statement-breakpoint
+-- Time step of the last accepted code, so a code can't be replayed within its window
+ALTER TABLE "users" ADD COLUMN "totp_last_step" BIGINT; --> statement-breakpoint
+
+CREATE TABLE "recovery_codes" (
+    code_id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+); --> statement-breakpoint
+
+CREATE INDEX "recovery_codes_user_id_idx" ON "recovery_codes" (user_id);
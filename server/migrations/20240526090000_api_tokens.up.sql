@@ -0,0 +1,22 @@
+-- Add up migration script here
+DO $$ BEGIN
+ CREATE TYPE "api_token_scope" AS ENUM('READ', 'WRITE');
+EXCEPTION
+ WHEN duplicate_object THEN null;
+END $$;
+--> statement-breakpoint
+
+CREATE TABLE "api_tokens" (
+    token_id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    -- The first few characters of the token, so users can tell their tokens apart
+    token_prefix VARCHAR(16) NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    scopes api_token_scope[] NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+); --> statement-breakpoint
+
+CREATE INDEX "api_tokens_user_id_idx" ON "api_tokens" (user_id);
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE "recommendations" DROP CONSTRAINT IF EXISTS "recommendations_user_id_fkey";
+ALTER TABLE "user_preferences" DROP CONSTRAINT IF EXISTS "user_preferences_user_id_fkey";
+ALTER TABLE "users" DROP COLUMN "pending_email";
+-- Postgres can't drop a value from an enum, 'EMAIL_CHANGE' stays on token_purpose
+DELETE FROM "user_tokens" WHERE purpose = 'EMAIL_CHANGE';
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TYPE "token_purpose" ADD VALUE IF NOT EXISTS 'EMAIL_CHANGE'; --> statement-breakpoint
+-- The new address waits here until the user confirms it from the link sent to it
+ALTER TABLE "users" ADD COLUMN "pending_email" VARCHAR(255); --> statement-breakpoint
+
+-- Deleting an account removes everything that belongs to it
+DELETE FROM "user_preferences" WHERE user_id NOT IN (SELECT user_id FROM "users"); --> statement-breakpoint
+ALTER TABLE "user_preferences" ADD CONSTRAINT "user_preferences_user_id_fkey"
+    FOREIGN KEY (user_id) REFERENCES "users" (user_id) ON DELETE CASCADE; --> statement-breakpoint
+DELETE FROM "recommendations" WHERE user_id NOT IN (SELECT user_id FROM "users"); --> statement-breakpoint
+ALTER TABLE "recommendations" ADD CONSTRAINT "recommendations_user_id_fkey"
+    FOREIGN KEY (user_id) REFERENCES "users" (user_id) ON DELETE CASCADE;
\ No newline at end of file
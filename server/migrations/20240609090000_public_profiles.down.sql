@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS "playlist_songs"; --> statement-breakpoint
+DROP TABLE IF EXISTS "playlists"; --> statement-breakpoint
+ALTER TABLE "users" DROP COLUMN IF EXISTS "show_top_artists"; --> statement-breakpoint
+-- Usernames suffixed to resolve duplicates are left as they are
+DROP INDEX IF EXISTS "users_username_lower_key";
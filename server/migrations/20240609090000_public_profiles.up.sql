@@ -0,0 +1,34 @@
+-- Add up migration script here
+-- Usernames are unique regardless of case, so keep the oldest account's name and suffix the rest
+UPDATE "users" SET username = LEFT(username, 91) || '_' || LEFT(user_id::text, 8)
+WHERE user_id IN (
+    SELECT user_id FROM (
+        SELECT user_id, ROW_NUMBER() OVER (PARTITION BY LOWER(username) ORDER BY created_at, user_id) AS position
+        FROM "users"
+    ) AS ranked
+    WHERE position > 1
+); --> statement-breakpoint
+CREATE UNIQUE INDEX "users_username_lower_key" ON "users" (LOWER(username)); --> statement-breakpoint
+
+-- Top artists only show on the public profile once the user opts in
+ALTER TABLE "users" ADD COLUMN "show_top_artists" BOOLEAN NOT NULL DEFAULT FALSE; --> statement-breakpoint
+
+CREATE TABLE "playlists" (
+    playlist_id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    -- Private unless the owner chooses to show it on their public profile
+    is_public BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+); --> statement-breakpoint
+CREATE INDEX "playlists_user_id_idx" ON "playlists" (user_id); --> statement-breakpoint
+
+CREATE TABLE "playlist_songs" (
+    playlist_id UUID NOT NULL REFERENCES "playlists" (playlist_id) ON DELETE CASCADE,
+    song_id UUID NOT NULL REFERENCES "songs" (song_id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (playlist_id, song_id)
+);
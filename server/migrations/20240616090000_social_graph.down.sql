@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS "playlists_user_id_created_at_idx"; --> statement-breakpoint
+DROP TABLE IF EXISTS "listening_history"; --> statement-breakpoint
+DROP TABLE IF EXISTS "song_likes"; --> statement-breakpoint
+DROP TABLE IF EXISTS "follows"; --> statement-breakpoint
+ALTER TABLE "users" DROP COLUMN IF EXISTS "history_visibility";
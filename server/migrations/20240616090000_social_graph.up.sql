@@ -0,0 +1,32 @@
+-- Add up migration script here
+-- Who can see the user's likes and plays in their activity. Text like `preferred_platform`,
+-- so `SELECT *` into `Users` keeps working without type overrides
+ALTER TABLE "users" ADD COLUMN "history_visibility" TEXT NOT NULL DEFAULT 'FOLLOWERS'
+    CHECK (history_visibility IN ('PUBLIC', 'FOLLOWERS', 'PRIVATE')); --> statement-breakpoint
+
+CREATE TABLE "follows" (
+    follower_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    followee_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (follower_id, followee_id),
+    CHECK (follower_id <> followee_id)
+); --> statement-breakpoint
+CREATE INDEX "follows_followee_id_idx" ON "follows" (followee_id, created_at); --> statement-breakpoint
+
+CREATE TABLE "song_likes" (
+    like_id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    song_id UUID NOT NULL REFERENCES "songs" (song_id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, song_id)
+); --> statement-breakpoint
+CREATE INDEX "song_likes_user_id_created_at_idx" ON "song_likes" (user_id, created_at); --> statement-breakpoint
+
+CREATE TABLE "listening_history" (
+    play_id UUID NOT NULL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    song_id UUID NOT NULL REFERENCES "songs" (song_id) ON DELETE CASCADE,
+    played_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+); --> statement-breakpoint
+CREATE INDEX "listening_history_user_id_played_at_idx" ON "listening_history" (user_id, played_at); --> statement-breakpoint
+CREATE INDEX "playlists_user_id_created_at_idx" ON "playlists" (user_id, created_at);
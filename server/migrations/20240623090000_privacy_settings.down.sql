@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE "users" DROP COLUMN IF EXISTS "allow_social_recommendations"; --> statement-breakpoint
+ALTER TABLE "users" DROP COLUMN IF EXISTS "profile_visibility";
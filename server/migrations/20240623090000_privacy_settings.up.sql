@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- Who can see the user's public profile, one of the `history_visibility` values
+ALTER TABLE "users" ADD COLUMN "profile_visibility" TEXT NOT NULL DEFAULT 'PUBLIC'
+    CHECK (profile_visibility IN ('PUBLIC', 'FOLLOWERS', 'PRIVATE')); --> statement-breakpoint
+-- Whether recommendations may be based on what followed users listen to
+ALTER TABLE "users" ADD COLUMN "allow_social_recommendations" BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+UPDATE "albums" SET "release_date" = TO_TIMESTAMP(0) WHERE "release_date" IS NULL; --> statement-breakpoint
+ALTER TABLE "albums" ALTER COLUMN "release_date" SET NOT NULL;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- Many public datasets don't have release dates, albums imported from them are left without one
+ALTER TABLE "albums" ALTER COLUMN "release_date" DROP NOT NULL;
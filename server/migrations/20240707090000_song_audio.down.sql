@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE "songs" DROP COLUMN IF EXISTS "audio_uploaded_by"; --> statement-breakpoint
+ALTER TABLE "songs" DROP COLUMN IF EXISTS "audio_key";
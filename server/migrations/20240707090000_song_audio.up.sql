@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- Storage key of the song's audio file, for songs whose audio we host
+ALTER TABLE "songs" ADD COLUMN "audio_key" TEXT; --> statement-breakpoint
+-- Who uploaded the audio, only they may replace it
+ALTER TABLE "songs" ADD COLUMN "audio_uploaded_by" UUID REFERENCES "users" (user_id) ON DELETE SET NULL;
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "songs" DROP COLUMN IF EXISTS "preview_key";
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- Storage key of the 30 second preview cut from the song's audio
+ALTER TABLE "songs" ADD COLUMN "preview_key" TEXT;
@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- What users told us about songs recommended to them, one verdict per song
+CREATE TABLE "recommendation_feedback" (
+    user_id UUID NOT NULL REFERENCES "users" (user_id) ON DELETE CASCADE,
+    song_id UUID NOT NULL REFERENCES "songs" (song_id) ON DELETE CASCADE,
+    feedback TEXT NOT NULL CHECK (feedback IN ('THUMBS_UP', 'THUMBS_DOWN', 'HIDE', 'NOT_NOW')),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, song_id)
+);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE "songs" DROP COLUMN IF EXISTS "created_by"; --> statement-breakpoint
+ALTER TABLE "users" DROP COLUMN IF EXISTS "is_admin";
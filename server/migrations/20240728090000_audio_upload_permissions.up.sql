@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- Admins can upload audio for any song
+ALTER TABLE "users" ADD COLUMN "is_admin" BOOLEAN NOT NULL DEFAULT FALSE; --> statement-breakpoint
+-- Who added the song, NULL for songs imported into the catalog
+ALTER TABLE "songs" ADD COLUMN "created_by" UUID REFERENCES "users" (user_id) ON DELETE SET NULL;
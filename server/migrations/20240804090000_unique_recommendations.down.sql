@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "recommendations" DROP CONSTRAINT IF EXISTS "recommendations_user_id_song_id_key";
@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- Keep only the best scored row where concurrent requests stored the same song twice
+DELETE FROM "recommendations" AS duplicate USING "recommendations" AS kept
+WHERE duplicate.user_id = kept.user_id AND duplicate.song_id = kept.song_id
+    AND (duplicate.match_score, duplicate.recommendation_id) < (kept.match_score, kept.recommendation_id); --> statement-breakpoint
+ALTER TABLE "recommendations" ADD CONSTRAINT "recommendations_user_id_song_id_key" UNIQUE (user_id, song_id);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+-- pg_trgm stays installed, other database objects may rely on it
+DROP INDEX IF EXISTS "playlists_name_trgm_idx"; --> statement-breakpoint
+DROP INDEX IF EXISTS "albums_title_trgm_idx"; --> statement-breakpoint
+DROP INDEX IF EXISTS "artists_name_trgm_idx"; --> statement-breakpoint
+DROP INDEX IF EXISTS "songs_title_trgm_idx";
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- Trigram indexes let the ILIKE '%query%' searches use an index instead of scanning every row
+CREATE EXTENSION IF NOT EXISTS pg_trgm; --> statement-breakpoint
+CREATE INDEX IF NOT EXISTS "songs_title_trgm_idx" ON "songs" USING GIN (title gin_trgm_ops); --> statement-breakpoint
+CREATE INDEX IF NOT EXISTS "artists_name_trgm_idx" ON "artists" USING GIN (name gin_trgm_ops); --> statement-breakpoint
+CREATE INDEX IF NOT EXISTS "albums_title_trgm_idx" ON "albums" USING GIN (title gin_trgm_ops); --> statement-breakpoint
+CREATE INDEX IF NOT EXISTS "playlists_name_trgm_idx" ON "playlists" USING GIN (name gin_trgm_ops);
@@ -0,0 +1,262 @@
+//! Estimates the audio features stored in `songs` from mono samples.
+
+use rustfft::{num_complex::Complex, FftPlanner};
+
+/// STFT frame and hop, about 93 ms and 23 ms at the analysis rate.
+const FRAME: usize = 2048;
+const HOP: usize = 512;
+/// Tempos outside this range are taken to be a multiple or fraction of the real one.
+const MIN_BPM: f32 = 40.0;
+const MAX_BPM: f32 = 240.0;
+/// Tempo most music is near, octave errors are resolved towards it.
+const PRIOR_BPM: f32 = 120.0;
+/// How strongly beat tracking sticks to the estimated tempo.
+const TIGHTNESS: f32 = 100.0;
+/// Quietest loudness reported, in dBFS.
+const SILENCE_DB: f32 = -60.0;
+
+/// Major and minor key profiles, Krumhansl & Kessler (1982), starting at the tonic.
+const MAJOR_PROFILE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// What the feature estimates need from the spectrum, gathered in one pass over STFT frames of
+/// Hann-windowed audio so the whole spectrogram is never kept in memory.
+pub struct Spectrum {
+    /// Spectral flux of the log magnitudes, one value per frame after the first
+    flux: Vec<f32>,
+    /// Magnitudes between C2 and C7 summed by pitch class, 0 is C
+    chroma: [f32; 12],
+    sample_rate: u32,
+}
+
+impl Spectrum {
+    pub fn new(samples: &[f32], sample_rate: u32) -> Self {
+        let fft = FftPlanner::<f32>::new().plan_fft_forward(FRAME);
+        let window = (0..FRAME)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME as f32).cos())
+            .collect::<Vec<_>>();
+
+        // Pitch class of each bin in the chroma range
+        let pitch_classes = (0..FRAME / 2)
+            .map(|bin| {
+                let frequency = bin as f32 * sample_rate as f32 / FRAME as f32;
+                (65.0..=2100.0).contains(&frequency).then(|| {
+                    let pitch = 12.0 * (frequency / 440.0).log2() + 69.0;
+                    (pitch.round() as i32).rem_euclid(12) as usize
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut flux = Vec::new();
+        let mut chroma = [0.0; 12];
+        let mut previous: Option<Vec<f32>> = None;
+        let mut buffer = Vec::with_capacity(FRAME);
+
+        for frame in samples.windows(FRAME).step_by(HOP) {
+            buffer.clear();
+            buffer.extend(frame.iter().zip(&window).map(|(s, w)| Complex::new(s * w, 0.0)));
+            fft.process(&mut buffer);
+
+            let magnitudes = buffer[..FRAME / 2].iter().map(|bin| bin.norm()).collect::<Vec<_>>();
+            for (magnitude, pitch_class) in magnitudes.iter().zip(&pitch_classes) {
+                if let Some(pitch_class) = pitch_class {
+                    chroma[*pitch_class] += magnitude;
+                }
+            }
+
+            let log_magnitudes = magnitudes.iter().map(|magnitude| (1.0 + 1000.0 * magnitude).ln()).collect::<Vec<_>>();
+            if let Some(previous) = &previous {
+                flux.push(log_magnitudes.iter().zip(previous).map(|(now, before)| (now - before).max(0.0)).sum());
+            }
+            previous = Some(log_magnitudes);
+        }
+
+        Self { flux, chroma, sample_rate }
+    }
+
+    /// Frames per second.
+    fn frame_rate(&self) -> f32 {
+        self.sample_rate as f32 / HOP as f32
+    }
+}
+
+/// Root mean square level in dBFS, floored at `SILENCE_DB`.
+pub fn loudness(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return SILENCE_DB;
+    }
+
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    (20.0 * rms.log10()).max(SILENCE_DB)
+}
+
+/// Tempo in beats per minute, `None` if the audio is too short or has no onsets.
+///
+/// The tempo is first estimated from the autocorrelation of the onset strength, weighted towards
+/// `PRIOR_BPM`. Beats are then tracked with dynamic programming (Ellis, 2007) and the tempo taken
+/// from their average spacing.
+pub fn tempo(spectrum: &Spectrum) -> Option<f32> {
+    let onsets = onset_strength(spectrum);
+    let frame_rate = spectrum.frame_rate();
+
+    let min_lag = (60.0 * frame_rate / MAX_BPM).floor() as usize;
+    let max_lag = (60.0 * frame_rate / MIN_BPM).ceil() as usize;
+    if onsets.len() < max_lag * 2 {
+        return None;
+    }
+
+    let autocorrelation = (0..=max_lag + 1)
+        .map(|lag| onsets.iter().zip(&onsets[lag..]).map(|(a, b)| a * b).sum::<f32>())
+        .collect::<Vec<_>>();
+    if autocorrelation[0] <= 0.0 {
+        return None;
+    }
+
+    let weighted = |lag: usize| {
+        let bpm = 60.0 * frame_rate / lag as f32;
+        autocorrelation[lag] * (-0.5 * (bpm / PRIOR_BPM).log2().powi(2)).exp()
+    };
+    let best = (min_lag.max(1)..=max_lag).max_by(|a, b| weighted(*a).total_cmp(&weighted(*b)))?;
+
+    // Parabolic interpolation between the neighbouring lags for a finer estimate
+    let (before, at, after) = (autocorrelation[best - 1], autocorrelation[best], autocorrelation[best + 1]);
+    let curvature = before - 2.0 * at + after;
+    let offset = if curvature < 0.0 { (0.5 * (before - after) / curvature).clamp(-0.5, 0.5) } else { 0.0 };
+    let period = best as f32 + offset;
+
+    let beats = track_beats(&onsets, period);
+    let period = match (beats.first(), beats.last()) {
+        (Some(first), Some(last)) if beats.len() >= 4 => (last - first) as f32 / (beats.len() - 1) as f32,
+        _ => period,
+    };
+
+    Some(60.0 * frame_rate / period)
+}
+
+/// Spectral flux with the local average removed, normalized.
+fn onset_strength(spectrum: &Spectrum) -> Vec<f32> {
+    let flux = &spectrum.flux;
+
+    // Subtract the average over about half a second, so sustained loud passages don't count as onsets
+    let radius = (spectrum.frame_rate() / 4.0) as usize;
+    let detrended = (0..flux.len())
+        .map(|i| {
+            let window = &flux[i.saturating_sub(radius)..(i + radius + 1).min(flux.len())];
+            (flux[i] - window.iter().sum::<f32>() / window.len() as f32).max(0.0)
+        })
+        .collect::<Vec<_>>();
+
+    let deviation = (detrended.iter().map(|o| o * o).sum::<f32>() / detrended.len().max(1) as f32).sqrt();
+    if deviation == 0.0 {
+        return detrended;
+    }
+
+    detrended.into_iter().map(|o| o / deviation).collect()
+}
+
+/// Frames of the beats that best line up with the onsets while staying close to `period` apart.
+fn track_beats(onsets: &[f32], period: f32) -> Vec<usize> {
+    let mut score = onsets.to_vec();
+    let mut previous = vec![None; onsets.len()];
+
+    for t in 0..onsets.len() {
+        let earliest = (t as f32 - 2.0 * period).round().max(0.0) as usize;
+        let latest = (t as f32 - period / 2.0).round();
+        if latest < 0.0 {
+            continue;
+        }
+
+        let best = (earliest..=latest as usize)
+            .map(|tau| {
+                let penalty = TIGHTNESS * ((t - tau) as f32 / period).ln().powi(2);
+                (tau, score[tau] - penalty)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((tau, best_score)) = best {
+            score[t] = onsets[t] + best_score;
+            previous[t] = Some(tau);
+        }
+    }
+
+    // The last beat is the best scoring frame within the last period
+    let tail = onsets.len().saturating_sub(period.ceil() as usize);
+    let Some(mut beat) = (tail..onsets.len()).max_by(|a, b| score[*a].total_cmp(&score[*b])) else {
+        return Vec::new();
+    };
+
+    let mut beats = vec![beat];
+    while let Some(before) = previous[beat] {
+        beats.push(before);
+        beat = before;
+    }
+    beats.reverse();
+
+    beats
+}
+
+/// Estimated key as a pitch class, 0 is C, 1 is C♯ and so on. `None` for audio without pitched content.
+///
+/// The chroma profile of the spectrum is compared with the major and minor key profiles of every tonic.
+pub fn key(spectrum: &Spectrum) -> Option<i16> {
+    let chroma = &spectrum.chroma;
+    if chroma.iter().all(|energy| *energy == 0.0) {
+        return None;
+    }
+
+    (0..12)
+        .flat_map(|tonic| [(tonic, &MAJOR_PROFILE), (tonic, &MINOR_PROFILE)])
+        .map(|(tonic, profile)| {
+            let rotated = (0..12).map(|pc| profile[(pc + 12 - tonic) % 12]).collect::<Vec<_>>();
+            (tonic, correlation(chroma, &rotated))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(tonic, _)| tonic as i16)
+}
+
+fn correlation(a: &[f32], b: &[f32]) -> f32 {
+    let mean_a = a.iter().sum::<f32>() / a.len() as f32;
+    let mean_b = b.iter().sum::<f32>() / b.len() as f32;
+    let covariance = a.iter().zip(b).map(|(x, y)| (x - mean_a) * (y - mean_b)).sum::<f32>();
+    let deviation_a = a.iter().map(|x| (x - mean_a).powi(2)).sum::<f32>().sqrt();
+    let deviation_b = b.iter().map(|y| (y - mean_b).powi(2)).sum::<f32>().sqrt();
+
+    if deviation_a == 0.0 || deviation_b == 0.0 {
+        return 0.0;
+    }
+
+    covariance / (deviation_a * deviation_b)
+}
+
+/// A rough stand-in for Spotify's speechiness, from 0 for music to 1 for spoken word.
+///
+/// Speech is broken up by short pauses between syllables and words, music rarely is. This is the
+/// share of 20 ms frames quieter than half the average of the second around them (the low energy
+/// ratio), which is about 0.15 or less for music and 0.5 or more for speech, rescaled to 0 to 1.
+pub fn speechiness(samples: &[f32], sample_rate: u32) -> f32 {
+    let frame = (sample_rate / 50) as usize;
+    let levels = samples
+        .chunks_exact(frame.max(1))
+        .map(|chunk| (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt())
+        .collect::<Vec<_>>();
+
+    let mut quiet = 0;
+    let mut counted = 0;
+    for second in levels.chunks(50) {
+        let average = second.iter().sum::<f32>() / second.len() as f32;
+        // Silence has no syllables to count
+        if average < 1e-3 {
+            continue;
+        }
+
+        quiet += second.iter().filter(|level| **level < 0.5 * average).count();
+        counted += second.len();
+    }
+
+    if counted == 0 {
+        return 0.0;
+    }
+
+    let low_energy_ratio = quiet as f32 / counted as f32;
+    ((low_energy_ratio - 0.15) / 0.35).clamp(0.0, 1.0)
+}
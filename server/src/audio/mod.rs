@@ -0,0 +1,282 @@
+//! Decodes song audio, extracts the features stored in `songs` and cuts previews, see `ingest`.
+
+pub mod features;
+pub mod preview;
+
+use std::{fmt, io::Cursor, sync::Arc};
+
+use axum::body::Bytes;
+use common::schema::song::AudioFeatures;
+use sqlx::{Pool, Postgres};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_FLAC, CODEC_TYPE_MP3},
+    errors::Error as DecodeError,
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint
+};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::storage::{BlobStore, StorageError};
+use features::Spectrum;
+use preview::preview_clip;
+
+/// Audio is mixed down to mono and decimated to about this rate before it is analyzed.
+const ANALYSIS_RATE: u32 = 22_050;
+/// Only the start of longer files is analyzed, so a long upload can't use up the memory.
+/// The whole file still counts towards the duration.
+const MAX_ANALYZED_SECONDS: u32 = 600;
+
+#[derive(Debug)]
+pub enum AudioError {
+    /// The file isn't WAV, FLAC or MP3
+    UnsupportedFormat,
+    /// The file claims to be audio but can't be decoded
+    Invalid(String),
+    SongNotFound,
+    Storage(StorageError),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::UnsupportedFormat => write!(f, "Audio must be WAV, FLAC or MP3"),
+            AudioError::Invalid(e) => write!(f, "Invalid audio: {}", e),
+            AudioError::SongNotFound => write!(f, "Song not found"),
+            AudioError::Storage(e) => write!(f, "{}", e),
+            AudioError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl From<StorageError> for AudioError {
+    fn from(e: StorageError) -> Self {
+        AudioError::Storage(e)
+    }
+}
+
+impl From<sqlx::Error> for AudioError {
+    fn from(e: sqlx::Error) -> Self {
+        AudioError::Database(e)
+    }
+}
+
+/// The formats audio can be uploaded in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Wav,
+    Flac,
+    Mp3,
+}
+
+impl AudioFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            AudioFormat::Wav => "wav",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Mp3 => "mp3",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            AudioFormat::Wav => "audio/wav",
+            AudioFormat::Flac => "audio/flac",
+            AudioFormat::Mp3 => "audio/mpeg",
+        }
+    }
+
+    /// The format of a file stored under `audio_key`.
+    pub fn from_key(key: &str) -> Option<AudioFormat> {
+        match key.rsplit_once('.')?.1 {
+            "wav" => Some(AudioFormat::Wav),
+            "flac" => Some(AudioFormat::Flac),
+            "mp3" => Some(AudioFormat::Mp3),
+            _ => None,
+        }
+    }
+}
+
+/// Storage key of a song's audio file.
+pub fn audio_key(song_id: Uuid, format: AudioFormat) -> String {
+    format!("audio/{}.{}", song_id, format.extension())
+}
+
+/// Storage key of a song's preview, always a WAV.
+pub fn preview_key(song_id: Uuid) -> String {
+    format!("previews/{}.wav", song_id)
+}
+
+/// A decoded file, mixed down to mono.
+pub struct Decoded {
+    pub format: AudioFormat,
+    /// The first `MAX_ANALYZED_SECONDS` of audio
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    /// Length of the whole file, in seconds
+    pub duration: f32,
+}
+
+/// Detects the container from the file's contents and decodes its first audio track.
+///
+/// Decoding is CPU bound, call it from `tokio::task::spawn_blocking`.
+pub fn decode(bytes: Vec<u8>) -> Result<Decoded, AudioError> {
+    let source = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+    let mut reader = symphonia::default::get_probe()
+        .format(&Hint::new(), source, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|_| AudioError::UnsupportedFormat)?
+        .format;
+
+    let track = reader.default_track().ok_or_else(|| AudioError::Invalid("no audio track".to_string()))?;
+    let track_id = track.id;
+    // WAV is the only container enabled with uncompressed codecs
+    let format = match track.codec_params.codec {
+        CODEC_TYPE_FLAC => AudioFormat::Flac,
+        CODEC_TYPE_MP3 => AudioFormat::Mp3,
+        _ => AudioFormat::Wav,
+    };
+    let source_rate = track.codec_params.sample_rate.ok_or_else(|| AudioError::Invalid("unknown sample rate".to_string()))?;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|_| AudioError::UnsupportedFormat)?;
+
+    // Averaging every `factor` samples is a crude low-pass filter, enough for what is measured
+    let factor = (source_rate / ANALYSIS_RATE).max(1) as usize;
+    let sample_rate = source_rate / factor as u32;
+    let max_samples = (MAX_ANALYZED_SECONDS * sample_rate) as usize;
+
+    let mut samples = Vec::new();
+    let mut pending = Vec::with_capacity(factor);
+    let mut frames = 0u64;
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match reader.next_packet() {
+            Ok(packet) => packet,
+            Err(DecodeError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(DecodeError::ResetRequired) => break,
+            Err(e) => return Err(AudioError::Invalid(e.to_string())),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet is skipped, as players do
+            Err(DecodeError::DecodeError(_)) => continue,
+            Err(e) => return Err(AudioError::Invalid(e.to_string())),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count();
+        frames += decoded.frames() as u64;
+        if samples.len() >= max_samples {
+            continue;
+        }
+
+        let buffer = match &mut buffer {
+            Some(buffer) if buffer.capacity() >= decoded.capacity() * channels => buffer,
+            _ => buffer.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+        };
+        buffer.copy_interleaved_ref(decoded);
+
+        for frame in buffer.samples().chunks_exact(channels) {
+            pending.push(frame.iter().sum::<f32>() / channels as f32);
+            if pending.len() == factor {
+                samples.push(pending.iter().sum::<f32>() / factor as f32);
+                pending.clear();
+            }
+        }
+    }
+
+    if frames == 0 {
+        return Err(AudioError::Invalid("no audio".to_string()));
+    }
+    samples.truncate(max_samples);
+
+    Ok(Decoded { format, samples, sample_rate, duration: frames as f32 / source_rate as f32 })
+}
+
+/// Estimates every feature of the decoded audio. Also CPU bound.
+pub fn analyze(decoded: &Decoded) -> AudioFeatures {
+    let spectrum = Spectrum::new(&decoded.samples, decoded.sample_rate);
+
+    AudioFeatures {
+        duration: decoded.duration.round().min(i16::MAX as f32) as u16,
+        tempo: features::tempo(&spectrum),
+        key: features::key(&spectrum),
+        loudness: features::loudness(&decoded.samples),
+        speechiness: features::speechiness(&decoded.samples, decoded.sample_rate),
+    }
+}
+
+/// Stores `bytes` as the audio of the song along with a preview clip, analyzes it and writes the
+/// features into the song's columns, replacing any it had. `uploaded_by` is recorded as the owner of the audio.
+pub async fn ingest(
+    db: &Pool<Postgres>,
+    storage: &Arc<dyn BlobStore>,
+    song_id: Uuid,
+    bytes: Vec<u8>,
+    uploaded_by: Option<Uuid>,
+) -> Result<AudioFeatures, AudioError> {
+    let previous_key = sqlx::query_scalar::<_, Option<String>>("SELECT audio_key FROM songs WHERE song_id = $1")
+        .bind(song_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or(AudioError::SongNotFound)?;
+
+    let bytes = Bytes::from(bytes);
+    let (format, features, preview) = {
+        let bytes = bytes.clone();
+        tokio::task::spawn_blocking(move || {
+            decode(bytes.to_vec()).map(|decoded| (decoded.format, analyze(&decoded), preview_clip(&decoded)))
+        })
+        .await
+        // Decoders can panic on malformed input rather than returning an error
+        .map_err(|e| AudioError::Invalid(e.to_string()))??
+    };
+
+    let key = audio_key(song_id, format);
+    storage.put(&key, bytes).await?;
+    storage.put(&preview_key(song_id), Bytes::from(preview)).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE songs SET
+            audio_key = $2,
+            preview_key = $3,
+            audio_uploaded_by = $4,
+            duration = $5,
+            tempo = $6,
+            key = $7,
+            loudness = $8,
+            speechiness = $9
+        WHERE song_id = $1
+        "#,
+    )
+    .bind(song_id)
+    .bind(&key)
+    .bind(preview_key(song_id))
+    .bind(uploaded_by)
+    .bind(features.duration as i16)
+    .bind(features.tempo)
+    .bind(features.key)
+    .bind(features.loudness)
+    .bind(features.speechiness)
+    .execute(db)
+    .await?;
+
+    // A file in another format is left behind under the old key
+    if let Some(previous_key) = previous_key.filter(|previous_key| *previous_key != key) {
+        if let Err(e) = storage.delete(&previous_key).await {
+            warn!("Failed to delete replaced audio {}: {}", previous_key, e);
+        }
+    }
+
+    Ok(features)
+}
@@ -0,0 +1,50 @@
+use super::Decoded;
+
+/// Length of a preview, in seconds.
+pub const PREVIEW_SECONDS: u32 = 30;
+/// Fade in and out, in seconds, so the clip doesn't start or stop with a click.
+const FADE_SECONDS: f32 = 0.5;
+
+/// A `PREVIEW_SECONDS` clip of the decoded audio as a 16-bit mono WAV, at the analysis rate.
+///
+/// Songs often open quietly, so the clip starts a third of the way in. Shorter songs are used whole.
+pub fn preview_clip(decoded: &Decoded) -> Vec<u8> {
+    let length = ((PREVIEW_SECONDS * decoded.sample_rate) as usize).min(decoded.samples.len());
+    let start = (decoded.samples.len() / 3).min(decoded.samples.len() - length);
+    let clip = &decoded.samples[start..start + length];
+
+    let fade = ((FADE_SECONDS * decoded.sample_rate as f32) as usize).min(length / 2).max(1);
+    let data = clip
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| {
+            let gain = (i.min(length - 1 - i) as f32 / fade as f32).min(1.0);
+            ((sample * gain).clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+        })
+        .flat_map(i16::to_le_bytes)
+        .collect::<Vec<_>>();
+
+    wav(&data, decoded.sample_rate)
+}
+
+/// Wraps 16-bit mono PCM in a WAV header.
+fn wav(data: &[u8], sample_rate: u32) -> Vec<u8> {
+    let mut wav = Vec::with_capacity(44 + data.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, one channel
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    // Bytes per second, then per sample
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(data);
+
+    wav
+}
@@ -0,0 +1,174 @@
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+
+use super::ImportError;
+
+/// The formats a dataset can be read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Comma separated, with a header row
+    Csv,
+    /// An array of objects
+    Json,
+}
+
+impl Format {
+    /// Guesses the format from the file extension.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// A row of a dataset as read from the file. The aliases are the column names used by
+/// common public datasets, such as the Spotify tracks datasets on Kaggle.
+#[derive(Debug, Deserialize)]
+pub struct DatasetRow {
+    #[serde(alias = "track_name", alias = "name")]
+    pub title: Option<String>,
+    /// With several artists, separated by `;`, the first one is used
+    #[serde(alias = "artists", alias = "artist_name")]
+    pub artist: Option<String>,
+    #[serde(alias = "album_name")]
+    pub album: Option<String>,
+    /// `YYYY-MM-DD`, `YYYY-MM` or `YYYY`
+    pub release_date: Option<String>,
+    #[serde(alias = "track_genre")]
+    pub genre: Option<String>,
+    pub duration_ms: Option<u32>,
+    pub tempo: Option<f32>,
+    pub time_signature: Option<i16>,
+    pub key: Option<i16>,
+    pub loudness: Option<f32>,
+    pub speechiness: Option<f32>,
+    pub danceability: Option<f32>,
+    #[serde(alias = "url", alias = "track_url")]
+    pub external_url: Option<String>,
+    #[serde(alias = "album_cover", alias = "image_url")]
+    pub cover: Option<String>,
+}
+
+/// A row that passed validation, ready to be imported.
+#[derive(Debug, Clone)]
+pub struct Track {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub release_date: Option<DateTime<Utc>>,
+    /// One of the labels of the `genre` enum
+    pub genre: String,
+    /// In seconds
+    pub duration: i16,
+    pub tempo: Option<f32>,
+    pub time_signature: Option<i16>,
+    /// Pitch class, 0 is C
+    pub key: Option<i16>,
+    pub loudness: Option<f32>,
+    pub speechiness: Option<f32>,
+    pub danceability: Option<f32>,
+    pub external_url: Option<String>,
+    pub cover: Option<String>,
+}
+
+/// Reads every row of the dataset. Rows that can't be parsed are returned as an error message
+/// rather than failing the whole file.
+pub fn read(path: &Path, format: Format) -> Result<Vec<Result<DatasetRow, String>>, ImportError> {
+    let file = File::open(path).map_err(|e| ImportError::Read(format!("Failed to open {}: {}", path.display(), e)))?;
+
+    match format {
+        Format::Csv => {
+            let mut reader = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(file);
+
+            Ok(reader.deserialize::<DatasetRow>().map(|row| row.map_err(|e| e.to_string())).collect())
+        }
+        Format::Json => {
+            let values: Vec<serde_json::Value> = serde_json::from_reader(BufReader::new(file))
+                .map_err(|e| ImportError::Read(format!("{} is not a JSON array: {}", path.display(), e)))?;
+
+            Ok(values.into_iter().map(|value| serde_json::from_value(value).map_err(|e| e.to_string())).collect())
+        }
+    }
+}
+
+impl DatasetRow {
+    /// Checks the row has everything a song needs.
+    /// `genres` maps normalized genre names to the labels of the `genre` enum.
+    pub fn validate(self, genres: &HashMap<String, String>) -> Result<Track, String> {
+        let title = required(self.title, "title")?;
+        let artist = required(self.artist.map(|artists| artists.split(';').next().unwrap_or_default().to_string()), "artist")?;
+        let album = required(self.album, "album")?;
+
+        let genre_name = required(self.genre, "genre")?;
+        let genre = genres
+            .get(&normalize_genre(&genre_name))
+            .cloned()
+            .ok_or_else(|| format!("unknown genre `{}`", genre_name))?;
+
+        let duration_ms = self.duration_ms.ok_or_else(|| "missing duration_ms".to_string())?;
+        let duration = i16::try_from((duration_ms + 500) / 1000)
+            .map_err(|_| format!("duration of {} ms is too long", duration_ms))?;
+
+        let release_date = match self.release_date.filter(|date| !date.is_empty()) {
+            Some(date) => Some(parse_release_date(&date).ok_or_else(|| format!("invalid release_date `{}`", date))?),
+            None => None,
+        };
+
+        Ok(Track {
+            title,
+            artist,
+            album,
+            release_date,
+            genre,
+            duration,
+            tempo: self.tempo,
+            time_signature: self.time_signature,
+            // Spotify uses -1 for songs it couldn't detect a key for
+            key: self.key.filter(|key| (0..12).contains(key)),
+            loudness: self.loudness,
+            speechiness: self.speechiness,
+            danceability: self.danceability,
+            external_url: self.external_url.filter(|url| !url.is_empty()),
+            cover: self.cover.filter(|url| !url.is_empty()),
+        })
+    }
+}
+
+fn required(value: Option<String>, name: &str) -> Result<String, String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("missing {}", name))
+}
+
+fn parse_release_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = match date.len() {
+        4 => NaiveDate::from_ymd_opt(date.parse().ok()?, 1, 1)?,
+        7 => NaiveDate::parse_from_str(&format!("{}-01", date), "%Y-%m-%d").ok()?,
+        _ => NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()?,
+    };
+
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+/// Lowercases, drops punctuation and collapses whitespace, so `The Beatles` and `the  beatles!`
+/// are the same artist.
+pub fn normalize(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Like `normalize`, but ignoring spaces and hyphens too, so `hip hop`, `hip-hop` and `Hip-Hop`
+/// match, as do `r-n-b` and `R&B`.
+pub fn normalize_genre(s: &str) -> String {
+    s.to_lowercase().replace('&', "n").chars().filter(|c| c.is_alphanumeric()).collect()
+}
@@ -0,0 +1,276 @@
+//! Bulk-loads songs, artists and albums into the catalog from public datasets, see `import`.
+
+pub mod dataset;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::Path
+};
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, Pool, Postgres};
+use uuid::Uuid;
+
+use dataset::{normalize, normalize_genre, Format, Track};
+
+#[derive(Debug)]
+pub enum ImportError {
+    /// The dataset could not be read
+    Read(String),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Read(e) => write!(f, "Failed to read dataset: {}", e),
+            ImportError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ImportError {
+    fn from(e: sqlx::Error) -> Self {
+        ImportError::Database(e)
+    }
+}
+
+/// A row that wasn't imported, and why.
+#[derive(Debug)]
+pub struct Rejection {
+    /// 1-based, not counting the CSV header
+    pub row: usize,
+    pub reason: String,
+}
+
+/// What an import added to the catalog.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub artists: usize,
+    pub albums: usize,
+    pub songs: usize,
+    /// Songs already in the catalog, or earlier in the dataset
+    pub duplicates: usize,
+    pub rejected: Vec<Rejection>,
+}
+
+struct NewArtist {
+    artist_id: Uuid,
+    name: String,
+}
+
+struct NewAlbum {
+    album_id: Uuid,
+    artist_id: Uuid,
+    title: String,
+    release_date: Option<DateTime<Utc>>,
+    genre: String,
+    cover: Option<String>,
+}
+
+struct NewSong {
+    song_id: Uuid,
+    artist_id: Uuid,
+    album_id: Uuid,
+    track: Track,
+}
+
+/// Imports every valid row of the dataset at `path`, in a single transaction.
+///
+/// Songs are deduplicated by their normalized title and artist, against the catalog and within
+/// the dataset. Artists and albums are matched by normalized name the same way, so importing a
+/// dataset twice adds nothing. Rows with a genre missing from the `genre` enum, or without the
+/// fields a song needs, are rejected and listed in the report.
+///
+/// With `dry_run` the report is built without writing anything.
+pub async fn import(db: &Pool<Postgres>, path: &Path, format: Format, dry_run: bool) -> Result<ImportReport, ImportError> {
+    let genres = sqlx::query_scalar::<_, String>("SELECT unnest(enum_range(NULL::genre))::text")
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|genre| (normalize_genre(&genre), genre))
+        .collect::<HashMap<_, _>>();
+
+    let mut artists = sqlx::query_as::<_, (Uuid, String)>("SELECT artist_id, name FROM artists")
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|(artist_id, name)| (normalize(&name), artist_id))
+        .collect::<HashMap<_, _>>();
+
+    let mut albums = sqlx::query_as::<_, (Uuid, Uuid, String)>("SELECT album_id, artist_id, title FROM albums")
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|(album_id, artist_id, title)| ((artist_id, normalize(&title)), album_id))
+        .collect::<HashMap<_, _>>();
+
+    let mut songs = sqlx::query_as::<_, (String, String)>(
+        "SELECT songs.title, artists.name FROM songs JOIN artists ON artists.artist_id = songs.artist_id",
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|(title, artist)| (normalize(&title), normalize(&artist)))
+    .collect::<HashSet<_>>();
+
+    let mut report = ImportReport::default();
+    let mut new_artists = Vec::new();
+    let mut new_albums = Vec::new();
+    let mut new_songs = Vec::new();
+
+    for (index, row) in dataset::read(path, format)?.into_iter().enumerate() {
+        let track = match row.and_then(|row| row.validate(&genres)) {
+            Ok(track) => track,
+            Err(reason) => {
+                report.rejected.push(Rejection { row: index + 1, reason });
+                continue;
+            }
+        };
+
+        if !songs.insert((normalize(&track.title), normalize(&track.artist))) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        let artist_id = *artists.entry(normalize(&track.artist)).or_insert_with(|| {
+            let artist_id = Uuid::new_v4();
+            new_artists.push(NewArtist { artist_id, name: track.artist.clone() });
+            artist_id
+        });
+
+        let album_id = *albums.entry((artist_id, normalize(&track.album))).or_insert_with(|| {
+            let album_id = Uuid::new_v4();
+            new_albums.push(NewAlbum {
+                album_id,
+                artist_id,
+                title: track.album.clone(),
+                release_date: track.release_date,
+                genre: track.genre.clone(),
+                cover: track.cover.clone(),
+            });
+            album_id
+        });
+
+        new_songs.push(NewSong { song_id: Uuid::new_v4(), artist_id, album_id, track });
+    }
+
+    report.artists = new_artists.len();
+    report.albums = new_albums.len();
+    report.songs = new_songs.len();
+
+    if dry_run || new_songs.is_empty() {
+        return Ok(report);
+    }
+
+    let mut tx = db.begin().await?;
+
+    copy_in(
+        &mut tx,
+        "COPY artists (artist_id, name) FROM STDIN (FORMAT csv)",
+        new_artists.iter().map(|artist| vec![Some(artist.artist_id.to_string()), Some(artist.name.clone())]),
+    )
+    .await?;
+
+    copy_in(
+        &mut tx,
+        "COPY albums (album_id, artist_id, title, release_date, genre, cover) FROM STDIN (FORMAT csv)",
+        new_albums.iter().map(|album| {
+            vec![
+                Some(album.album_id.to_string()),
+                Some(album.artist_id.to_string()),
+                Some(album.title.clone()),
+                album.release_date.map(|date| date.to_rfc3339()),
+                Some(album.genre.clone()),
+                album.cover.clone(),
+            ]
+        }),
+    )
+    .await?;
+
+    copy_in(
+        &mut tx,
+        "COPY songs (song_id, artist_id, album_id, title, duration, genre, external_url, tempo, time_signature, key, loudness, speechiness, danceability) FROM STDIN (FORMAT csv)",
+        new_songs.iter().map(|song| {
+            let track = &song.track;
+            vec![
+                Some(song.song_id.to_string()),
+                Some(song.artist_id.to_string()),
+                Some(song.album_id.to_string()),
+                Some(track.title.clone()),
+                Some(track.duration.to_string()),
+                Some(track.genre.clone()),
+                Some(array_literal(track.external_url.iter())),
+                track.tempo.map(|value| value.to_string()),
+                track.time_signature.map(|value| value.to_string()),
+                track.key.map(|value| value.to_string()),
+                track.loudness.map(|value| value.to_string()),
+                track.speechiness.map(|value| value.to_string()),
+                track.danceability.map(|value| value.to_string()),
+            ]
+        }),
+    )
+    .await?;
+
+    // Albums and artists keep lists of their tracks, rebuild them for everything that got new songs
+    let album_ids = new_songs.iter().map(|song| song.album_id).collect::<HashSet<_>>().into_iter().collect::<Vec<_>>();
+    let artist_ids = new_songs.iter().map(|song| song.artist_id).collect::<HashSet<_>>().into_iter().collect::<Vec<_>>();
+
+    sqlx::query(
+        r#"
+        UPDATE albums SET tracks = ARRAY(SELECT song_id FROM songs WHERE songs.album_id = albums.album_id)
+        WHERE album_id = ANY($1)
+        "#,
+    )
+    .bind(&album_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE artists SET
+            albums = ARRAY(SELECT album_id FROM albums WHERE albums.artist_id = artists.artist_id),
+            tracks = ARRAY(SELECT song_id FROM songs WHERE songs.artist_id = artists.artist_id),
+            genres = ARRAY(SELECT DISTINCT genre FROM songs WHERE songs.artist_id = artists.artist_id)
+        WHERE artist_id = ANY($1)
+        "#,
+    )
+    .bind(&artist_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+/// Streams `rows` into a `COPY ... FROM STDIN (FORMAT csv)` statement. `None` is written as NULL.
+async fn copy_in(
+    conn: &mut PgConnection,
+    statement: &str,
+    rows: impl Iterator<Item = Vec<Option<String>>>,
+) -> Result<u64, ImportError> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+    for row in rows {
+        // An unquoted empty field is NULL, a quoted one an empty string, and nothing written is ever empty
+        writer
+            .write_record(row.iter().map(|field| field.as_deref().unwrap_or("")))
+            .map_err(|e| ImportError::Read(e.to_string()))?;
+    }
+    let data = writer.into_inner().map_err(|e| ImportError::Read(e.to_string()))?;
+
+    let mut copy = conn.copy_in_raw(statement).await?;
+    copy.send(data).await?;
+
+    Ok(copy.finish().await?)
+}
+
+/// A Postgres array literal, such as `{"a","b"}`.
+fn array_literal<'a>(values: impl Iterator<Item = &'a String>) -> String {
+    let values = values
+        .map(|value| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>();
+
+    format!("{{{}}}", values.join(","))
+}
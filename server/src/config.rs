@@ -1,32 +1,493 @@
-#[derive(Debug, Clone)]
+use axum_extra::extract::cookie::SameSite;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+/// The server's settings. Loaded once at startup by `Config::load` and kept in `AppState`.
+///
+/// Each layer overrides the one before it:
+/// 1. The defaults below
+/// 2. A TOML file, `config.toml` or the file named by `MELODY_CONFIG`
+/// 3. The environment variables listed in `ENV_VARS`
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_config"))]
 pub struct Config {
-    pub database_url: String,
-    pub jwt_secret: String,
-    pub jwt_expires_in: String,
-    pub jwt_maxage: i32,
+    #[validate]
+    pub database: DatabaseConfig,
+    #[validate]
+    pub server: ServerConfig,
+    #[validate(url(message = "must be a URL"))]
+    pub client_url: String,
+    /// When the unversioned `/api/...` routes will be removed, as an HTTP date, sent in their `Sunset` header
+    pub legacy_api_sunset: Option<String>,
+    #[validate]
+    pub jwt: JwtConfig,
+    pub cookies: CookieConfig,
+    #[validate]
+    pub mail: MailConfig,
+    #[validate]
+    pub password: PasswordConfig,
+    #[validate]
+    pub rate_limit: RateLimitConfig,
+    #[validate]
+    pub storage: StorageConfig,
+    #[validate]
+    pub ml: MlConfig,
 }
 
-impl Config {
-    pub fn init() -> Config {
-        // Conditional function to load env file based on environment
-        if cfg!(debug_assertions) {
-            // Load from `.env.local` in development
-            dotenv::from_filename(".env.local").ok().expect("Failed to load .env.local file");
-        } else {
-            // Load from `.env` in production
-            dotenv::dotenv().ok().expect("Failed to load .env file");
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct DatabaseConfig {
+    #[validate(length(min = 1, message = "must be set"))]
+    pub url: String,
+    /// Size of the connection pool
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub max_connections: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct ServerConfig {
+    pub port: u16,
+    /// Origins allowed to call the API from a browser, with credentials
+    #[validate(length(min = 1, message = "must list at least one origin"), custom = "validate_origins")]
+    pub cors_origins: Vec<String>,
+    /// Whether a reverse proxy in front of the server sets `X-Real-IP`. Only then is the header
+    /// trusted as the client's IP, otherwise clients could pick their own rate limit bucket.
+    pub trust_proxy: bool,
+}
+
+/// How access and refresh tokens are signed and how long they last.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct JwtConfig {
+    /// Shared HS256 secret, empty if unset. Only used for signing when no key files are configured
+    pub secret: String,
+    /// Directory of `<kid>.pem` RSA/Ed25519 private keys used to sign tokens
+    pub keys_dir: String,
+    /// Key id new tokens are signed with, defaults to the newest key in `keys_dir`
+    pub active_kid: Option<String>,
+    /// Lifetime of access tokens, in seconds
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub access_token_maxage: i64,
+    /// Lifetime of refresh tokens, in seconds
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub refresh_token_maxage: i64,
+}
+
+/// Attributes of the session cookies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookieConfig {
+    /// Only send the cookies over HTTPS, on by default in release builds
+    pub secure: bool,
+    pub same_site: SameSitePolicy,
+    /// Domain the cookies are set for, defaults to the API's host
+    pub domain: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SameSitePolicy {
+    Strict,
+    Lax,
+    None,
+}
+
+impl From<SameSitePolicy> for SameSite {
+    fn from(policy: SameSitePolicy) -> Self {
+        match policy {
+            SameSitePolicy::Strict => SameSite::Strict,
+            SameSitePolicy::Lax => SameSite::Lax,
+            SameSitePolicy::None => SameSite::None,
         }
+    }
+}
+
+/// Argon2id cost parameters used when hashing passwords.
+/// Changing them causes existing hashes to be upgraded on the user's next login.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct PasswordConfig {
+    /// Memory cost in KiB
+    #[validate(range(min = 8, message = "must be at least 8"))]
+    pub memory_kib: u32,
+    /// Number of iterations
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub iterations: u32,
+    /// Degree of parallelism
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub parallelism: u32,
+}
+
+/// A token bucket: up to `burst` requests at once, refilled at `per_minute` requests per minute.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Validate)]
+pub struct RateLimit {
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub burst: u32,
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub per_minute: u32,
+}
+
+/// How failed logins lock an account: after `max_failures` consecutive failures the account is
+/// locked for `base_seconds`, doubling with every further failure up to `max_seconds`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_lockout"))]
+pub struct LockoutPolicy {
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub max_failures: u32,
+    pub base_seconds: u32,
+    pub max_seconds: u32,
+}
 
-        let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-        let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
-        let jwt_expires_in = std::env::var("JWT_EXPIRED_IN").expect("JWT_EXPIRED_IN must be set");
-        let jwt_maxage = std::env::var("JWT_MAXAGE").expect("JWT_MAXAGE must be set");
-        
+/// Rate limits per route group and for login attempts.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RateLimitConfig {
+    /// Where limiter state is kept: `memory` or `postgres` (shared between instances)
+    #[validate(custom = "validate_rate_limit_store")]
+    pub store: String,
+    /// Per-IP limit for `/api/v1/auth/*`
+    #[validate]
+    pub auth: RateLimit,
+    /// Per-IP limit for every other route
+    #[validate]
+    pub api: RateLimit,
+    /// Per-account limit for login attempts, regardless of IP
+    #[validate]
+    pub login_account: RateLimit,
+    #[validate]
+    pub lockout: LockoutPolicy,
+}
+
+/// Settings for the outgoing mail transport.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_mail"))]
+pub struct MailConfig {
+    /// Which `Mailer` implementation to use: `smtp` or `file`
+    #[validate(custom = "validate_mail_transport")]
+    pub transport: String,
+    /// The `From` header used for every outgoing email
+    #[validate(length(min = 1, message = "must be set"))]
+    pub from: String,
+    /// Directory the file mailer writes `.eml` files into
+    pub dir: String,
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<u16>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+}
+
+/// Where uploaded files such as avatars and song audio are stored.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_storage"))]
+pub struct StorageConfig {
+    /// Which `BlobStore` implementation to use: `local` or `s3`
+    #[validate(custom = "validate_storage_backend")]
+    pub backend: String,
+    /// Directory the local store writes files into
+    pub dir: String,
+    /// Base URL stored files are served from, the blob key is appended to it
+    #[validate(url(message = "must be a URL"))]
+    pub public_url: String,
+    /// Largest avatar upload accepted, in bytes
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub max_avatar_bytes: usize,
+    /// Largest song audio upload accepted, in bytes
+    #[validate(range(min = 1, message = "must be at least 1"))]
+    pub max_audio_bytes: usize,
+    /// Bucket avatars are stored in, which has to be publicly readable
+    pub s3_bucket: Option<String>,
+    /// Bucket song audio and previews are stored in, which must not be. They're only streamed
+    /// by the server, to logged in users.
+    pub s3_private_bucket: Option<String>,
+    pub s3_region: String,
+    /// Endpoint of an S3-compatible service, defaults to AWS
+    pub s3_endpoint: Option<String>,
+    pub s3_access_key_id: Option<String>,
+    pub s3_secret_access_key: Option<String>,
+}
+
+/// The embedding model used for recommendations, the arguments of `ml::model::BertEmbeddingsModel::new`.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct MlConfig {
+    /// Hugging Face repository of the BERT model
+    #[validate(length(min = 1, message = "must be set"))]
+    pub model: String,
+    /// Branch, tag or commit of the model repository
+    #[validate(length(min = 1, message = "must be set"))]
+    pub revision: String,
+    /// Safetensors file of precomputed song embeddings. Only read when the model is loaded, so a
+    /// missing file doesn't stop the server from starting.
+    pub embeddings_file: Option<String>,
+    /// Name of the tensor in `embeddings_file`
+    pub embeddings_key: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
         Config {
-            database_url,
-            jwt_secret,
-            jwt_expires_in,
-            jwt_maxage: jwt_maxage.parse::<i32>().unwrap(),
+            database: DatabaseConfig {
+                url: String::new(),
+                max_connections: 10,
+            },
+            server: ServerConfig {
+                port: 8000,
+                cors_origins: vec!["http://localhost:3000".to_string()],
+                trust_proxy: false,
+            },
+            client_url: "http://localhost:3000".to_string(),
+            legacy_api_sunset: None,
+            jwt: JwtConfig {
+                secret: String::new(),
+                keys_dir: "keys".to_string(),
+                active_kid: None,
+                access_token_maxage: 60 * 60,
+                refresh_token_maxage: 7 * 24 * 60 * 60,
+            },
+            cookies: CookieConfig {
+                secure: !cfg!(debug_assertions),
+                same_site: SameSitePolicy::Lax,
+                domain: None,
+            },
+            mail: MailConfig {
+                transport: "file".to_string(),
+                from: "Rusty Melody <no-reply@rustymelody.com>".to_string(),
+                dir: "mail".to_string(),
+                smtp_host: None,
+                smtp_port: None,
+                smtp_username: None,
+                smtp_password: None,
+            },
+            password: PasswordConfig {
+                memory_kib: 19456,
+                iterations: 2,
+                parallelism: 1,
+            },
+            rate_limit: RateLimitConfig {
+                store: "memory".to_string(),
+                auth: RateLimit { burst: 10, per_minute: 10 },
+                api: RateLimit { burst: 60, per_minute: 120 },
+                login_account: RateLimit { burst: 5, per_minute: 5 },
+                lockout: LockoutPolicy {
+                    max_failures: 5,
+                    base_seconds: 30,
+                    max_seconds: 3600,
+                },
+            },
+            storage: StorageConfig {
+                backend: "local".to_string(),
+                dir: "uploads".to_string(),
+                public_url: "http://localhost:8000/uploads".to_string(),
+                max_avatar_bytes: 5 * 1024 * 1024,
+                max_audio_bytes: 50 * 1024 * 1024,
+                s3_bucket: None,
+                s3_private_bucket: None,
+                s3_region: "us-east-1".to_string(),
+                s3_endpoint: None,
+                s3_access_key_id: None,
+                s3_secret_access_key: None,
+            },
+            ml: MlConfig {
+                model: "sentence-transformers/all-MiniLM-L6-v2".to_string(),
+                revision: "main".to_string(),
+                embeddings_file: None,
+                embeddings_key: "embeddings".to_string(),
+            },
+        }
+    }
+}
+
+/// The environment variables that override settings, and the setting each one sets.
+const ENV_VARS: &[(&str, &str)] = &[
+    ("DATABASE_URL", "database.url"),
+    ("DATABASE_MAX_CONNECTIONS", "database.max_connections"),
+    ("SERVER_PORT", "server.port"),
+    // Comma separated
+    ("CORS_ORIGINS", "server.cors_origins"),
+    ("TRUST_PROXY", "server.trust_proxy"),
+    ("CLIENT_URL", "client_url"),
+    ("LEGACY_API_SUNSET", "legacy_api_sunset"),
+    ("JWT_SECRET", "jwt.secret"),
+    ("JWT_KEYS_DIR", "jwt.keys_dir"),
+    ("JWT_ACTIVE_KID", "jwt.active_kid"),
+    ("JWT_MAXAGE", "jwt.access_token_maxage"),
+    ("JWT_REFRESH_MAXAGE", "jwt.refresh_token_maxage"),
+    ("COOKIE_SECURE", "cookies.secure"),
+    ("COOKIE_SAME_SITE", "cookies.same_site"),
+    ("COOKIE_DOMAIN", "cookies.domain"),
+    ("MAIL_TRANSPORT", "mail.transport"),
+    ("MAIL_FROM", "mail.from"),
+    ("MAIL_DIR", "mail.dir"),
+    ("SMTP_HOST", "mail.smtp_host"),
+    ("SMTP_PORT", "mail.smtp_port"),
+    ("SMTP_USERNAME", "mail.smtp_username"),
+    ("SMTP_PASSWORD", "mail.smtp_password"),
+    ("ARGON2_MEMORY_KIB", "password.memory_kib"),
+    ("ARGON2_ITERATIONS", "password.iterations"),
+    ("ARGON2_PARALLELISM", "password.parallelism"),
+    ("RATE_LIMIT_STORE", "rate_limit.store"),
+    ("RATE_LIMIT_AUTH_BURST", "rate_limit.auth.burst"),
+    ("RATE_LIMIT_AUTH_PER_MINUTE", "rate_limit.auth.per_minute"),
+    ("RATE_LIMIT_API_BURST", "rate_limit.api.burst"),
+    ("RATE_LIMIT_API_PER_MINUTE", "rate_limit.api.per_minute"),
+    ("RATE_LIMIT_LOGIN_BURST", "rate_limit.login_account.burst"),
+    ("RATE_LIMIT_LOGIN_PER_MINUTE", "rate_limit.login_account.per_minute"),
+    ("LOGIN_MAX_FAILURES", "rate_limit.lockout.max_failures"),
+    ("LOGIN_LOCKOUT_SECONDS", "rate_limit.lockout.base_seconds"),
+    ("LOGIN_LOCKOUT_MAX_SECONDS", "rate_limit.lockout.max_seconds"),
+    ("STORAGE_BACKEND", "storage.backend"),
+    ("STORAGE_DIR", "storage.dir"),
+    ("STORAGE_PUBLIC_URL", "storage.public_url"),
+    ("AVATAR_MAX_BYTES", "storage.max_avatar_bytes"),
+    ("AUDIO_MAX_BYTES", "storage.max_audio_bytes"),
+    ("S3_BUCKET", "storage.s3_bucket"),
+    ("S3_PRIVATE_BUCKET", "storage.s3_private_bucket"),
+    ("S3_REGION", "storage.s3_region"),
+    ("S3_ENDPOINT", "storage.s3_endpoint"),
+    ("S3_ACCESS_KEY_ID", "storage.s3_access_key_id"),
+    ("S3_SECRET_ACCESS_KEY", "storage.s3_secret_access_key"),
+    ("ML_MODEL", "ml.model"),
+    ("ML_MODEL_REVISION", "ml.revision"),
+    ("ML_EMBEDDINGS_FILE", "ml.embeddings_file"),
+    ("ML_EMBEDDINGS_KEY", "ml.embeddings_key"),
+];
+
+impl Config {
+    /// Loads and validates the settings.
+    /// On failure the error lists every problem found, one per line.
+    pub fn load() -> Result<Config, String> {
+        let (file, required) = match std::env::var("MELODY_CONFIG") {
+            Ok(file) => (file, true),
+            Err(_) => ("config.toml".to_string(), false),
+        };
+
+        let defaults = ::config::Config::try_from(&Config::default()).map_err(|e| e.to_string())?;
+        let mut builder = ::config::Config::builder()
+            .add_source(defaults)
+            .add_source(::config::File::new(&file, ::config::FileFormat::Toml).required(required));
+
+        for (name, key) in ENV_VARS {
+            let Ok(value) = std::env::var(name) else { continue };
+            builder = if *key == "server.cors_origins" {
+                let origins = value.split(',').map(|origin| origin.trim().to_string()).collect::<Vec<_>>();
+                builder.set_override(*key, origins)
+            } else {
+                builder.set_override(*key, value)
+            }
+            .map_err(|e| e.to_string())?;
         }
+
+        let config = builder
+            .build()
+            .and_then(|config| config.try_deserialize::<Config>())
+            .map_err(|e| e.to_string())?;
+
+        config.validate().map_err(|errors| {
+            let mut problems = Vec::new();
+            report(&errors, "", &mut problems);
+            problems.join("\n")
+        })?;
+
+        Ok(config)
     }
 }
+
+/// Flattens validation errors into `setting.path: message` lines.
+fn report(errors: &ValidationErrors, path: &str, problems: &mut Vec<String>) {
+    for (field, kind) in errors.errors() {
+        let path = match (path, *field) {
+            ("", "__all__") => "config".to_string(),
+            (path, "__all__") => path.to_string(),
+            ("", field) => field.to_string(),
+            (path, field) => format!("{}.{}", path, field),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                for error in errors {
+                    let message = error.message.clone().unwrap_or_else(|| error.code.clone());
+                    problems.push(format!("{}: {}", path, message));
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => report(errors, &path, problems),
+            ValidationErrorsKind::List(errors) => {
+                for (index, errors) in errors {
+                    report(errors, &format!("{}[{}]", path, index), problems);
+                }
+            }
+        }
+    }
+}
+
+fn invalid(message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new("invalid");
+    error.message = Some(message.into());
+    error
+}
+
+fn validate_config(config: &Config) -> Result<(), ValidationError> {
+    if config.jwt.refresh_token_maxage < config.jwt.access_token_maxage {
+        return Err(invalid("jwt.refresh_token_maxage must not be shorter than jwt.access_token_maxage"));
+    }
+    if matches!(config.cookies.same_site, SameSitePolicy::None) && !config.cookies.secure {
+        return Err(invalid("cookies.same_site = \"none\" requires cookies.secure"));
+    }
+
+    Ok(())
+}
+
+fn validate_origins(origins: &[String]) -> Result<(), ValidationError> {
+    if origins.iter().any(|origin| axum::http::HeaderValue::from_str(origin).is_err() || !validator::validate_url(origin)) {
+        return Err(invalid("must all be URLs"));
+    }
+
+    Ok(())
+}
+
+fn validate_lockout(lockout: &LockoutPolicy) -> Result<(), ValidationError> {
+    if lockout.max_seconds < lockout.base_seconds {
+        return Err(invalid("max_seconds must not be less than base_seconds"));
+    }
+
+    Ok(())
+}
+
+fn validate_rate_limit_store(store: &str) -> Result<(), ValidationError> {
+    match store {
+        "memory" | "postgres" => Ok(()),
+        _ => Err(invalid("must be `memory` or `postgres`")),
+    }
+}
+
+fn validate_mail_transport(transport: &str) -> Result<(), ValidationError> {
+    match transport {
+        "smtp" | "file" => Ok(()),
+        _ => Err(invalid("must be `smtp` or `file`")),
+    }
+}
+
+fn validate_mail(mail: &MailConfig) -> Result<(), ValidationError> {
+    if mail.transport == "smtp" && mail.smtp_host.is_none() {
+        return Err(invalid("smtp_host must be set to send mail over SMTP"));
+    }
+
+    Ok(())
+}
+
+fn validate_storage_backend(backend: &str) -> Result<(), ValidationError> {
+    match backend {
+        "local" | "s3" => Ok(()),
+        _ => Err(invalid("must be `local` or `s3`")),
+    }
+}
+
+fn validate_storage(storage: &StorageConfig) -> Result<(), ValidationError> {
+    if storage.backend != "s3" {
+        return Ok(());
+    }
+
+    match (&storage.s3_bucket, &storage.s3_private_bucket) {
+        (None, _) => return Err(invalid("s3_bucket must be set to store files in S3")),
+        (_, None) => return Err(invalid("s3_private_bucket must be set to store song audio in S3")),
+        (Some(bucket), Some(private_bucket)) if bucket == private_bucket => {
+            return Err(invalid("s3_private_bucket must not be the public s3_bucket"));
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
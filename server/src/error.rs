@@ -0,0 +1,211 @@
+use std::fmt;
+
+use axum::{
+    extract::{multipart::MultipartError, rejection::JsonRejection},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json
+};
+use common::schema::error::{ErrorCode, ErrorResponse, FieldErrors};
+use tracing::error;
+use validator::ValidationErrors;
+
+use crate::{audio::AudioError, storage::StorageError, utils::jwt::AuthError};
+
+/// Shown instead of the details of unexpected errors, which are logged rather than sent to the client.
+const INTERNAL_ERROR_MESSAGE: &str = "Something went wrong, please try again later";
+
+/// Every way a request can fail. Each variant maps to an HTTP status and a stable `ErrorCode`,
+/// and is sent to the client as an `ErrorResponse`.
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest(String),
+    Validation(ValidationErrors),
+    InvalidCredentials(String),
+    InvalidCode,
+    InvalidLink(String),
+    Unauthenticated(String),
+    ChallengeExpired,
+    EmailNotVerified,
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    PayloadTooLarge(String),
+    UnsupportedMediaType(String),
+    TooManyRequests { message: String, retry_after: u64 },
+    ServiceUnavailable(String),
+    Database(sqlx::Error),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_)
+            | AppError::Validation(_)
+            | AppError::InvalidCredentials(_)
+            | AppError::InvalidCode
+            | AppError::InvalidLink(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthenticated(_) | AppError::ChallengeExpired => StatusCode::UNAUTHORIZED,
+            AppError::EmailNotVerified | AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Database(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::BadRequest(_) => ErrorCode::BadRequest,
+            AppError::Validation(_) => ErrorCode::ValidationFailed,
+            AppError::InvalidCredentials(_) => ErrorCode::InvalidCredentials,
+            AppError::InvalidCode => ErrorCode::InvalidCode,
+            AppError::InvalidLink(_) => ErrorCode::InvalidLink,
+            AppError::Unauthenticated(_) => ErrorCode::Unauthenticated,
+            AppError::ChallengeExpired => ErrorCode::ChallengeExpired,
+            AppError::EmailNotVerified => ErrorCode::EmailNotVerified,
+            AppError::Forbidden(_) => ErrorCode::Forbidden,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::Conflict(_) => ErrorCode::Conflict,
+            AppError::PayloadTooLarge(_) => ErrorCode::PayloadTooLarge,
+            AppError::UnsupportedMediaType(_) => ErrorCode::UnsupportedMediaType,
+            AppError::TooManyRequests { .. } => ErrorCode::RateLimited,
+            AppError::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
+            AppError::Database(_) | AppError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// The message sent to the client. Never includes the details of unexpected errors.
+    fn client_message(&self) -> String {
+        match self {
+            AppError::Validation(errors) => errors
+                .field_errors()
+                .values()
+                .flat_map(|errors| errors.iter())
+                .map(|error| error.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            AppError::Database(_) | AppError::Internal(_) => INTERNAL_ERROR_MESSAGE.to_string(),
+            e => e.to_string(),
+        }
+    }
+
+    /// The messages of a failed validation, by field.
+    fn fields(&self) -> FieldErrors {
+        match self {
+            AppError::Validation(errors) => errors
+                .field_errors()
+                .into_iter()
+                .map(|(field, errors)| (field.to_string(), errors.iter().map(|error| error.to_string()).collect()))
+                .collect(),
+            _ => FieldErrors::new(),
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::BadRequest(message)
+            | AppError::InvalidCredentials(message)
+            | AppError::InvalidLink(message)
+            | AppError::Unauthenticated(message)
+            | AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::PayloadTooLarge(message)
+            | AppError::UnsupportedMediaType(message)
+            | AppError::TooManyRequests { message, .. }
+            | AppError::ServiceUnavailable(message)
+            | AppError::Internal(message) => write!(f, "{}", message),
+            AppError::Validation(errors) => write!(f, "Validation failed: {}", errors),
+            AppError::InvalidCode => write!(f, "Invalid code"),
+            AppError::ChallengeExpired => write!(f, "Invalid or expired login challenge, please log in again"),
+            AppError::EmailNotVerified => write!(f, "Please verify your email before logging in"),
+            AppError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if status.is_server_error() {
+            error!("{}", self);
+        }
+
+        let error_response = ErrorResponse {
+            status: if status.is_server_error() { "error" } else { "fail" }.to_string(),
+            code: self.code(),
+            message: self.client_message(),
+            fields: self.fields(),
+        };
+
+        let mut response = (status, Json(error_response)).into_response();
+        if let AppError::TooManyRequests { retry_after, .. } = self {
+            response.headers_mut().insert(header::RETRY_AFTER, retry_after.into());
+        }
+
+        response
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        AppError::Database(e)
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        AppError::Validation(errors)
+    }
+}
+
+impl From<AuthError> for AppError {
+    fn from(e: AuthError) -> Self {
+        match e {
+            AuthError::InvalidToken => AppError::Unauthenticated("Invalid token".to_string()),
+            AuthError::WrongCredentials => AppError::InvalidCredentials("Wrong credentials".to_string()),
+            AuthError::MissingCredentials => AppError::Unauthenticated("You are not logged in, please provide token".to_string()),
+            AuthError::TokenCreation => AppError::Internal("Failed to create token".to_string()),
+        }
+    }
+}
+
+impl From<StorageError> for AppError {
+    fn from(e: StorageError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<AudioError> for AppError {
+    fn from(e: AudioError) -> Self {
+        match e {
+            AudioError::UnsupportedFormat => AppError::UnsupportedMediaType(e.to_string()),
+            AudioError::Invalid(_) => AppError::BadRequest(e.to_string()),
+            AudioError::SongNotFound => AppError::NotFound(e.to_string()),
+            AudioError::Storage(e) => e.into(),
+            AudioError::Database(e) => e.into(),
+        }
+    }
+}
+
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            JsonRejection::MissingJsonContentType(_) => AppError::UnsupportedMediaType(rejection.body_text()),
+            _ => AppError::BadRequest(rejection.body_text()),
+        }
+    }
+}
+
+impl From<MultipartError> for AppError {
+    fn from(e: MultipartError) -> Self {
+        AppError::BadRequest(e.body_text())
+    }
+}
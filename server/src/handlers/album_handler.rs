@@ -0,0 +1,82 @@
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{error::AppError, model::SongSummaryRow, AppState};
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use uuid::Uuid;
+use common::schema::{
+    album::{AlbumDetails, AlbumDetailsResponse, AlbumSummary},
+    song::SongSummary
+};
+
+/// Looks an album up with its tracks.
+#[utoipa::path(
+    get,
+    path = "/api/v1/albums/{album_id}",
+    tag = "songs",
+    params(
+        ("album_id" = uuid::Uuid, Path, description = "Id of the album"),
+    ),
+    responses(
+        (status = 200, description = "The album", body = AlbumDetailsResponse),
+        (status = 404, description = "Album not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_album_handler(
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    Path(album_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+
+    let album = sqlx::query!(
+        r#"
+        SELECT albums.album_id, albums.title, albums.artist_id, artists.name AS artist, albums.cover,
+            albums.release_date, albums.genre::TEXT AS genre
+        FROM albums
+        JOIN artists ON artists.artist_id = albums.artist_id
+        WHERE albums.album_id = $1
+        "#,
+        album_id
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Album not found".to_string()))?;
+
+    // `albums.tracks` keeps the album's order, songs missing from it go last
+    let tracks = sqlx::query_as!(
+        SongSummaryRow,
+        r#"
+        SELECT songs.song_id, songs.title, songs.artist_id, artists.name AS artist, songs.album_id,
+            albums.title AS album, albums.cover, songs.duration, songs.audio_key IS NOT NULL AS "has_audio!"
+        FROM songs
+        JOIN artists ON artists.artist_id = songs.artist_id
+        JOIN albums ON albums.album_id = songs.album_id
+        WHERE songs.album_id = $1
+        ORDER BY array_position(albums.tracks, songs.song_id) NULLS LAST, songs.title
+        "#,
+        album_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(AlbumDetailsResponse {
+        status: "success".to_string(),
+        album: AlbumDetails {
+            album: AlbumSummary {
+                album_id: album.album_id,
+                title: album.title,
+                artist_id: album.artist_id,
+                artist: album.artist,
+                cover: album.cover,
+                release_date: album.release_date,
+                track_count: tracks.len() as i64,
+            },
+            genre: album.genre,
+            tracks: tracks.into_iter().map(SongSummary::from).collect(),
+        },
+    }))
+}
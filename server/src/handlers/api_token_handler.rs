@@ -0,0 +1,175 @@
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{
+    error::AppError,
+    model::{ApiTokenScope, ApiTokens, Users},
+    utils::{api_token::{generate_api_token, DISPLAY_PREFIX_LENGTH}, token::hash_token, validated_json::ValidatedJson},
+    AppState
+};
+use chrono::{TimeDelta, Utc};
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use common::schema::api_token::{
+    ApiToken, ApiTokenListResponse, ApiTokenScope as Scope, CreateApiTokenResponse, CreateApiTokenSchema
+};
+use common::schema::message::MessageResponse;
+
+/// How many API tokens a single user may have at once.
+const MAX_API_TOKENS: i64 = 20;
+
+fn filter_api_token(token: ApiTokens) -> ApiToken {
+    ApiToken {
+        id: token.token_id,
+        name: token.name,
+        prefix: token.token_prefix,
+        scopes: token.scopes.into_iter().map(|scope| match scope {
+            ApiTokenScope::Read => Scope::Read,
+            ApiTokenScope::Write => Scope::Write,
+        }).collect(),
+        expires_at: token.expires_at,
+        last_used_at: token.last_used_at,
+        created_at: token.created_at,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/tokens",
+    tag = "api-tokens",
+    responses(
+        (status = 200, body = ApiTokenListResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "API tokens can't manage tokens", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn list_api_tokens_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let tokens = sqlx::query_as!(
+        ApiTokens,
+        r#"SELECT token_id, user_id, name, token_prefix, scopes AS "scopes: Vec<ApiTokenScope>", expires_at, last_used_at, created_at
+        FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC"#,
+        user.user_id
+    )
+    .fetch_all(&state.try_read().unwrap().db)
+    .await?;
+
+    Ok(Json(ApiTokenListResponse {
+        status: "success".to_string(),
+        tokens: tokens.into_iter().map(filter_api_token).collect(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/tokens",
+    tag = "api-tokens",
+    request_body = CreateApiTokenSchema,
+    responses(
+        (status = 200, description = "The new token, only ever shown here", body = CreateApiTokenResponse),
+        (status = 400, description = "Invalid request or failed validation", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "API tokens can't manage tokens", body = ErrorResponse),
+        (status = 409, description = "Too many tokens", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn create_api_token_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<CreateApiTokenSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.try_read().unwrap().db.clone();
+
+    let token_count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM api_tokens WHERE user_id = $1"#,
+        user.user_id
+    )
+    .fetch_one(&db)
+    .await?;
+
+    if token_count >= MAX_API_TOKENS {
+        return Err(AppError::Conflict(
+            format!("You can have at most {} API tokens, revoke one to create another", MAX_API_TOKENS)
+        ));
+    }
+
+    let scopes = [(Scope::Read, ApiTokenScope::Read), (Scope::Write, ApiTokenScope::Write)]
+        .into_iter()
+        .filter(|(scope, _)| payload.scopes.contains(scope))
+        .map(|(_, scope)| scope)
+        .collect::<Vec<_>>();
+
+    let token = generate_api_token();
+    let expires_at = payload
+        .expires_in_days
+        .map(|days| {
+            TimeDelta::try_days(days)
+                .map(|expiry| Utc::now() + expiry)
+                .ok_or_else(|| AppError::BadRequest("Expiry is out of range".to_string()))
+        })
+        .transpose()?;
+
+    let api_token = sqlx::query_as!(
+        ApiTokens,
+        r#"INSERT INTO api_tokens (token_id, user_id, name, token_prefix, token_hash, scopes, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING token_id, user_id, name, token_prefix, scopes AS "scopes: Vec<ApiTokenScope>", expires_at, last_used_at, created_at"#,
+        uuid::Uuid::new_v4(),
+        user.user_id,
+        payload.name.trim(),
+        &token[..DISPLAY_PREFIX_LENGTH],
+        hash_token(&token),
+        scopes as Vec<ApiTokenScope>,
+        expires_at
+    )
+    .fetch_one(&db)
+    .await?;
+
+    Ok(Json(CreateApiTokenResponse {
+        status: "success".to_string(),
+        token,
+        api_token: filter_api_token(api_token),
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/user/tokens/{token_id}",
+    tag = "api-tokens",
+    params(
+        ("token_id" = uuid::Uuid, Path, description = "Id of the token to revoke"),
+    ),
+    responses(
+        (status = 200, body = MessageResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "API tokens can't manage tokens", body = ErrorResponse),
+        (status = 404, description = "Token not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn revoke_api_token_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    Path(token_id): Path<uuid::Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let result = sqlx::query!(
+        "DELETE FROM api_tokens WHERE token_id = $1 AND user_id = $2",
+        token_id,
+        user.user_id
+    )
+    .execute(&state.try_read().unwrap().db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("API token not found".to_string()));
+    }
+
+    Ok(Json(MessageResponse::success("API token revoked")))
+}
\ No newline at end of file
@@ -0,0 +1,92 @@
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{error::AppError, model::SongSummaryRow, AppState};
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use uuid::Uuid;
+use common::schema::{
+    album::AlbumSummary,
+    artist::{ArtistDetails, ArtistDetailsResponse, ArtistSummary},
+    song::SongSummary
+};
+
+/// How many of an artist's songs are listed with the artist.
+const TOP_SONGS_LIMIT: i64 = 10;
+
+/// Looks an artist up with their discography and most played songs.
+#[utoipa::path(
+    get,
+    path = "/api/v1/artists/{artist_id}",
+    tag = "songs",
+    params(
+        ("artist_id" = uuid::Uuid, Path, description = "Id of the artist"),
+    ),
+    responses(
+        (status = 200, description = "The artist", body = ArtistDetailsResponse),
+        (status = 404, description = "Artist not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_artist_handler(
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    Path(artist_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+
+    let artist = sqlx::query!(
+        r#"SELECT artist_id, name, COALESCE(genres::TEXT[], '{}') AS "genres!" FROM artists WHERE artist_id = $1"#,
+        artist_id
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Artist not found".to_string()))?;
+
+    let albums = sqlx::query_as!(
+        AlbumSummary,
+        r#"
+        SELECT albums.album_id, albums.title, albums.artist_id, artists.name AS artist, albums.cover,
+            albums.release_date, COUNT(songs.song_id) AS "track_count!"
+        FROM albums
+        JOIN artists ON artists.artist_id = albums.artist_id
+        LEFT JOIN songs ON songs.album_id = albums.album_id
+        WHERE albums.artist_id = $1
+        GROUP BY albums.album_id, artists.name
+        ORDER BY albums.release_date DESC NULLS LAST, albums.title
+        "#,
+        artist_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let top_songs = sqlx::query_as!(
+        SongSummaryRow,
+        r#"
+        SELECT songs.song_id, songs.title, songs.artist_id, artists.name AS artist, songs.album_id,
+            albums.title AS album, albums.cover, songs.duration, songs.audio_key IS NOT NULL AS "has_audio!"
+        FROM songs
+        JOIN artists ON artists.artist_id = songs.artist_id
+        JOIN albums ON albums.album_id = songs.album_id
+        WHERE songs.artist_id = $1
+        ORDER BY (SELECT COUNT(*) FROM listening_history WHERE listening_history.song_id = songs.song_id) DESC,
+            songs.title
+        LIMIT $2
+        "#,
+        artist_id,
+        TOP_SONGS_LIMIT
+    )
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(ArtistDetailsResponse {
+        status: "success".to_string(),
+        artist: ArtistDetails {
+            artist: ArtistSummary { artist_id: artist.artist_id, name: artist.name },
+            genres: artist.genres,
+            albums,
+            top_songs: top_songs.into_iter().map(SongSummary::from).collect(),
+        },
+    }))
+}
@@ -1,51 +1,56 @@
 use axum::{
-    http::{header, Response, StatusCode},
-    response::IntoResponse,
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
     routing::get,
     Json, 
     Router,
     Extension
 };
-use axum_extra::extract::cookie::{Cookie, SameSite, CookieJar};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{TimeDelta, Utc};
 use serde_json::json;
 use crate::{
-    model::Users, utils::{hash::*, jwt::{decode_token, generate_tokens, AccessClaims, AuthError, JwtTokens, RefreshClaims}}, AppState
+    error::AppError,
+    handlers::user_handler::{filter_user, username_taken_or_database_error},
+    mailer::Email,
+    model::{TokenPurpose, Users},
+    rate_limit::Decision,
+    utils::{cookie::{removal_cookie, session_cookie}, hash::*, jwt::{decode_token, generate_challenge_token, generate_tokens, JwtTokens, RefreshClaims}, token::{consume_token, issue_token}, validated_json::ValidatedJson},
+    AppState
 };
 use tokio::sync::RwLock;
+use tracing::error;
 use std::sync::Arc;
-use common::schema::user::{ FilteredUser, LoginUserSchema, SignupUserSchema, UserData, UserResponse };
-use common::schema::platform::Platform;
-use common::schema::feedback::ErrorResponse;
-
+use common::schema::message::MessageResponse;
+use common::schema::two_factor::TwoFactorChallengeResponse;
+use common::schema::user::{ EmailRequestSchema, LoginResponse, LoginUserSchema, ResetPasswordSchema, SignupUserSchema, UserData, UserLoginResponse, UserResponse, VerifyEmailSchema };
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = SignupUserSchema,
+    responses(
+        (status = 200, description = "Account created, a verification email has been sent", body = UserResponse),
+        (status = 400, description = "Invalid request or failed validation", body = ErrorResponse),
+        (status = 409, description = "Email or username already taken", body = ErrorResponse),
+        (status = 429, description = "Too many requests, see the `Retry-After` header", body = ErrorResponse),
+    )
+)]
 pub async fn register_user_handler(
     state: Extension<Arc<RwLock<AppState>>>,
-    Json(payload): Json<SignupUserSchema>
-    ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(payload): ValidatedJson<SignupUserSchema>
+    ) -> Result<impl IntoResponse, AppError> {
     let user_exists: Option<bool> = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)")
         .bind(&payload.email.to_owned().to_ascii_lowercase())
         .fetch_one(&state.try_read().unwrap().db)
-        .await
-        .map_err(|e| {
-            let error_response = ErrorResponse {
-                status: "fail".to_string(),
-                message: e.to_string(),
-            };
-
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-        })?;
-
-    if let Some(exists) = user_exists {
-        if exists {
-            let error_response = ErrorResponse {
-                status: "fail".to_string(),
-                message: "User with this email already exists".to_string(),
-            };
-
-            (StatusCode::CONFLICT, Json(error_response));
-        }
+        .await?;
+
+    if user_exists == Some(true) {
+        return Err(AppError::Conflict("User with this email already exists".to_string()));
     }
 
-    let hashed_password = hash(&payload.password).unwrap();
+    let hashed_password = hash(&payload.password, &state.try_read().unwrap().env.password).await?;
 
     let user = sqlx::query_as!(
         Users,
@@ -63,202 +68,436 @@ pub async fn register_user_handler(
     )
     .fetch_one(&state.try_read().unwrap().db)
     .await
-    .map_err(|e| {
-        let error_response = ErrorResponse {
-            status: "fail".to_string(),
-            message: format!("Database error: {}", e)
-        };
-
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-    })?;
+    .map_err(username_taken_or_database_error)?;
 
-    // Convert the preferred_platform from Option<String> to Option<Platform>
-    let preferred_platform = user.preferred_platform.map(|s| Platform::from(s));
+    send_verification_email(&state, &user).await;
 
     let user_response = json!(UserResponse {
         status: "success".to_string(),
-        message: "User registered successfully".to_string(),
+        message: "User registered successfully. Check your email to verify your account".to_string(),
         data: UserData {
-            user: FilteredUser {
-                user_id: user.user_id,
-                name: user.name,
-                username: user.username,
-                email: user.email,
-                preferred_platform: preferred_platform.unwrap_or(Platform::Spotify),
-                photo: user.photo.unwrap_or_else(|| "".to_string()),
-                createdAt: user.created_at.unwrap(),
-                updatedAt: user.updated_at.unwrap()
-            }
+            user: filter_user(user)
         }
     });
 
     Ok(Json(user_response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginUserSchema,
+    responses(
+        (status = 200, description = "Logged in and the tokens set as cookies, or a challenge for `/api/v1/auth/2fa/verify` if the account has two-factor authentication enabled", body = LoginResponse),
+        (status = 400, description = "Invalid email or password", body = ErrorResponse),
+        (status = 403, description = "Email not verified yet", body = ErrorResponse),
+        (status = 429, description = "Too many requests, see the `Retry-After` header", body = ErrorResponse),
+    )
+)]
 pub async fn login_user_handler(
     state: Extension<Arc<RwLock<AppState>>>,
-    Json(payload): Json<LoginUserSchema>
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    // Add logic to login a user
+    ValidatedJson(payload): ValidatedJson<LoginUserSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let email = payload.email.to_owned().to_ascii_lowercase();
+    let rate_limiter = state.try_read().unwrap().rate_limiter.clone();
+
+    // Throttle attempts per account, whichever IP they come from
+    if let Decision::Limited { retry_after } = rate_limiter.check_account(&email).await? {
+        return Err(too_many_attempts(retry_after));
+    }
+
+    if let Some(locked_until) = rate_limiter.locked_until(&email).await? {
+        return Err(too_many_attempts((locked_until - Utc::now()).num_seconds().max(1) as u64));
+    }
+
     let user = sqlx::query_as!(
         Users,
         "SELECT * FROM users WHERE email = $1",
-        &payload.email.to_owned()
+        &email
     )
     .fetch_optional(&state.try_read().unwrap().db)
-    .await
-    .map_err(|e| {
-        let error_response = ErrorResponse {
-            status: "fail".to_string(),
-            message: format!("Database error: {}", e)
-        };
+    .await?;
 
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-    })?
-    .ok_or_else(|| {
-        let error_response = ErrorResponse {
-            status: "fail".to_string(),
-            message: "Invalid email or password: could not locate user with that email".to_string(),
-        };
+    let is_valid = match &user {
+        Some(user) => verify(&payload.password, &user.password).await?,
+        None => false,
+    };
 
-        (StatusCode::BAD_REQUEST, Json(error_response))
-    })?;
+    // Unknown emails and wrong passwords get the same response so accounts can't be enumerated
+    let user = match user.filter(|_| is_valid) {
+        Some(user) => user,
+        None => {
+            if let Some(locked_until) = rate_limiter.record_failure(&email).await? {
+                return Err(too_many_attempts((locked_until - Utc::now()).num_seconds().max(1) as u64));
+            }
 
-    let is_valid = verify(&payload.password, &user.password);
+            return Err(AppError::InvalidCredentials("Invalid email or password".to_string()));
+        }
+    };
 
-    if !is_valid {
-        let error_response = ErrorResponse {
-            status: "fail".to_string(),
-            message: "Invalid password. Does not match hash".to_string(),
-        };
+    rate_limiter.reset_failures(&email).await?;
 
-        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    if !user.verified {
+        return Err(AppError::EmailNotVerified);
     }
 
-    let JwtTokens { access_token, refresh_token } = generate_tokens(user);
-
-    let is_production = !cfg!(debug_assertions);
-    let access_cookie = Cookie::build(("access_token", access_token.to_owned()))
-        .path("/")
-        .max_age(time::Duration::hours(1))
-        .same_site(SameSite::Lax)
-        .secure(is_production)
-        .http_only(true);
-
-    let refresh_cookie = Cookie::build(("refresh_token", refresh_token.to_owned()))
-        .path("/")
-        .max_age(time::Duration::hours(1))
-        .same_site(SameSite::Lax)
-        .secure(is_production)
-        .http_only(true);
-
-    let logged_in_cookie = Cookie::build(("logged_in", "true"))
-        .path("/")
-        .max_age(time::Duration::hours(1))
-        .same_site(SameSite::Lax)
-        .secure(is_production)
-        .http_only(false);
-
-    let res = json!({
-        "status": "success",
-        "access_token": access_token,
-        "refresh_token": refresh_token
-    });
+    // Upgrade legacy bcrypt hashes, or hashes made with outdated parameters, now that we know the password
+    let password_config = state.try_read().unwrap().env.password.clone();
+    if needs_rehash(&user.password, &password_config) {
+        match hash(&payload.password, &password_config).await {
+            Ok(new_hash) => {
+                if let Err(e) = sqlx::query!(
+                    "UPDATE users SET password = $1 WHERE user_id = $2",
+                    new_hash,
+                    user.user_id
+                )
+                .execute(&state.try_read().unwrap().db)
+                .await
+                {
+                    error!("Failed to rehash password for {}: {}", user.user_id, e);
+                }
+            }
+            Err(e) => error!("Failed to rehash password for {}: {}", user.user_id, e),
+        }
+    }
 
-    let mut response = Response::new(res.to_string());
-    response.headers_mut()
-        .insert(header::SET_COOKIE, access_cookie.to_string().parse().unwrap());
-    response.headers_mut()
-        .append(header::SET_COOKIE, refresh_cookie.to_string().parse().unwrap());
-    response.headers_mut()
-        .append(header::SET_COOKIE, logged_in_cookie.to_string().parse().unwrap());
+    // With 2FA enabled the session is only issued once the code is verified
+    if user.totp_enabled {
+        let challenge = TwoFactorChallengeResponse {
+            status: "2fa_required".to_string(),
+            challenge_token: generate_challenge_token(&state.read().await.keys, &user),
+        };
+
+        return Ok(Json(LoginResponse::TwoFactorRequired(challenge)).into_response());
+    }
 
-    Ok(response)
+    Ok(session_response(&*state.read().await, user))
 }
 
-pub async fn logout_handler () -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let cookie = Cookie::build(("access_token", ""))
-        .path("/")
-        .max_age(time::Duration::hours(-1))
-        .same_site(SameSite::Lax)
-        .http_only(true);
+/// Issues a new access/refresh token pair for the user and sets them as cookies.
+pub fn session_response(state: &AppState, user: Users) -> Response {
+    let JwtTokens { access_token, refresh_token } = generate_tokens(&state.keys, &state.env.jwt, user);
+
+    let cookies = &state.env.cookies;
+    let access_maxage = time::Duration::seconds(state.env.jwt.access_token_maxage);
+    let refresh_maxage = time::Duration::seconds(state.env.jwt.refresh_token_maxage);
+    let access_cookie = session_cookie(cookies, "access_token", access_token.to_owned(), access_maxage);
+    let refresh_cookie = session_cookie(cookies, "refresh_token", refresh_token.to_owned(), refresh_maxage);
+    // The client stays logged in for as long as it can refresh
+    let logged_in_cookie = session_cookie(cookies, "logged_in", "true".to_string(), refresh_maxage);
+
+    let mut headers = HeaderMap::new();
+    for cookie in [access_cookie, refresh_cookie, logged_in_cookie] {
+        headers.append(header::SET_COOKIE, cookie.to_string().parse().unwrap());
+    }
+
+    let body = UserLoginResponse {
+        status: "success".to_string(),
+        access_token,
+        refresh_token,
+    };
 
-    let mut response = Response::new(json!({ "status": "success" }).to_string());
-    response.headers_mut()
-        .insert(header::SET_COOKIE, cookie.to_string().parse().unwrap());
+    (headers, Json(body)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Logged out, the session cookies are cleared"),
+    )
+)]
+pub async fn logout_handler(
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let cookies = state.read().await.env.cookies.clone();
+
+    let mut headers = HeaderMap::new();
+    for name in ["access_token", "refresh_token", "logged_in"] {
+        headers.append(header::SET_COOKIE, removal_cookie(&cookies, name).to_string().parse().unwrap());
+    }
 
-    Ok(response)
+    Ok((headers, Json(MessageResponse::success("Logged out"))))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    responses(
+        (status = 200, description = "New token pair, also set as cookies", body = UserLoginResponse),
+        (status = 401, description = "Missing or invalid refresh token cookie", body = ErrorResponse),
+    )
+)]
 pub async fn refresh_token_handler(
     state: Extension<Arc<RwLock<AppState>>>,
     cookies: CookieJar,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<impl IntoResponse, AppError> {
     // Extract the refresh token from the cookies
     let refresh_token = match cookies.get("refresh_token") {
         Some(cookie) => cookie.value().to_string(),
-        None => {
-            let error_response = ErrorResponse {
-                status: "fail".to_string(),
-                message: "No refresh token found".to_string(),
-            };
-
-            return Err((StatusCode::UNAUTHORIZED, Json(error_response)));
-        }
+        None => return Err(AppError::Unauthenticated("No refresh token found".to_string())),
     };
 
     // Verify the refresh token and get the user ID
-    let user_id = decode_token::<RefreshClaims>(&refresh_token)
-        .map_err(|e| {
-            let error_response = ErrorResponse {
-                status: "fail".to_string(),
-                message: match e {
-                    AuthError::InvalidToken => "Invalid refresh token".to_string(),
-                    _ => "Unknown error".to_string(),
-                },
-            };
-
-            (StatusCode::UNAUTHORIZED, Json(error_response))
-        })?
+    let user_id = decode_token::<RefreshClaims>(&state.read().await.keys, &refresh_token)
+        .map_err(|_| AppError::Unauthenticated("Invalid refresh token".to_string()))?
         .sub;
 
-    // Generate a new pair of access and refresh tokens
     let user = sqlx::query_as!(
         Users,
         "SELECT * FROM users WHERE user_id = $1",
         uuid::Uuid::parse_str(&user_id).unwrap()
     )
-    .fetch_one(&state.try_read().unwrap().db)
-    .await
-    .map_err(|e| {
-        let error_response = ErrorResponse {
-            status: "fail".to_string(),
-            message: format!("Database error: {}", e)
-        };
+    .fetch_optional(&state.read().await.db)
+    .await?
+    .ok_or_else(|| AppError::Unauthenticated("The user belonging to this token no longer exists".to_string()))?;
+
+    // Rotate both tokens, setting the new access token cookie so the client can retry the failed request
+    Ok(session_response(&*state.read().await, user))
+}
+
+/// Lifetime of an email verification link.
+const VERIFICATION_TOKEN_TTL_HOURS: i64 = 24;
+/// Lifetime of a password reset link.
+const RESET_TOKEN_TTL_HOURS: i64 = 1;
+
+pub(crate) fn too_many_attempts(retry_after: u64) -> AppError {
+    AppError::TooManyRequests {
+        message: format!("Too many login attempts, please try again in {} seconds", retry_after),
+        retry_after,
+    }
+}
+
+/// Issues a fresh verification token for the user and emails them the link.
+/// Failures are logged rather than returned, the user can always request another link.
+async fn send_verification_email(state: &Arc<RwLock<AppState>>, user: &Users) {
+    let (db, mailer, client_url) = {
+        let state = state.read().await;
+        (state.db.clone(), state.mailer.clone(), state.env.client_url.clone())
+    };
+
+    let ttl = TimeDelta::try_hours(VERIFICATION_TOKEN_TTL_HOURS).expect("Token lifetime out of range");
+    let token = match issue_token(&db, user.user_id, TokenPurpose::EmailVerification, ttl).await {
+        Ok(token) => token,
+        Err(e) => {
+            error!("Failed to create verification token for {}: {}", user.user_id, e);
+            return;
+        }
+    };
+
+    let link = format!("{}/verify-email?token={}", client_url, token);
+    if let Err(e) = mailer.send(Email::verification(&user.email, &user.name, &link)).await {
+        error!("Failed to send verification email to {}: {}", user.user_id, e);
+    }
+}
 
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email/resend",
+    tag = "auth",
+    request_body = EmailRequestSchema,
+    responses(
+        (status = 200, description = "A link has been sent if an unverified account exists", body = MessageResponse),
+        (status = 400, description = "Invalid request or failed validation", body = ErrorResponse),
+        (status = 429, description = "Too many requests, see the `Retry-After` header", body = ErrorResponse),
+    )
+)]
+pub async fn request_verification_handler(
+    state: Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<EmailRequestSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let user = sqlx::query_as!(
+        Users,
+        "SELECT * FROM users WHERE email = $1",
+        &payload.email.to_owned().to_ascii_lowercase()
+    )
+    .fetch_optional(&state.try_read().unwrap().db)
+    .await?;
+
+    // Respond the same way whether or not the account exists so emails can't be enumerated
+    if let Some(user) = user.filter(|user| !user.verified) {
+        send_verification_email(&state, &user).await;
+    }
+
+    Ok(Json(MessageResponse::success("If an unverified account exists for that email, a verification link has been sent")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email",
+    tag = "auth",
+    request_body = VerifyEmailSchema,
+    responses(
+        (status = 200, description = "Email verified", body = MessageResponse),
+        (status = 400, description = "Invalid or expired link", body = ErrorResponse),
+        (status = 429, description = "Too many requests, see the `Retry-After` header", body = ErrorResponse),
+    )
+)]
+pub async fn verify_email_handler(
+    state: Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<VerifyEmailSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.try_read().unwrap().db.clone();
+
+    let user_id = consume_token(&db, &payload.token, TokenPurpose::EmailVerification)
+        .await?
+        .ok_or_else(|| AppError::InvalidLink("Invalid or expired verification link".to_string()))?;
+
+    sqlx::query!(
+        "UPDATE users SET verified = TRUE, updated_at = NOW() WHERE user_id = $1",
+        user_id
+    )
+    .execute(&db)
+    .await?;
+
+    Ok(Json(MessageResponse::success("Email verified successfully")))
+}
+
+/// Second step of an email change, started by `user_handler::change_email_handler`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/confirm-email-change",
+    tag = "auth",
+    request_body = VerifyEmailSchema,
+    responses(
+        (status = 200, description = "Email changed", body = MessageResponse),
+        (status = 400, description = "Invalid or expired link", body = ErrorResponse),
+        (status = 409, description = "Email already taken", body = ErrorResponse),
+        (status = 429, description = "Too many requests, see the `Retry-After` header", body = ErrorResponse),
+    )
+)]
+pub async fn confirm_email_change_handler(
+    state: Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<VerifyEmailSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.try_read().unwrap().db.clone();
+
+    let user_id = consume_token(&db, &payload.token, TokenPurpose::EmailChange)
+        .await?
+        .ok_or_else(|| AppError::InvalidLink("Invalid or expired confirmation link".to_string()))?;
+
+    // Following the link proves ownership of the new address, so it counts as verified
+    let result = sqlx::query!(
+        r#"
+        UPDATE users SET email = pending_email, pending_email = NULL, verified = TRUE, updated_at = NOW()
+        WHERE user_id = $1 AND pending_email IS NOT NULL
+        "#,
+        user_id
+    )
+    .execute(&db)
+    .await
+    .map_err(|e| match e {
+        // Someone else registered the address while the link was waiting to be clicked
+        sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+            AppError::Conflict("User with this email already exists".to_string())
+        }
+        e => AppError::Database(e),
     })?;
 
-    let JwtTokens { access_token, refresh_token } = generate_tokens(user);
-
-    // Set the new refresh token in the cookies
-    let refresh_token_cookie = Cookie::build(("refresh_token", refresh_token.to_owned()))
-        .path("/")
-        .max_age(time::Duration::hours(1))
-        .same_site(SameSite::Lax)
-        .secure(!cfg!(debug_assertions))
-        .http_only(true);
-
-    // Return the new access and refresh tokens
-    let res = json!({
-        "status": "success",
-        "access_token": access_token,
-        "refresh_token": refresh_token
-    });
+    if result.rows_affected() == 0 {
+        return Err(AppError::InvalidLink("Invalid or expired confirmation link".to_string()));
+    }
+
+    Ok(Json(MessageResponse::success("Email changed successfully")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/forgot-password",
+    tag = "auth",
+    request_body = EmailRequestSchema,
+    responses(
+        (status = 200, description = "A link has been sent if the account exists", body = MessageResponse),
+        (status = 400, description = "Invalid request or failed validation", body = ErrorResponse),
+        (status = 429, description = "Too many requests, see the `Retry-After` header", body = ErrorResponse),
+    )
+)]
+pub async fn forgot_password_handler(
+    state: Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<EmailRequestSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let (db, mailer, client_url) = {
+        let state = state.read().await;
+        (state.db.clone(), state.mailer.clone(), state.env.client_url.clone())
+    };
 
-    let mut response = Response::new(res.to_string());
-    response.headers_mut()
-        .insert(header::SET_COOKIE, refresh_token_cookie.to_string().parse().unwrap());
+    let user = sqlx::query_as!(
+        Users,
+        "SELECT * FROM users WHERE email = $1",
+        &payload.email.to_owned().to_ascii_lowercase()
+    )
+    .fetch_optional(&db)
+    .await?;
+
+    // Respond the same way whether or not the account exists so emails can't be enumerated
+    if let Some(user) = user {
+        let ttl = TimeDelta::try_hours(RESET_TOKEN_TTL_HOURS).expect("Token lifetime out of range");
+        let token = issue_token(&db, user.user_id, TokenPurpose::PasswordReset, ttl).await?;
+
+        let link = format!("{}/reset-password?token={}", client_url, token);
+        if let Err(e) = mailer.send(Email::password_reset(&user.email, &user.name, &link)).await {
+            error!("Failed to send password reset email to {}: {}", user.user_id, e);
+        }
+    }
 
-    Ok(Json(res))
-}
\ No newline at end of file
+    Ok(Json(MessageResponse::success("If an account exists for that email, a password reset link has been sent")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/reset-password",
+    tag = "auth",
+    request_body = ResetPasswordSchema,
+    responses(
+        (status = 200, description = "Password reset", body = MessageResponse),
+        (status = 400, description = "Invalid or expired link, or failed validation", body = ErrorResponse),
+        (status = 429, description = "Too many requests, see the `Retry-After` header", body = ErrorResponse),
+    )
+)]
+pub async fn reset_password_handler(
+    state: Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<ResetPasswordSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let (db, password_config) = {
+        let state = state.read().await;
+        (state.db.clone(), state.env.password.clone())
+    };
+
+    let user_id = consume_token(&db, &payload.token, TokenPurpose::PasswordReset)
+        .await?
+        .ok_or_else(|| AppError::InvalidLink("Invalid or expired password reset link".to_string()))?;
+
+    let hashed_password = hash(&payload.password, &password_config).await?;
+
+    // Following a reset link proves ownership of the email, so the account is verified as well
+    sqlx::query!(
+        "UPDATE users SET password = $1, verified = TRUE, updated_at = NOW() WHERE user_id = $2",
+        hashed_password,
+        user_id
+    )
+    .execute(&db)
+    .await?;
+
+    Ok(Json(MessageResponse::success("Password reset successfully")))
+}
+
+/// Publishes the public signing keys so other services can verify our tokens.
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "well-known",
+    responses(
+        (status = 200, description = "The public keys tokens are signed with, as a JSON Web Key Set", body = Object),
+    )
+)]
+pub async fn jwks_handler(
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> impl IntoResponse {
+    let jwks = state.read().await.keys.jwks();
+
+    // Keep caches short so a newly added key is picked up before it becomes active
+    ([(header::CACHE_CONTROL, "public, max-age=300")], Json(jwks))
+}
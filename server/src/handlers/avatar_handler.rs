@@ -0,0 +1,117 @@
+use axum::{
+    body::Bytes,
+    extract::Multipart,
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{
+    error::AppError,
+    handlers::user_handler::filter_user,
+    model::Users,
+    utils::avatar::{avatar_key, resize_avatar, AvatarError, AVATAR_SIZES},
+    AppState
+};
+use chrono::Utc;
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use common::schema::user::{UserData, UserResponse};
+
+/// Name of the multipart field the image is uploaded in.
+const AVATAR_FIELD: &str = "avatar";
+
+/// The multipart form `upload_avatar_handler` accepts, only used to document it.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct AvatarUpload {
+    /// A PNG, JPEG, WebP or GIF image
+    #[schema(value_type = String, format = Binary)]
+    avatar: Vec<u8>,
+}
+
+/// Accepts an image in the `avatar` field of a multipart form, stores it resized to every size in
+/// `AVATAR_SIZES` and points `users.photo` at the largest one.
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/avatar",
+    tag = "user",
+    request_body(content = AvatarUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The user with their new avatar", body = UserResponse),
+        (status = 400, description = "No `avatar` field", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 413, description = "Image too large", body = ErrorResponse),
+        (status = 415, description = "Not a PNG, JPEG, WebP or GIF image", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn upload_avatar_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    mut multipart: Multipart
+) -> Result<impl IntoResponse, AppError> {
+    let (db, storage, max_bytes) = {
+        let state = state.read().await;
+        (state.db.clone(), state.storage.clone(), state.env.storage.max_avatar_bytes)
+    };
+
+    let mut upload = None;
+    while let Some(mut field) = multipart.next_field().await? {
+        if field.name() != Some(AVATAR_FIELD) {
+            continue;
+        }
+
+        // Read in chunks so an oversized upload is rejected without buffering all of it
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.chunk().await? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(AppError::PayloadTooLarge(format!("Avatars must be at most {} KB", max_bytes / 1024)));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        upload = Some(bytes);
+        break;
+    }
+
+    let bytes = upload.ok_or_else(|| AppError::BadRequest("Missing avatar file".to_string()))?;
+
+    let resized = tokio::task::spawn_blocking(move || resize_avatar(&bytes))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map_err(|e| match e {
+            AvatarError::UnsupportedFormat => AppError::UnsupportedMediaType(
+                "Avatars must be PNG, JPEG, WebP or GIF images".to_string()
+            ),
+            AvatarError::Invalid(e) => AppError::BadRequest(format!("Invalid image: {}", e)),
+        })?;
+
+    for (size, encoded) in resized {
+        storage.put(&avatar_key(user.user_id, size), Bytes::from(encoded)).await?;
+    }
+
+    // Keys are reused between uploads, so the version busts any cached copy of the old avatar
+    let largest = AVATAR_SIZES[AVATAR_SIZES.len() - 1];
+    let url = storage
+        .url(&avatar_key(user.user_id, largest))
+        .ok_or_else(|| AppError::Internal("Avatars have no public URL".to_string()))?;
+    let photo = format!("{}?v={}", url, Utc::now().timestamp());
+
+    let user = sqlx::query_as!(
+        Users,
+        "UPDATE users SET photo = $1, updated_at = NOW() WHERE user_id = $2 RETURNING *",
+        photo,
+        user.user_id
+    )
+    .fetch_one(&db)
+    .await?;
+
+    Ok(Json(UserResponse {
+        status: "success".to_string(),
+        message: "Avatar updated".to_string(),
+        data: UserData {
+            user: filter_user(user)
+        }
+    }))
+}
@@ -0,0 +1,287 @@
+use axum::{
+    http::header,
+    response::IntoResponse,
+    Extension
+};
+use crate::{
+    error::AppError,
+    handlers::user_handler::filter_user,
+    model::Users,
+    utils::export::zip_files,
+    AppState
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::RwLock;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+struct ExportedPreference {
+    preference_id: uuid::Uuid,
+    song_id: uuid::Uuid,
+    song_title: Option<String>,
+    artist_id: uuid::Uuid,
+    artist_name: Option<String>,
+    album_id: uuid::Uuid,
+    album_title: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ExportedPlay {
+    song_id: uuid::Uuid,
+    song_title: String,
+    played_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct ExportedLike {
+    song_id: uuid::Uuid,
+    song_title: String,
+    liked_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct ExportedHistory {
+    plays: Vec<ExportedPlay>,
+    likes: Vec<ExportedLike>,
+}
+
+#[derive(Serialize)]
+struct ExportedPlaylistSong {
+    song_id: uuid::Uuid,
+    song_title: String,
+    position: i32,
+    added_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct ExportedPlaylist {
+    playlist_id: uuid::Uuid,
+    name: String,
+    description: Option<String>,
+    is_public: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    songs: Vec<ExportedPlaylistSong>,
+}
+
+#[derive(Serialize)]
+struct ExportedRecommendation {
+    recommendation_id: uuid::Uuid,
+    song_id: uuid::Uuid,
+    song_title: Option<String>,
+    match_score: f32,
+}
+
+#[derive(Serialize)]
+struct ExportedRecommendationFeedback {
+    song_id: uuid::Uuid,
+    song_title: String,
+    feedback: String,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct ExportedFollow {
+    username: String,
+    followed_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct ExportedFollows {
+    following: Vec<ExportedFollow>,
+    followers: Vec<ExportedFollow>,
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, AppError> {
+    serde_json::to_vec_pretty(value)
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Downloads everything stored about the logged in user as a ZIP of JSON files,
+/// one per kind of data: profile, preferences, history, playlists, recommendations,
+/// feedback on recommendations and follows.
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/export",
+    tag = "user",
+    responses(
+        (status = 200, description = "ZIP of JSON files", content_type = "application/zip"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "API tokens can't export data", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn export_user_data_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+
+    let preferences = sqlx::query_as!(
+        ExportedPreference,
+        r#"
+        SELECT user_preferences.preference_id, user_preferences.song_id, songs.title AS "song_title?",
+            user_preferences.artist_id, artists.name AS "artist_name?",
+            user_preferences.album_id, albums.title AS "album_title?"
+        FROM user_preferences
+        LEFT JOIN songs ON songs.song_id = user_preferences.song_id
+        LEFT JOIN artists ON artists.artist_id = user_preferences.artist_id
+        LEFT JOIN albums ON albums.album_id = user_preferences.album_id
+        WHERE user_preferences.user_id = $1
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let plays = sqlx::query_as!(
+        ExportedPlay,
+        r#"
+        SELECT listening_history.song_id, songs.title AS song_title, listening_history.played_at
+        FROM listening_history
+        JOIN songs ON songs.song_id = listening_history.song_id
+        WHERE listening_history.user_id = $1
+        ORDER BY listening_history.played_at
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let likes = sqlx::query_as!(
+        ExportedLike,
+        r#"
+        SELECT song_likes.song_id, songs.title AS song_title, song_likes.created_at AS liked_at
+        FROM song_likes
+        JOIN songs ON songs.song_id = song_likes.song_id
+        WHERE song_likes.user_id = $1
+        ORDER BY song_likes.created_at
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let playlist_rows = sqlx::query!(
+        r#"
+        SELECT playlist_id, name, description, is_public, created_at, updated_at
+        FROM playlists
+        WHERE user_id = $1
+        ORDER BY created_at
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let mut playlists = Vec::with_capacity(playlist_rows.len());
+    for row in playlist_rows {
+        let songs = sqlx::query_as!(
+            ExportedPlaylistSong,
+            r#"
+            SELECT playlist_songs.song_id, songs.title AS song_title, playlist_songs.position, playlist_songs.added_at
+            FROM playlist_songs
+            JOIN songs ON songs.song_id = playlist_songs.song_id
+            WHERE playlist_songs.playlist_id = $1
+            ORDER BY playlist_songs.position
+            "#,
+            row.playlist_id
+        )
+        .fetch_all(&db)
+        .await?;
+
+        playlists.push(ExportedPlaylist {
+            playlist_id: row.playlist_id,
+            name: row.name,
+            description: row.description,
+            is_public: row.is_public,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            songs,
+        });
+    }
+
+    let recommendations = sqlx::query_as!(
+        ExportedRecommendation,
+        r#"
+        SELECT recommendations.recommendation_id, recommendations.song_id, songs.title AS "song_title?",
+            recommendations.match_score
+        FROM recommendations
+        LEFT JOIN songs ON songs.song_id = recommendations.song_id
+        WHERE recommendations.user_id = $1
+        ORDER BY recommendations.match_score DESC
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let recommendation_feedback = sqlx::query_as!(
+        ExportedRecommendationFeedback,
+        r#"
+        SELECT recommendation_feedback.song_id, songs.title AS song_title,
+            recommendation_feedback.feedback, recommendation_feedback.created_at
+        FROM recommendation_feedback
+        JOIN songs ON songs.song_id = recommendation_feedback.song_id
+        WHERE recommendation_feedback.user_id = $1
+        ORDER BY recommendation_feedback.created_at
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let following = sqlx::query_as!(
+        ExportedFollow,
+        r#"
+        SELECT users.username, follows.created_at AS followed_at
+        FROM follows
+        JOIN users ON users.user_id = follows.followee_id
+        WHERE follows.follower_id = $1
+        ORDER BY follows.created_at
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let followers = sqlx::query_as!(
+        ExportedFollow,
+        r#"
+        SELECT users.username, follows.created_at AS followed_at
+        FROM follows
+        JOIN users ON users.user_id = follows.follower_id
+        WHERE follows.followee_id = $1
+        ORDER BY follows.created_at
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let files = vec![
+        ("profile.json", to_json(&filter_user(user.clone()))?),
+        ("preferences.json", to_json(&preferences)?),
+        ("history.json", to_json(&ExportedHistory { plays, likes })?),
+        ("playlists.json", to_json(&playlists)?),
+        ("recommendations.json", to_json(&recommendations)?),
+        ("recommendation_feedback.json", to_json(&recommendation_feedback)?),
+        ("follows.json", to_json(&ExportedFollows { following, followers })?),
+    ];
+
+    let archive = tokio::task::spawn_blocking(move || zip_files(&files))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map_err(|e| AppError::Internal(format!("Failed to create the export: {}", e)))?;
+
+    let filename = format!("rusty-melody-{}-{}.zip", user.username, Utc::now().format("%Y-%m-%d"));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        archive,
+    ))
+}
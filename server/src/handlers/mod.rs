@@ -1,2 +1,13 @@
 pub mod auth_handler;
-pub mod user_handler;
\ No newline at end of file
+pub mod user_handler;
+pub mod two_factor_handler;
+pub mod api_token_handler;
+pub mod avatar_handler;
+pub mod profile_handler;
+pub mod social_handler;
+pub mod export_handler;
+pub mod song_handler;
+pub mod artist_handler;
+pub mod album_handler;
+pub mod recommendation_handler;
+pub mod search_handler;
\ No newline at end of file
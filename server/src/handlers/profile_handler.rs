@@ -0,0 +1,141 @@
+use axum::{
+    extract::Path,
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{error::AppError, model::Users, utils::privacy::can_view, AppState};
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use common::schema::profile::{PublicPlaylist, PublicProfile, PublicProfileResponse, RecentPlay, TopArtist};
+use common::schema::social::Visibility;
+
+/// How many artists are listed on a public profile.
+const TOP_ARTISTS_LIMIT: i64 = 10;
+/// How many recent plays are listed on a public profile.
+const RECENT_PLAYS_LIMIT: i64 = 10;
+
+/// Looks a user up by username, ignoring case. Doesn't need a login, but what is shown
+/// depends on the user's privacy settings and whether the viewer follows them.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{username}",
+    tag = "profiles",
+    params(
+        ("username" = String, Path, description = "Username, case insensitive"),
+    ),
+    responses(
+        (status = 200, description = "The profile, `restricted` if the viewer isn't allowed to see more than the basics", body = PublicProfileResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    ),
+    security((), ("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn get_public_profile_handler(
+    Path(username): Path<String>,
+    viewer: Option<Extension<Users>>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let viewer = viewer.map(|Extension(viewer)| viewer);
+
+    let user = sqlx::query!(
+        r#"
+        SELECT user_id, username, name, photo, show_top_artists, profile_visibility, history_visibility, created_at,
+            (SELECT COUNT(*) FROM follows WHERE followee_id = users.user_id) AS "followers!",
+            (SELECT COUNT(*) FROM follows WHERE follower_id = users.user_id) AS "following!"
+        FROM users WHERE LOWER(username) = LOWER($1)
+        "#,
+        username
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let profile_visible = can_view(&db, Visibility::from(user.profile_visibility), user.user_id, viewer.as_ref())
+        .await?;
+
+    let mut profile = PublicProfile {
+        username: user.username,
+        name: user.name,
+        photo: user.photo.unwrap_or_default(),
+        top_artists: None,
+        playlists: Vec::new(),
+        recent_plays: None,
+        restricted: !profile_visible,
+        follower_count: user.followers,
+        following_count: user.following,
+        member_since: user.created_at.unwrap_or_default(),
+    };
+
+    if !profile_visible {
+        return Ok(Json(PublicProfileResponse {
+            status: "success".to_string(),
+            profile,
+        }));
+    }
+
+    profile.top_artists = if user.show_top_artists {
+        let artists = sqlx::query_as!(
+            TopArtist,
+            r#"
+            SELECT artists.artist_id, artists.name FROM user_preferences
+            JOIN artists ON artists.artist_id = user_preferences.artist_id
+            WHERE user_preferences.user_id = $1
+            GROUP BY artists.artist_id, artists.name
+            ORDER BY COUNT(*) DESC, artists.name
+            LIMIT $2
+            "#,
+            user.user_id,
+            TOP_ARTISTS_LIMIT
+        )
+        .fetch_all(&db)
+        .await?;
+
+        Some(artists)
+    } else {
+        None
+    };
+
+    profile.playlists = sqlx::query_as!(
+        PublicPlaylist,
+        r#"
+        SELECT playlists.playlist_id, playlists.name, playlists.description, playlists.created_at,
+            COUNT(playlist_songs.song_id) AS "song_count!"
+        FROM playlists
+        LEFT JOIN playlist_songs ON playlist_songs.playlist_id = playlists.playlist_id
+        WHERE playlists.user_id = $1 AND playlists.is_public
+        GROUP BY playlists.playlist_id
+        ORDER BY playlists.created_at DESC
+        "#,
+        user.user_id
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let history_visible = can_view(&db, Visibility::from(user.history_visibility), user.user_id, viewer.as_ref())
+        .await?;
+
+    if history_visible {
+        let plays = sqlx::query_as!(
+            RecentPlay,
+            r#"
+            SELECT songs.song_id, songs.title, listening_history.played_at FROM listening_history
+            JOIN songs ON songs.song_id = listening_history.song_id
+            WHERE listening_history.user_id = $1
+            ORDER BY listening_history.played_at DESC
+            LIMIT $2
+            "#,
+            user.user_id,
+            RECENT_PLAYS_LIMIT
+        )
+        .fetch_all(&db)
+        .await?;
+
+        profile.recent_plays = Some(plays);
+    }
+
+    Ok(Json(PublicProfileResponse {
+        status: "success".to_string(),
+        profile,
+    }))
+}
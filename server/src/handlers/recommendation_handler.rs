@@ -0,0 +1,143 @@
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{
+    error::AppError,
+    handlers::social_handler::ensure_song_exists,
+    model::{ApiTokenScope, Users},
+    recommend::{recommend, store},
+    utils::{api_token::ApiTokenAuth, validated_json::ValidatedJson},
+    AppState
+};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use common::schema::{
+    message::MessageResponse,
+    recommendation::{RecommendationFeedbackSchema, RecommendationListResponse}
+};
+
+const DEFAULT_RECOMMENDATIONS_LIMIT: i64 = 20;
+const MAX_RECOMMENDATIONS_LIMIT: i64 = 50;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RecommendationQuery {
+    /// How many songs to recommend, 20 by default and at most 50
+    pub limit: Option<i64>,
+}
+
+/// A fresh batch of songs recommended to the logged in user, best match first.
+///
+/// Every request recommends again from the user's likes, plays and feedback so far, so the batch
+/// after giving feedback reflects it. The batch replaces the stored one, except for requests made
+/// with an API token without the `WRITE` scope.
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/recommendations",
+    tag = "recommendations",
+    params(RecommendationQuery),
+    responses(
+        (status = 200, body = RecommendationListResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn get_recommendations_handler(
+    Query(query): Query<RecommendationQuery>,
+    Extension(user): Extension<Users>,
+    api_token: Option<Extension<ApiTokenAuth>>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let limit = query.limit.unwrap_or(DEFAULT_RECOMMENDATIONS_LIMIT).clamp(1, MAX_RECOMMENDATIONS_LIMIT);
+
+    let recommendations = recommend(&db, user.user_id, limit).await?;
+    let can_write = match api_token {
+        Some(Extension(api_token)) => api_token.scopes.contains(&ApiTokenScope::Write),
+        None => true,
+    };
+    if can_write {
+        store(&db, user.user_id, &recommendations).await?;
+    }
+
+    Ok(Json(RecommendationListResponse {
+        status: "success".to_string(),
+        recommendations,
+    }))
+}
+
+/// Records what the logged in user thinks of a recommended song, replacing what they said before.
+#[utoipa::path(
+    put,
+    path = "/api/v1/user/recommendations/{song_id}/feedback",
+    tag = "recommendations",
+    params(
+        ("song_id" = uuid::Uuid, Path, description = "Id of the song"),
+    ),
+    request_body = RecommendationFeedbackSchema,
+    responses(
+        (status = 200, body = MessageResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "Song not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn put_recommendation_feedback_handler(
+    Path(song_id): Path<uuid::Uuid>,
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<RecommendationFeedbackSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    ensure_song_exists(&db, song_id).await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO recommendation_feedback (user_id, song_id, feedback) VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, song_id) DO UPDATE SET feedback = EXCLUDED.feedback, created_at = NOW()
+        "#,
+        user.user_id,
+        song_id,
+        payload.feedback.as_str()
+    )
+    .execute(&db)
+    .await?;
+
+    Ok(Json(MessageResponse::success("Feedback saved")))
+}
+
+/// Takes back the logged in user's feedback on a song, so it can be recommended again.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/user/recommendations/{song_id}/feedback",
+    tag = "recommendations",
+    params(
+        ("song_id" = uuid::Uuid, Path, description = "Id of the song"),
+    ),
+    responses(
+        (status = 200, description = "No feedback, also if there was none before", body = MessageResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn delete_recommendation_feedback_handler(
+    Path(song_id): Path<uuid::Uuid>,
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    sqlx::query!(
+        "DELETE FROM recommendation_feedback WHERE user_id = $1 AND song_id = $2",
+        user.user_id,
+        song_id
+    )
+    .execute(&state.read().await.db)
+    .await?;
+
+    Ok(Json(MessageResponse::success("Feedback removed")))
+}
@@ -0,0 +1,208 @@
+use axum::{
+    extract::Query,
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{error::AppError, model::SongSummaryRow, AppState};
+use serde::Deserialize;
+use sqlx::{Pool, Postgres};
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use common::schema::{
+    album::AlbumSummary,
+    artist::ArtistSummary,
+    search::{PlaylistSummary, SearchResponse, SearchResults},
+    social::UserSummary,
+    song::SongSummary
+};
+
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 50;
+const DEFAULT_SUGGESTIONS_LIMIT: i64 = 5;
+const MAX_SUGGESTIONS_LIMIT: i64 = 10;
+/// Longer queries are cut short rather than refused, nothing that long names a song anyway.
+const MAX_QUERY_CHARS: usize = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// What to look for in song titles, artist names, album titles and playlist names, ignoring case
+    #[serde(default)]
+    pub q: String,
+    /// How many matches to return of each kind
+    pub limit: Option<i64>,
+}
+
+/// Escapes `%`, `_` and `\` so they match themselves in a `LIKE` pattern.
+fn escape_like(query: &str) -> String {
+    query.replace('\\', r"\\").replace('%', r"\%").replace('_', r"\_")
+}
+
+/// Songs, artists, albums and public playlists whose name contains `query`, up to `limit` of each.
+///
+/// Exact matches come first, then names starting with the query, then shorter names. Songs also
+/// match by their artist's name, after those matching by title. Only playlists of users whose
+/// profile is public are searched.
+async fn search(db: &Pool<Postgres>, query: &str, limit: i64) -> Result<SearchResults, AppError> {
+    if query.is_empty() {
+        return Ok(SearchResults::default());
+    }
+
+    let exact = escape_like(query);
+    let prefix = format!("{}%", exact);
+    let contains = format!("%{}%", exact);
+
+    let songs = sqlx::query_as!(
+        SongSummaryRow,
+        r#"
+        SELECT songs.song_id, songs.title, songs.artist_id, artists.name AS artist, songs.album_id,
+            albums.title AS album, albums.cover, songs.duration, songs.audio_key IS NOT NULL AS "has_audio!"
+        FROM songs
+        JOIN artists ON artists.artist_id = songs.artist_id
+        JOIN albums ON albums.album_id = songs.album_id
+        WHERE songs.title ILIKE $3 OR artists.name ILIKE $3
+        ORDER BY songs.title ILIKE $1 DESC, songs.title ILIKE $2 DESC, songs.title ILIKE $3 DESC,
+            LENGTH(songs.title), songs.title
+        LIMIT $4
+        "#,
+        exact,
+        prefix,
+        contains,
+        limit
+    )
+    .fetch_all(db);
+
+    let artists = sqlx::query_as!(
+        ArtistSummary,
+        r#"
+        SELECT artist_id, name FROM artists
+        WHERE name ILIKE $3
+        ORDER BY name ILIKE $1 DESC, name ILIKE $2 DESC, LENGTH(name), name
+        LIMIT $4
+        "#,
+        exact,
+        prefix,
+        contains,
+        limit
+    )
+    .fetch_all(db);
+
+    let albums = sqlx::query_as!(
+        AlbumSummary,
+        r#"
+        SELECT albums.album_id, albums.title, albums.artist_id, artists.name AS artist, albums.cover,
+            albums.release_date, COUNT(songs.song_id) AS "track_count!"
+        FROM albums
+        JOIN artists ON artists.artist_id = albums.artist_id
+        LEFT JOIN songs ON songs.album_id = albums.album_id
+        WHERE albums.title ILIKE $3
+        GROUP BY albums.album_id, artists.name
+        ORDER BY albums.title ILIKE $1 DESC, albums.title ILIKE $2 DESC, LENGTH(albums.title), albums.title
+        LIMIT $4
+        "#,
+        exact,
+        prefix,
+        contains,
+        limit
+    )
+    .fetch_all(db);
+
+    let playlists = sqlx::query!(
+        r#"
+        SELECT playlists.playlist_id, playlists.name, users.username, users.name AS owner_name, users.photo,
+            COUNT(playlist_songs.song_id) AS "song_count!"
+        FROM playlists
+        JOIN users ON users.user_id = playlists.user_id
+        LEFT JOIN playlist_songs ON playlist_songs.playlist_id = playlists.playlist_id
+        WHERE playlists.is_public AND users.profile_visibility = 'PUBLIC' AND playlists.name ILIKE $3
+        GROUP BY playlists.playlist_id, users.user_id
+        ORDER BY playlists.name ILIKE $1 DESC, playlists.name ILIKE $2 DESC, LENGTH(playlists.name), playlists.name
+        LIMIT $4
+        "#,
+        exact,
+        prefix,
+        contains,
+        limit
+    )
+    .fetch_all(db);
+
+    // The four don't depend on each other, so they run at the same time
+    let (songs, artists, albums, playlists) = tokio::try_join!(songs, artists, albums, playlists)?;
+
+    let playlists = playlists
+        .into_iter()
+        .map(|playlist| PlaylistSummary {
+            playlist_id: playlist.playlist_id,
+            name: playlist.name,
+            owner: UserSummary {
+                username: playlist.username,
+                name: playlist.owner_name,
+                photo: playlist.photo.unwrap_or_default(),
+            },
+            song_count: playlist.song_count,
+        })
+        .collect();
+
+    Ok(SearchResults {
+        songs: songs.into_iter().map(SongSummary::from).collect(),
+        artists,
+        albums,
+        playlists,
+    })
+}
+
+/// Searches the catalog and public playlists, for the search results page.
+#[utoipa::path(
+    get,
+    path = "/api/v1/search",
+    tag = "search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Up to `limit` matches of each kind, 20 by default and at most 50", body = SearchResponse),
+    )
+)]
+pub async fn search_handler(
+    Query(query): Query<SearchQuery>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    let q = query.q.trim().chars().take(MAX_QUERY_CHARS).collect::<String>();
+
+    let results = search(&db, &q, limit).await?;
+
+    Ok(Json(SearchResponse {
+        status: "success".to_string(),
+        query: q,
+        results,
+    }))
+}
+
+/// A few of the best matches of each kind, for suggestions while the user types.
+#[utoipa::path(
+    get,
+    path = "/api/v1/search/suggestions",
+    tag = "search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Up to `limit` matches of each kind, 5 by default and at most 10", body = SearchResponse),
+    )
+)]
+pub async fn search_suggestions_handler(
+    Query(query): Query<SearchQuery>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let limit = query.limit.unwrap_or(DEFAULT_SUGGESTIONS_LIMIT).clamp(1, MAX_SUGGESTIONS_LIMIT);
+    let q = query.q.trim().chars().take(MAX_QUERY_CHARS).collect::<String>();
+
+    let results = search(&db, &q, limit).await?;
+
+    Ok(Json(SearchResponse {
+        status: "success".to_string(),
+        query: q,
+        results,
+    }))
+}
@@ -0,0 +1,512 @@
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{
+    error::AppError,
+    model::Users,
+    utils::{cursor::{next_page, Cursor, PageQuery}, privacy::can_view, validated_json::ValidatedJson},
+    AppState
+};
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use common::schema::message::MessageResponse;
+use common::schema::social::{
+    FeedItem, FeedItemKind, FeedPlaylist, FeedResponse, FeedSong, FollowListResponse, FollowStatusResponse,
+    RecordPlaySchema, UserSummary, Visibility
+};
+
+fn invalid_cursor() -> AppError {
+    AppError::BadRequest("Invalid cursor".to_string())
+}
+
+/// Resolves the cursor in the query, `None` for the first page.
+fn page_cursor(query: &PageQuery) -> Result<Option<Cursor>, AppError> {
+    query.cursor.as_deref()
+        .map(|cursor| Cursor::decode(cursor).ok_or_else(invalid_cursor))
+        .transpose()
+}
+
+async fn find_user_id(db: &Pool<Postgres>, username: &str) -> Result<uuid::Uuid, AppError> {
+    sqlx::query_scalar!("SELECT user_id FROM users WHERE LOWER(username) = LOWER($1)", username)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+}
+
+/// Like `find_user_id`, but also checks that the user's profile visibility lets `viewer` see their follows.
+async fn find_visible_user_id(db: &Pool<Postgres>, username: &str, viewer: Option<&Users>) -> Result<uuid::Uuid, AppError> {
+    let user = sqlx::query!(
+        "SELECT user_id, profile_visibility FROM users WHERE LOWER(username) = LOWER($1)",
+        username
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let visible = can_view(db, Visibility::from(user.profile_visibility), user.user_id, viewer)
+        .await?;
+
+    if !visible {
+        return Err(AppError::Forbidden("This user's profile is private".to_string()));
+    }
+
+    Ok(user.user_id)
+}
+
+async fn follow_status(db: &Pool<Postgres>, follower_id: uuid::Uuid, followee_id: uuid::Uuid) -> Result<FollowStatusResponse, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            EXISTS(SELECT 1 FROM follows WHERE follower_id = $1 AND followee_id = $2) AS "following!",
+            (SELECT COUNT(*) FROM follows WHERE followee_id = $2) AS "follower_count!"
+        "#,
+        follower_id,
+        followee_id
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(FollowStatusResponse {
+        status: "success".to_string(),
+        following: row.following,
+        follower_count: row.follower_count,
+    })
+}
+
+/// Whether the logged in user follows `username`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{username}/follow",
+    tag = "social",
+    params(
+        ("username" = String, Path, description = "Username, case insensitive"),
+    ),
+    responses(
+        (status = 200, body = FollowStatusResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn get_follow_status_handler(
+    Path(username): Path<String>,
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let followee_id = find_user_id(&db, &username).await?;
+
+    let status = follow_status(&db, user.user_id, followee_id)
+        .await?;
+
+    Ok(Json(status))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{username}/follow",
+    tag = "social",
+    params(
+        ("username" = String, Path, description = "Username, case insensitive"),
+    ),
+    responses(
+        (status = 200, description = "Following, also if already followed", body = FollowStatusResponse),
+        (status = 400, description = "Can't follow yourself", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn follow_user_handler(
+    Path(username): Path<String>,
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let followee_id = find_user_id(&db, &username).await?;
+
+    if followee_id == user.user_id {
+        return Err(AppError::BadRequest("You can't follow yourself".to_string()));
+    }
+
+    // Following someone twice is a no-op rather than an error
+    sqlx::query!(
+        "INSERT INTO follows (follower_id, followee_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        user.user_id,
+        followee_id
+    )
+    .execute(&db)
+    .await?;
+
+    let status = follow_status(&db, user.user_id, followee_id)
+        .await?;
+
+    Ok(Json(status))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{username}/follow",
+    tag = "social",
+    params(
+        ("username" = String, Path, description = "Username, case insensitive"),
+    ),
+    responses(
+        (status = 200, description = "Not following, also if not followed before", body = FollowStatusResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn unfollow_user_handler(
+    Path(username): Path<String>,
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let followee_id = find_user_id(&db, &username).await?;
+
+    sqlx::query!(
+        "DELETE FROM follows WHERE follower_id = $1 AND followee_id = $2",
+        user.user_id,
+        followee_id
+    )
+    .execute(&db)
+    .await?;
+
+    let status = follow_status(&db, user.user_id, followee_id)
+        .await?;
+
+    Ok(Json(status))
+}
+
+struct FollowRow {
+    user_id: uuid::Uuid,
+    username: String,
+    name: String,
+    photo: Option<String>,
+    followed_at: DateTime<Utc>,
+}
+
+fn follow_list_response(mut rows: Vec<FollowRow>, limit: i64) -> FollowListResponse {
+    let next_cursor = next_page(&mut rows, limit, |row| Cursor { at: row.followed_at, id: row.user_id });
+
+    FollowListResponse {
+        status: "success".to_string(),
+        users: rows.into_iter().map(|row| UserSummary {
+            username: row.username,
+            name: row.name,
+            photo: row.photo.unwrap_or_default(),
+        }).collect(),
+        next_cursor,
+    }
+}
+
+/// Users following `username`, most recent first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{username}/followers",
+    tag = "social",
+    params(
+        ("username" = String, Path, description = "Username, case insensitive"),
+        PageQuery,
+    ),
+    responses(
+        (status = 200, body = FollowListResponse),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
+        (status = 403, description = "The user's profile is hidden from the viewer", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    ),
+    security((), ("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn list_followers_handler(
+    Path(username): Path<String>,
+    Query(query): Query<PageQuery>,
+    viewer: Option<Extension<Users>>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let viewer = viewer.map(|Extension(viewer)| viewer);
+    let user_id = find_visible_user_id(&db, &username, viewer.as_ref()).await?;
+    let cursor = page_cursor(&query)?;
+    let limit = query.limit();
+
+    let rows = sqlx::query_as!(
+        FollowRow,
+        r#"
+        SELECT users.user_id, users.username, users.name, users.photo, follows.created_at AS followed_at
+        FROM follows
+        JOIN users ON users.user_id = follows.follower_id
+        WHERE follows.followee_id = $1
+            AND ($2::timestamptz IS NULL OR (follows.created_at, users.user_id) < ($2, $3))
+        ORDER BY follows.created_at DESC, users.user_id DESC
+        LIMIT $4
+        "#,
+        user_id,
+        cursor.map(|cursor| cursor.at),
+        cursor.map(|cursor| cursor.id),
+        limit + 1
+    )
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(follow_list_response(rows, limit)))
+}
+
+/// Users `username` follows, most recent first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{username}/following",
+    tag = "social",
+    params(
+        ("username" = String, Path, description = "Username, case insensitive"),
+        PageQuery,
+    ),
+    responses(
+        (status = 200, body = FollowListResponse),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
+        (status = 403, description = "The user's profile is hidden from the viewer", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+    ),
+    security((), ("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn list_following_handler(
+    Path(username): Path<String>,
+    Query(query): Query<PageQuery>,
+    viewer: Option<Extension<Users>>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let viewer = viewer.map(|Extension(viewer)| viewer);
+    let user_id = find_visible_user_id(&db, &username, viewer.as_ref()).await?;
+    let cursor = page_cursor(&query)?;
+    let limit = query.limit();
+
+    let rows = sqlx::query_as!(
+        FollowRow,
+        r#"
+        SELECT users.user_id, users.username, users.name, users.photo, follows.created_at AS followed_at
+        FROM follows
+        JOIN users ON users.user_id = follows.followee_id
+        WHERE follows.follower_id = $1
+            AND ($2::timestamptz IS NULL OR (follows.created_at, users.user_id) < ($2, $3))
+        ORDER BY follows.created_at DESC, users.user_id DESC
+        LIMIT $4
+        "#,
+        user_id,
+        cursor.map(|cursor| cursor.at),
+        cursor.map(|cursor| cursor.id),
+        limit + 1
+    )
+    .fetch_all(&db)
+    .await?;
+
+    Ok(Json(follow_list_response(rows, limit)))
+}
+
+/// Likes, plays and new public playlists of everyone the logged in user follows, newest first.
+/// Nothing is shown for users whose `profile_visibility` is `PRIVATE`,
+/// and likes and plays are left out for users whose `history_visibility` is `PRIVATE`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/feed",
+    tag = "social",
+    params(
+        PageQuery,
+    ),
+    responses(
+        (status = 200, body = FeedResponse),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn get_feed_handler(
+    Query(query): Query<PageQuery>,
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    let cursor = page_cursor(&query)?;
+    let limit = query.limit();
+
+    let mut rows = sqlx::query!(
+        r#"
+        SELECT events.event_id AS "event_id!", events.kind AS "kind!", events.occurred_at AS "occurred_at!",
+            users.username, users.name, users.photo,
+            songs.song_id AS "song_id?", songs.title AS "song_title?",
+            playlists.playlist_id AS "playlist_id?", playlists.name AS "playlist_name?"
+        FROM (
+            SELECT like_id AS event_id, 'LIKE' AS kind, user_id, song_id, NULL::uuid AS playlist_id, created_at AS occurred_at
+            FROM song_likes
+            UNION ALL
+            SELECT play_id, 'PLAY', user_id, song_id, NULL, played_at
+            FROM listening_history
+            UNION ALL
+            SELECT playlist_id, 'PLAYLIST', user_id, NULL, playlist_id, created_at
+            FROM playlists WHERE is_public
+        ) AS events
+        JOIN follows ON follows.followee_id = events.user_id AND follows.follower_id = $1
+        JOIN users ON users.user_id = events.user_id
+        LEFT JOIN songs ON songs.song_id = events.song_id
+        LEFT JOIN playlists ON playlists.playlist_id = events.playlist_id
+        WHERE users.profile_visibility <> 'PRIVATE'
+            AND (events.kind = 'PLAYLIST' OR users.history_visibility <> 'PRIVATE')
+            AND ($2::timestamptz IS NULL OR (events.occurred_at, events.event_id) < ($2, $3))
+        ORDER BY events.occurred_at DESC, events.event_id DESC
+        LIMIT $4
+        "#,
+        user.user_id,
+        cursor.map(|cursor| cursor.at),
+        cursor.map(|cursor| cursor.id),
+        limit + 1
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let next_cursor = next_page(&mut rows, limit, |row| Cursor { at: row.occurred_at, id: row.event_id });
+
+    let items = rows.into_iter().map(|row| {
+        let kind = match row.kind.as_str() {
+            "LIKE" => FeedItemKind::Like,
+            "PLAY" => FeedItemKind::Play,
+            _ => FeedItemKind::Playlist,
+        };
+
+        FeedItem {
+            id: row.event_id,
+            kind,
+            user: UserSummary {
+                username: row.username,
+                name: row.name,
+                photo: row.photo.unwrap_or_default(),
+            },
+            song: row.song_id.zip(row.song_title).map(|(song_id, title)| FeedSong { song_id, title }),
+            playlist: row.playlist_id.zip(row.playlist_name).map(|(playlist_id, name)| FeedPlaylist { playlist_id, name }),
+            occurred_at: row.occurred_at,
+        }
+    }).collect();
+
+    Ok(Json(FeedResponse {
+        status: "success".to_string(),
+        items,
+        next_cursor,
+    }))
+}
+
+pub(crate) async fn ensure_song_exists(db: &Pool<Postgres>, song_id: uuid::Uuid) -> Result<(), AppError> {
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM songs WHERE song_id = $1) AS "exists!""#,
+        song_id
+    )
+    .fetch_one(db)
+    .await?;
+
+    if !exists {
+        return Err(AppError::NotFound("Song not found".to_string()));
+    }
+
+    Ok(())
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/user/likes/{song_id}",
+    tag = "social",
+    params(
+        ("song_id" = uuid::Uuid, Path, description = "Id of the song"),
+    ),
+    responses(
+        (status = 200, description = "Liked, also if liked before", body = MessageResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "Song not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn like_song_handler(
+    Path(song_id): Path<uuid::Uuid>,
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    ensure_song_exists(&db, song_id).await?;
+
+    sqlx::query!(
+        "INSERT INTO song_likes (like_id, user_id, song_id) VALUES ($1, $2, $3) ON CONFLICT (user_id, song_id) DO NOTHING",
+        uuid::Uuid::new_v4(),
+        user.user_id,
+        song_id
+    )
+    .execute(&db)
+    .await?;
+
+    Ok(Json(MessageResponse::success("Song liked")))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/user/likes/{song_id}",
+    tag = "social",
+    params(
+        ("song_id" = uuid::Uuid, Path, description = "Id of the song"),
+    ),
+    responses(
+        (status = 200, description = "Not liked, also if not liked before", body = MessageResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn unlike_song_handler(
+    Path(song_id): Path<uuid::Uuid>,
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    sqlx::query!(
+        "DELETE FROM song_likes WHERE user_id = $1 AND song_id = $2",
+        user.user_id,
+        song_id
+    )
+    .execute(&state.read().await.db)
+    .await?;
+
+    Ok(Json(MessageResponse::success("Song unliked")))
+}
+
+/// Adds a song to the logged in user's listening history.
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/history",
+    tag = "social",
+    request_body = RecordPlaySchema,
+    responses(
+        (status = 200, body = MessageResponse),
+        (status = 400, description = "Invalid request or failed validation", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "Song not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn record_play_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<RecordPlaySchema>
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+    ensure_song_exists(&db, payload.song_id).await?;
+
+    sqlx::query!(
+        "INSERT INTO listening_history (play_id, user_id, song_id) VALUES ($1, $2, $3)",
+        uuid::Uuid::new_v4(),
+        user.user_id,
+        payload.song_id
+    )
+    .execute(&db)
+    .await?;
+
+    Ok(Json(MessageResponse::success("Play recorded")))
+}
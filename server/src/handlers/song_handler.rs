@@ -0,0 +1,318 @@
+use axum::{
+    extract::{Multipart, Path},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{
+    audio::{self, AudioFormat},
+    error::AppError,
+    model::{SongSummaryRow, Users},
+    utils::stream::serve_blob,
+    AppState
+};
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use common::schema::{
+    platform::Platform,
+    song::{AudioFeaturesResponse, PlatformLink, SongDetails, SongDetailsResponse, SongFeatures, SongSummary}
+};
+
+/// Name of the multipart field the audio is uploaded in.
+const AUDIO_FIELD: &str = "audio";
+/// Full songs are only streamed to logged in users, so only the browser may cache them.
+const STREAM_CACHE_CONTROL: &str = "private, max-age=3600";
+const PREVIEW_CACHE_CONTROL: &str = "public, max-age=3600";
+/// How many similar songs are listed with a song.
+const SIMILAR_SONGS_LIMIT: i64 = 10;
+
+/// Looks a song up with its features, where it can be listened to and the songs most like it.
+///
+/// Similar songs are those of the same genre first, then those with the closest tempo, loudness,
+/// speechiness and danceability. A feature missing from either song counts as a typical difference.
+#[utoipa::path(
+    get,
+    path = "/api/v1/songs/{song_id}",
+    tag = "songs",
+    params(
+        ("song_id" = uuid::Uuid, Path, description = "Id of the song"),
+    ),
+    responses(
+        (status = 200, description = "The song", body = SongDetailsResponse),
+        (status = 404, description = "Song not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_song_handler(
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    Path(song_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let db = state.read().await.db.clone();
+
+    let song = sqlx::query!(
+        r#"
+        SELECT songs.song_id, songs.title, songs.artist_id, artists.name AS artist, songs.album_id,
+            albums.title AS album, albums.cover, albums.release_date, songs.duration, songs.genre::TEXT AS "genre!",
+            songs.tempo, songs.key, songs.time_signature, songs.loudness, songs.speechiness, songs.danceability,
+            songs.external_url, songs.audio_key IS NOT NULL AS "has_audio!"
+        FROM songs
+        JOIN artists ON artists.artist_id = songs.artist_id
+        JOIN albums ON albums.album_id = songs.album_id
+        WHERE songs.song_id = $1
+        "#,
+        song_id
+    )
+    .fetch_optional(&db)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Song not found".to_string()))?;
+
+    let similar = sqlx::query_as!(
+        SongSummaryRow,
+        r#"
+        SELECT songs.song_id, songs.title, songs.artist_id, artists.name AS artist, songs.album_id,
+            albums.title AS album, albums.cover, songs.duration, songs.audio_key IS NOT NULL AS "has_audio!"
+        FROM songs
+        JOIN artists ON artists.artist_id = songs.artist_id
+        JOIN albums ON albums.album_id = songs.album_id
+        JOIN songs AS seed ON seed.song_id = $1
+        WHERE songs.song_id <> seed.song_id
+        ORDER BY songs.genre = seed.genre DESC,
+            COALESCE(ABS(songs.tempo - seed.tempo) / 30, 1)
+                + COALESCE(ABS(songs.loudness - seed.loudness) / 6, 1)
+                + COALESCE(ABS(songs.speechiness - seed.speechiness) / 0.33, 1)
+                + COALESCE(ABS(songs.danceability - seed.danceability) / 0.33, 1),
+            songs.title
+        LIMIT $2
+        "#,
+        song_id,
+        SIMILAR_SONGS_LIMIT
+    )
+    .fetch_all(&db)
+    .await?;
+
+    let links = song.external_url
+        .into_iter()
+        .map(|url| PlatformLink { platform: Platform::from_url(&url), url })
+        .collect();
+
+    Ok(Json(SongDetailsResponse {
+        status: "success".to_string(),
+        song: SongDetails {
+            song: SongSummary::from(SongSummaryRow {
+                song_id: song.song_id,
+                title: song.title,
+                artist_id: song.artist_id,
+                artist: song.artist,
+                album_id: song.album_id,
+                album: song.album,
+                cover: song.cover,
+                duration: song.duration,
+                has_audio: song.has_audio,
+            }),
+            genre: song.genre,
+            release_date: song.release_date,
+            features: SongFeatures {
+                tempo: song.tempo,
+                key: song.key,
+                time_signature: song.time_signature,
+                loudness: song.loudness,
+                speechiness: song.speechiness,
+                danceability: song.danceability,
+            },
+            links,
+            similar: similar.into_iter().map(SongSummary::from).collect(),
+        },
+    }))
+}
+
+/// The multipart form `upload_song_audio_handler` accepts, only used to document it.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct AudioUpload {
+    /// A WAV, FLAC or MP3 file
+    #[schema(value_type = String, format = Binary)]
+    audio: Vec<u8>,
+}
+
+/// Accepts an audio file in the `audio` field of a multipart form, stores it as the song's audio and
+/// replaces the song's duration, tempo, key, loudness and speechiness with those measured from it.
+///
+/// Admins can upload audio for any song, other users only for songs they added. Once a song has
+/// audio, only the user who uploaded it can replace it.
+#[utoipa::path(
+    post,
+    path = "/api/v1/songs/{song_id}/audio",
+    tag = "songs",
+    params(
+        ("song_id" = uuid::Uuid, Path, description = "Id of the song"),
+    ),
+    request_body(content = AudioUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "The features measured from the audio", body = AudioFeaturesResponse),
+        (status = 400, description = "No `audio` field, or audio that can't be decoded", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 403, description = "Not an admin and not the user who added the song, or its audio was uploaded by someone else", body = ErrorResponse),
+        (status = 404, description = "Song not found", body = ErrorResponse),
+        (status = 413, description = "File too large", body = ErrorResponse),
+        (status = 415, description = "Not a WAV, FLAC or MP3 file", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn upload_song_audio_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    Path(song_id): Path<Uuid>,
+    mut multipart: Multipart
+) -> Result<impl IntoResponse, AppError> {
+    let (db, storage, max_bytes) = {
+        let state = state.read().await;
+        (state.db.clone(), state.storage.clone(), state.env.storage.max_audio_bytes)
+    };
+
+    // Checked before reading the upload, so a rejected one isn't buffered for nothing
+    let song = sqlx::query!("SELECT created_by FROM songs WHERE song_id = $1", song_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Song not found".to_string()))?;
+
+    if !user.is_admin && song.created_by != Some(user.user_id) {
+        return Err(AppError::Forbidden("Only admins and the user who added this song can upload its audio".to_string()));
+    }
+
+    // Claimed in the same statement that checks it's free, so of concurrent uploads only one gets it
+    let claimed = sqlx::query!(
+        r#"
+        UPDATE songs SET audio_uploaded_by = $2
+        WHERE song_id = $1 AND ((audio_key IS NULL AND audio_uploaded_by IS NULL) OR audio_uploaded_by = $2)
+        RETURNING song_id
+        "#,
+        song_id,
+        user.user_id
+    )
+    .fetch_optional(&db)
+    .await?;
+
+    if claimed.is_none() {
+        return Err(AppError::Forbidden("Only the user who uploaded this song's audio can replace it".to_string()));
+    }
+
+    let result = async {
+        let mut upload = None;
+        while let Some(mut field) = multipart.next_field().await? {
+            if field.name() != Some(AUDIO_FIELD) {
+                continue;
+            }
+
+            // Read in chunks so an oversized upload is rejected without buffering all of it
+            let mut bytes = Vec::new();
+            while let Some(chunk) = field.chunk().await? {
+                if bytes.len() + chunk.len() > max_bytes {
+                    return Err(AppError::PayloadTooLarge(format!("Audio files must be at most {} MB", max_bytes / (1024 * 1024))));
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+
+            upload = Some(bytes);
+            break;
+        }
+
+        let bytes = upload.ok_or_else(|| AppError::BadRequest("Missing audio file".to_string()))?;
+
+        Ok(audio::ingest(&db, &storage, song_id, bytes, Some(user.user_id)).await?)
+    }
+    .await;
+
+    if result.is_err() {
+        // A failed first upload shouldn't keep the song's audio from being uploaded by anyone else
+        sqlx::query!(
+            "UPDATE songs SET audio_uploaded_by = NULL WHERE song_id = $1 AND audio_key IS NULL AND audio_uploaded_by = $2",
+            song_id,
+            user.user_id
+        )
+        .execute(&db)
+        .await?;
+    }
+
+    let features = result?;
+
+    Ok(Json(AudioFeaturesResponse {
+        status: "success".to_string(),
+        data: features,
+    }))
+}
+
+
+/// Streams the song's audio. Supports `Range` requests, so players can seek.
+#[utoipa::path(
+    get,
+    path = "/api/v1/songs/{song_id}/stream",
+    tag = "songs",
+    params(
+        ("song_id" = uuid::Uuid, Path, description = "Id of the song"),
+    ),
+    responses(
+        (status = 200, description = "The whole file", content_type = "audio/*"),
+        (status = 206, description = "The range of the file asked for", content_type = "audio/*"),
+        (status = 304, description = "The cached copy with this `ETag` is current"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 404, description = "Song not found, or we don't host its audio", body = ErrorResponse),
+        (status = 416, description = "The range starts after the end of the file"),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn stream_song_handler(
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    Path(song_id): Path<Uuid>,
+    headers: HeaderMap
+) -> Result<impl IntoResponse, AppError> {
+    let (db, storage) = {
+        let state = state.read().await;
+        (state.db.clone(), state.storage.clone())
+    };
+
+    let key = sqlx::query_scalar!("SELECT audio_key FROM songs WHERE song_id = $1", song_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Song not found".to_string()))?
+        .ok_or_else(|| AppError::NotFound("This song has no audio to stream".to_string()))?;
+    let content_type = AudioFormat::from_key(&key).map_or("application/octet-stream", AudioFormat::content_type);
+
+    serve_blob(storage.as_ref(), &key, content_type, STREAM_CACHE_CONTROL, &headers).await
+}
+
+/// Streams a 30 second preview of the song, to anyone. Supports `Range` requests like the full stream.
+#[utoipa::path(
+    get,
+    path = "/api/v1/songs/{song_id}/preview",
+    tag = "songs",
+    params(
+        ("song_id" = uuid::Uuid, Path, description = "Id of the song"),
+    ),
+    responses(
+        (status = 200, description = "The whole preview", content_type = "audio/wav"),
+        (status = 206, description = "The range of the preview asked for", content_type = "audio/wav"),
+        (status = 304, description = "The cached copy with this `ETag` is current"),
+        (status = 404, description = "Song not found, or it has no preview", body = ErrorResponse),
+        (status = 416, description = "The range starts after the end of the preview"),
+    )
+)]
+pub async fn stream_preview_handler(
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    Path(song_id): Path<Uuid>,
+    headers: HeaderMap
+) -> Result<impl IntoResponse, AppError> {
+    let (db, storage) = {
+        let state = state.read().await;
+        (state.db.clone(), state.storage.clone())
+    };
+
+    let key = sqlx::query_scalar!("SELECT preview_key FROM songs WHERE song_id = $1", song_id)
+        .fetch_optional(&db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Song not found".to_string()))?
+        .ok_or_else(|| AppError::NotFound("This song has no preview".to_string()))?;
+
+    serve_blob(storage.as_ref(), &key, AudioFormat::Wav.content_type(), PREVIEW_CACHE_CONTROL, &headers).await
+}
\ No newline at end of file
@@ -0,0 +1,313 @@
+use axum::{
+    response::IntoResponse,
+    Json,
+    Extension
+};
+use crate::{
+    error::AppError,
+    handlers::auth_handler::{session_response, too_many_attempts},
+    model::Users,
+    rate_limit::Decision,
+    utils::{
+        hash::verify,
+        jwt::decode_challenge_token,
+        token::hash_token,
+        totp::{generate_recovery_codes, generate_secret, normalize_recovery_code, otpauth_uri, verify_code},
+        validated_json::ValidatedJson,
+    },
+    AppState
+};
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use tokio::sync::RwLock;
+use std::sync::Arc;
+use common::schema::message::MessageResponse;
+use common::schema::two_factor::{
+    DisableTwoFactorSchema, RecoveryCodesResponse, TwoFactorCodeSchema, TwoFactorEnrollResponse, TwoFactorVerifySchema
+};
+
+/// Replaces the user's recovery codes with a fresh set and returns them in plain text.
+async fn replace_recovery_codes(db: &Pool<Postgres>, user_id: uuid::Uuid) -> Result<Vec<String>, sqlx::Error> {
+    let codes = generate_recovery_codes();
+    let mut tx = db.begin().await?;
+
+    sqlx::query!("DELETE FROM recovery_codes WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for code in &codes {
+        sqlx::query!(
+            "INSERT INTO recovery_codes (code_id, user_id, code_hash) VALUES ($1, $2, $3)",
+            uuid::Uuid::new_v4(),
+            user_id,
+            hash_token(&normalize_recovery_code(code))
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(codes)
+}
+
+/// Checks a code from the authenticator app or, failing that, one of the recovery codes.
+/// Whichever matches is consumed so it can't be used again.
+pub(crate) async fn check_second_factor(db: &Pool<Postgres>, user: &Users, code: &str) -> Result<bool, AppError> {
+    let Some(secret) = &user.totp_secret else {
+        return Ok(false);
+    };
+
+    let step = verify_code(secret, &user.email, code, user.totp_last_step)
+        .map_err(AppError::Internal)?;
+
+    if let Some(step) = step {
+        // Only advance the step if no concurrent request has used this code already
+        let result = sqlx::query!(
+            "UPDATE users SET totp_last_step = $1 WHERE user_id = $2 AND (totp_last_step IS NULL OR totp_last_step < $1)",
+            step,
+            user.user_id
+        )
+        .execute(db)
+        .await?;
+
+        return Ok(result.rows_affected() == 1);
+    }
+
+    let recovery_code = sqlx::query_scalar!(
+        "UPDATE recovery_codes SET used_at = NOW() WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL RETURNING code_id",
+        user.user_id,
+        hash_token(&normalize_recovery_code(code))
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(recovery_code.is_some())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/2fa/enroll",
+    tag = "two-factor",
+    responses(
+        (status = 200, description = "Secret to add to an authenticator app", body = TwoFactorEnrollResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "Already enabled", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn enroll_two_factor_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, AppError> {
+    if user.totp_enabled {
+        return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+    }
+
+    let secret = generate_secret();
+    let otpauth_uri = otpauth_uri(&secret, &user.email)
+        .map_err(AppError::Internal)?;
+
+    // The secret stays pending until the user proves their app generates valid codes
+    sqlx::query!(
+        "UPDATE users SET totp_secret = $1, totp_last_step = NULL, updated_at = NOW() WHERE user_id = $2",
+        secret,
+        user.user_id
+    )
+    .execute(&state.try_read().unwrap().db)
+    .await?;
+
+    Ok(Json(TwoFactorEnrollResponse {
+        status: "success".to_string(),
+        otpauth_uri,
+        secret,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/2fa/enable",
+    tag = "two-factor",
+    request_body = TwoFactorCodeSchema,
+    responses(
+        (status = 200, description = "Enabled, with the recovery codes to store", body = RecoveryCodesResponse),
+        (status = 400, description = "Invalid code, or enrollment not started", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "Already enabled", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn enable_two_factor_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<TwoFactorCodeSchema>
+) -> Result<impl IntoResponse, AppError> {
+    if user.totp_enabled {
+        return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+    }
+
+    let secret = user.totp_secret.as_ref()
+        .ok_or_else(|| AppError::BadRequest("Start two-factor enrollment first".to_string()))?;
+
+    let step = verify_code(secret, &user.email, &payload.code, None)
+        .map_err(AppError::Internal)?
+        .ok_or(AppError::InvalidCode)?;
+
+    let db = state.try_read().unwrap().db.clone();
+
+    sqlx::query!(
+        "UPDATE users SET totp_enabled = TRUE, totp_last_step = $1, updated_at = NOW() WHERE user_id = $2",
+        step,
+        user.user_id
+    )
+    .execute(&db)
+    .await?;
+
+    let recovery_codes = replace_recovery_codes(&db, user.user_id).await?;
+
+    Ok(Json(RecoveryCodesResponse {
+        status: "success".to_string(),
+        recovery_codes,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/2fa/disable",
+    tag = "two-factor",
+    request_body = DisableTwoFactorSchema,
+    responses(
+        (status = 200, description = "Disabled", body = MessageResponse),
+        (status = 400, description = "Invalid password or code", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn disable_two_factor_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<DisableTwoFactorSchema>
+) -> Result<impl IntoResponse, AppError> {
+    if !user.totp_enabled {
+        return Err(AppError::BadRequest("Two-factor authentication is not enabled".to_string()));
+    }
+
+    let is_valid = verify(&payload.password, &user.password).await?;
+
+    if !is_valid {
+        return Err(AppError::InvalidCredentials("Invalid password".to_string()));
+    }
+
+    let db = state.try_read().unwrap().db.clone();
+
+    if !check_second_factor(&db, &user, &payload.code).await? {
+        return Err(AppError::InvalidCode);
+    }
+
+    sqlx::query!(
+        "UPDATE users SET totp_enabled = FALSE, totp_secret = NULL, totp_last_step = NULL, updated_at = NOW() WHERE user_id = $1",
+        user.user_id
+    )
+    .execute(&db)
+    .await?;
+
+    sqlx::query!("DELETE FROM recovery_codes WHERE user_id = $1", user.user_id)
+        .execute(&db)
+        .await?;
+
+    Ok(Json(MessageResponse::success("Two-factor authentication disabled")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/2fa/recovery-codes",
+    tag = "two-factor",
+    request_body = TwoFactorCodeSchema,
+    responses(
+        (status = 200, description = "New recovery codes, the old ones no longer work", body = RecoveryCodesResponse),
+        (status = 400, description = "Invalid code, or not enabled", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn regenerate_recovery_codes_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<TwoFactorCodeSchema>
+) -> Result<impl IntoResponse, AppError> {
+    if !user.totp_enabled {
+        return Err(AppError::BadRequest("Two-factor authentication is not enabled".to_string()));
+    }
+
+    let db = state.try_read().unwrap().db.clone();
+
+    if !check_second_factor(&db, &user, &payload.code).await? {
+        return Err(AppError::InvalidCode);
+    }
+
+    let recovery_codes = replace_recovery_codes(&db, user.user_id).await?;
+
+    Ok(Json(RecoveryCodesResponse {
+        status: "success".to_string(),
+        recovery_codes,
+    }))
+}
+
+/// Second step of a login for accounts with 2FA enabled.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/2fa/verify",
+    tag = "auth",
+    request_body = TwoFactorVerifySchema,
+    responses(
+        (status = 200, description = "Logged in and the tokens set as cookies", body = UserLoginResponse),
+        (status = 400, description = "Invalid code", body = ErrorResponse),
+        (status = 401, description = "Challenge expired, the login has to start over", body = ErrorResponse),
+        (status = 429, description = "Too many requests, see the `Retry-After` header", body = ErrorResponse),
+    )
+)]
+pub async fn verify_two_factor_handler(
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<TwoFactorVerifySchema>
+) -> Result<impl IntoResponse, AppError> {
+    let state = state.read().await;
+    let (db, rate_limiter) = (state.db.clone(), state.rate_limiter.clone());
+
+    let claims = decode_challenge_token(&state.keys, &payload.challenge_token)
+        .map_err(|_| AppError::ChallengeExpired)?;
+
+    // Codes are short, so guesses are throttled and lock the second step out like failed passwords
+    let account = format!("2fa:{}", claims.sub);
+    if let Decision::Limited { retry_after } = rate_limiter.check_account(&account).await? {
+        return Err(too_many_attempts(retry_after));
+    }
+
+    if let Some(locked_until) = rate_limiter.locked_until(&account).await? {
+        return Err(too_many_attempts((locked_until - Utc::now()).num_seconds().max(1) as u64));
+    }
+
+    let user_id = uuid::Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::ChallengeExpired)?;
+
+    let user = sqlx::query_as!(
+        Users,
+        "SELECT * FROM users WHERE user_id = $1",
+        user_id
+    )
+    .fetch_optional(&db)
+    .await?
+    .filter(|user| user.totp_enabled)
+    .ok_or(AppError::ChallengeExpired)?;
+
+    if !check_second_factor(&db, &user, &payload.code).await? {
+        if let Some(locked_until) = rate_limiter.record_failure(&account).await? {
+            return Err(too_many_attempts((locked_until - Utc::now()).num_seconds().max(1) as u64));
+        }
+
+        return Err(AppError::InvalidCode);
+    }
+
+    rate_limiter.reset_failures(&account).await?;
+
+    Ok(session_response(&state, user))
+}
@@ -1,13 +1,38 @@
 use axum::{
-    extract::Path, http::{ header, Response, StatusCode }, response::IntoResponse, routing::get, Extension, Json, Router
+    extract::Path, http::{ header, HeaderMap }, response::IntoResponse, routing::get, Extension, Json, Router
 };
-use axum_extra::{headers::Cookie, TypedHeader};
-use crate::{model::Users, utils::jwt::{decode_token, AccessClaims, AuthError}, AppState};
+use crate::{
+    error::AppError,
+    handlers::two_factor_handler::check_second_factor,
+    mailer::Email,
+    model::{TokenPurpose, Users},
+    utils::{avatar::{avatar_key, AVATAR_SIZES}, cookie::removal_cookie, hash::{hash, verify}, token::issue_token, validated_json::ValidatedJson},
+    AppState
+};
+use chrono::TimeDelta;
 use tokio::sync::RwLock;
+use tracing::error;
 use std::sync::Arc;
-use common::schema::{feedback::ErrorResponse, platform::Platform, user::{FilteredUser, UserData, UserResponse}};
+use common::schema::{
+    message::MessageResponse,
+    platform::Platform,
+    privacy::{PrivacySettings, UpdatePrivacySchema},
+    social::Visibility,
+    user::{ChangeEmailSchema, ChangePasswordSchema, DeleteAccountSchema, FilteredUser, UpdateProfileSchema, UserData, UserResponse}
+};
 use serde_json::json;
 
+/// How long the link confirming a new email address stays valid.
+const EMAIL_CHANGE_TOKEN_TTL_HOURS: i64 = 24;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/healthchecker",
+    tag = "health",
+    responses(
+        (status = 200, description = "The server is up", body = MessageResponse),
+    )
+)]
 pub async fn health_check_handler(Extension(state): Extension<Arc<RwLock<AppState>>>) -> impl IntoResponse {
     const MESSAGE: &str = "Rusty Melody is healthy!";
 
@@ -19,84 +44,362 @@ pub async fn health_check_handler(Extension(state): Extension<Arc<RwLock<AppStat
     Json(json_response)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/preferences",
+    tag = "user",
+    responses(
+        (status = 200, description = "Not implemented yet, the body is empty"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
 pub async fn get_user_preferences_handler(Extension(state): Extension<Arc<RwLock<AppState>>>) -> impl IntoResponse {
     // Add logic to get user preferences
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/user/preferences",
+    tag = "user",
+    responses(
+        (status = 200, description = "Not implemented yet, the body is empty"),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
 pub async fn update_user_preferences_handler(Extension(state): Extension<Arc<RwLock<AppState>>>) -> impl IntoResponse {
     // Add logic to update user preferences
 }
 
-pub async fn get_user_handler(
-    TypedHeader(cookies): TypedHeader<Cookie>,
-    Extension(state): Extension<Arc<RwLock<AppState>>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let token = cookies.get("access_token")
-        .ok_or((StatusCode::UNAUTHORIZED, Json(ErrorResponse {
-            status: "fail".to_string(),
-            message: "Access token not found".to_string(),
-        })))?;
-
-    // Decode the token to extract the user_id
-    let user_id = decode_token::<AccessClaims>(&token)
-        .map_err(|e| {
-            let error_response = ErrorResponse {
-                status: "fail".to_string(),
-                message: match e {
-                    AuthError::InvalidToken => "Invalid access token".to_string(),
-                    _ => "Unknown error".to_string(),
-                },
-            };
-
-            (StatusCode::UNAUTHORIZED, Json(error_response))
-        })?
-        .sub;
-
-    let user = sqlx::query_as!(
-        Users,
-        "SELECT * FROM users WHERE user_id = $1",
-        uuid::Uuid::parse_str(&user_id).unwrap()
-    )
-    .fetch_optional(&state.try_read().unwrap().db)
-    .await
-    .map_err(|e| {
-        let error_response = ErrorResponse {
-            status: "fail".to_string(),
-            message: format!("Database error: {}", e)
-        };
-
-        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-    })?
-    .ok_or_else(|| {
-        let error_response = ErrorResponse {
-            status: "fail".to_string(),
-            message: "User not found".to_string(),
-        };
-
-        (StatusCode::NOT_FOUND, Json(error_response))
-    })?;
-
+/// Converts a user record into the shape returned by the API, without credentials.
+pub(crate) fn filter_user(user: Users) -> FilteredUser {
     // Convert the preferred_platform from Option<String> to Option<Platform>
     let preferred_platform = user.preferred_platform.map(|s| Platform::from(s));
 
-    let filtered_user = FilteredUser {
+    FilteredUser {
         user_id: user.user_id,
         username: user.username,
         email: user.email,
         name: user.name,
         photo: user.photo.unwrap_or_else(|| "".to_string()),
         preferred_platform: preferred_platform.unwrap_or(Platform::Spotify),
+        two_factor_enabled: user.totp_enabled,
+        pending_email: user.pending_email,
+        show_top_artists: user.show_top_artists,
+        privacy: PrivacySettings {
+            history_visibility: Visibility::from(user.history_visibility),
+            profile_visibility: Visibility::from(user.profile_visibility),
+            allow_social_recommendations: user.allow_social_recommendations,
+        },
         createdAt: user.created_at.unwrap(),
         updatedAt: user.updated_at.unwrap()
-    };
+    }
+}
+
+/// Maps a clash on the case-insensitive username index to a conflict, anything else to a database error.
+pub(crate) fn username_taken_or_database_error(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db_error) if db_error.constraint() == Some("users_username_lower_key") => {
+            AppError::Conflict("Username is already taken".to_string())
+        }
+        e => AppError::Database(e),
+    }
+}
+
+/// Checks the password the user typed to confirm a sensitive change.
+async fn check_password(user: &Users, password: &str) -> Result<(), AppError> {
+    let is_valid = verify(password, &user.password).await?;
 
+    if !is_valid {
+        return Err(AppError::InvalidCredentials("Current password is incorrect".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Returns the user the request was authenticated as, whether by session cookie, bearer JWT or API token.
+#[utoipa::path(
+    get,
+    path = "/api/v1/user/info",
+    tag = "user",
+    responses(
+        (status = 200, description = "The logged in user", body = UserResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn get_user_handler(
+    Extension(user): Extension<Users>,
+) -> Result<impl IntoResponse, AppError> {
     let response = json!(UserResponse {
         status: "success".to_string(),
         message: "User found".to_string(),
         data: UserData {
-            user: filtered_user
+            user: filter_user(user)
         }
     });
 
     Ok(Json(response))
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/user/profile",
+    tag = "user",
+    request_body = UpdateProfileSchema,
+    responses(
+        (status = 200, description = "The updated user", body = UserResponse),
+        (status = 400, description = "Invalid request or failed validation", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "Username already taken", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn update_profile_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<UpdateProfileSchema>
+) -> Result<impl IntoResponse, AppError> {
+    let user = sqlx::query_as!(
+        Users,
+        r#"
+        UPDATE users SET
+            name = COALESCE($1, name),
+            username = COALESCE($2, username),
+            photo = COALESCE($3, photo),
+            preferred_platform = COALESCE($4, preferred_platform),
+            show_top_artists = COALESCE($5, show_top_artists),
+            updated_at = NOW()
+        WHERE user_id = $6
+        RETURNING *
+        "#,
+        payload.name.as_ref().map(|name| name.trim()),
+        payload.username,
+        payload.photo,
+        payload.preferred_platform.as_ref().map(Platform::as_str),
+        payload.show_top_artists,
+        user.user_id
+    )
+    .fetch_one(&state.try_read().unwrap().db)
+    .await
+    .map_err(username_taken_or_database_error)?;
+
+    let response = json!(UserResponse {
+        status: "success".to_string(),
+        message: "Profile updated".to_string(),
+        data: UserData {
+            user: filter_user(user)
+        }
+    });
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/user/privacy",
+    tag = "user",
+    request_body = UpdatePrivacySchema,
+    responses(
+        (status = 200, description = "The updated user", body = UserResponse),
+        (status = 400, description = "Invalid request or failed validation", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn update_privacy_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<UpdatePrivacySchema>
+) -> Result<impl IntoResponse, AppError> {
+    let user = sqlx::query_as!(
+        Users,
+        r#"
+        UPDATE users SET
+            history_visibility = COALESCE($1, history_visibility),
+            profile_visibility = COALESCE($2, profile_visibility),
+            allow_social_recommendations = COALESCE($3, allow_social_recommendations),
+            updated_at = NOW()
+        WHERE user_id = $4
+        RETURNING *
+        "#,
+        payload.history_visibility.map(|visibility| visibility.as_str()),
+        payload.profile_visibility.map(|visibility| visibility.as_str()),
+        payload.allow_social_recommendations,
+        user.user_id
+    )
+    .fetch_one(&state.read().await.db)
+    .await?;
+
+    let response = json!(UserResponse {
+        status: "success".to_string(),
+        message: "Privacy settings updated".to_string(),
+        data: UserData {
+            user: filter_user(user)
+        }
+    });
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/password",
+    tag = "user",
+    request_body = ChangePasswordSchema,
+    responses(
+        (status = 200, description = "Password changed", body = MessageResponse),
+        (status = 400, description = "Wrong current password or failed validation", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn change_password_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<ChangePasswordSchema>
+) -> Result<impl IntoResponse, AppError> {
+    check_password(&user, &payload.current_password).await?;
+
+    let (db, password_config) = {
+        let state = state.read().await;
+        (state.db.clone(), state.env.password.clone())
+    };
+
+    let hashed_password = hash(&payload.password, &password_config).await?;
+
+    sqlx::query!(
+        "UPDATE users SET password = $1, updated_at = NOW() WHERE user_id = $2",
+        hashed_password,
+        user.user_id
+    )
+    .execute(&db)
+    .await?;
+
+    // A reset link requested before the change shouldn't be able to undo it
+    sqlx::query!(
+        "DELETE FROM user_tokens WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL",
+        user.user_id,
+        TokenPurpose::PasswordReset as TokenPurpose
+    )
+    .execute(&db)
+    .await?;
+
+    Ok(Json(MessageResponse::success("Password changed successfully")))
+}
+
+/// Starts an email change. The new address only replaces the current one once it's confirmed
+/// from the link sent to it, see `auth_handler::confirm_email_change_handler`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/user/email",
+    tag = "user",
+    request_body = ChangeEmailSchema,
+    responses(
+        (status = 200, description = "A confirmation link has been sent to the new address", body = MessageResponse),
+        (status = 400, description = "Wrong password or failed validation", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+        (status = 409, description = "Email already taken", body = ErrorResponse),
+        (status = 503, description = "The confirmation email couldn't be sent", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn change_email_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<ChangeEmailSchema>
+) -> Result<impl IntoResponse, AppError> {
+    check_password(&user, &payload.password).await?;
+
+    let email = payload.email.trim().to_ascii_lowercase();
+    if email == user.email {
+        return Err(AppError::BadRequest("That is already your email".to_string()));
+    }
+
+    let (db, mailer, client_url) = {
+        let state = state.read().await;
+        (state.db.clone(), state.mailer.clone(), state.env.client_url.clone())
+    };
+
+    let email_taken = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM users WHERE email = $1) AS "exists!""#,
+        &email
+    )
+    .fetch_one(&db)
+    .await?;
+
+    if email_taken {
+        return Err(AppError::Conflict("User with this email already exists".to_string()));
+    }
+
+    sqlx::query!(
+        "UPDATE users SET pending_email = $1, updated_at = NOW() WHERE user_id = $2",
+        &email,
+        user.user_id
+    )
+    .execute(&db)
+    .await?;
+
+    let ttl = TimeDelta::try_hours(EMAIL_CHANGE_TOKEN_TTL_HOURS).expect("Token lifetime out of range");
+    let token = issue_token(&db, user.user_id, TokenPurpose::EmailChange, ttl).await?;
+
+    let link = format!("{}/confirm-email-change?token={}", client_url, token);
+    if let Err(e) = mailer.send(Email::email_change(&email, &user.name, &link)).await {
+        error!("Failed to send email change confirmation to {}: {}", user.user_id, e);
+        return Err(AppError::ServiceUnavailable("Failed to send the confirmation email, please try again".to_string()));
+    }
+
+    Ok(Json(MessageResponse::success("Check your new email to confirm the change")))
+}
+
+/// Deletes the account along with everything that references it.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/user",
+    tag = "user",
+    request_body = DeleteAccountSchema,
+    responses(
+        (status = 200, description = "Account deleted and the session cookies cleared", body = MessageResponse),
+        (status = 400, description = "Wrong password or code", body = ErrorResponse),
+        (status = 401, description = "Not logged in", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("bearer" = []))
+)]
+pub async fn delete_account_handler(
+    Extension(user): Extension<Users>,
+    Extension(state): Extension<Arc<RwLock<AppState>>>,
+    ValidatedJson(payload): ValidatedJson<DeleteAccountSchema>
+) -> Result<impl IntoResponse, AppError> {
+    check_password(&user, &payload.password).await?;
+
+    let (db, storage, cookies) = {
+        let state = state.read().await;
+        (state.db.clone(), state.storage.clone(), state.env.cookies.clone())
+    };
+
+    if user.totp_enabled {
+        let code = payload.code.as_deref().unwrap_or_default();
+        if !check_second_factor(&db, &user, code).await? {
+            return Err(AppError::InvalidCode);
+        }
+    }
+
+    // Tokens, preferences, recommendations and everything else owned by the user cascade
+    sqlx::query!("DELETE FROM users WHERE user_id = $1", user.user_id)
+        .execute(&db)
+        .await?;
+
+    for size in AVATAR_SIZES {
+        if let Err(e) = storage.delete(&avatar_key(user.user_id, size)).await {
+            error!("Failed to delete avatar of deleted user {}: {}", user.user_id, e);
+        }
+    }
+
+    let mut headers = HeaderMap::new();
+    for name in ["access_token", "refresh_token", "logged_in"] {
+        headers.append(header::SET_COOKIE, removal_cookie(&cookies, name).to_string().parse().unwrap());
+    }
+
+    Ok((headers, Json(MessageResponse::success("Account deleted"))))
+}
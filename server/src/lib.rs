@@ -0,0 +1,114 @@
+//! The Rusty Melody API. `main.rs` sets up the connections and serves `app`,
+//! the integration tests in `tests/` build the same app against a temporary database.
+
+mod handlers;
+mod model;
+mod routes;
+pub mod config;
+mod error;
+pub mod utils;
+mod middleware;
+pub mod mailer;
+mod rate_limit;
+pub mod storage;
+mod openapi;
+pub mod catalog;
+pub mod audio;
+pub mod recommend;
+
+use std::{path::Path, sync::Arc};
+use tokio::sync::RwLock;
+use config::Config;
+use mailer::Mailer;
+use rate_limit::RateLimiter;
+use storage::BlobStore;
+use utils::keys::JwtKeys;
+
+use axum::{
+    http::{
+        header::{ ACCEPT, AUTHORIZATION, CONTENT_TYPE },
+        HeaderValue,
+        Method,
+        Request
+    }, body::Body, Extension, Router
+};
+use tower_http::{
+    compression::{predicate::{NotForContentType, Predicate}, CompressionLayer, DefaultPredicate},
+    cors::CorsLayer,
+    services::ServeDir,
+    trace::TraceLayer
+};
+use tracing::{info, Span};
+
+use sqlx::{Pool, Postgres};
+
+#[derive(Debug)]
+pub struct AppState {
+    db: Pool<Postgres>,
+    env: Config,
+    mailer: Arc<dyn Mailer>,
+    rate_limiter: RateLimiter,
+    keys: JwtKeys,
+    storage: Arc<dyn BlobStore>,
+}
+
+impl AppState {
+    /// The rate limiter is built from `env`, using `db` when limits are kept in Postgres.
+    pub fn new(
+        db: Pool<Postgres>,
+        env: Config,
+        mailer: Arc<dyn Mailer>,
+        keys: JwtKeys,
+        storage: Arc<dyn BlobStore>,
+    ) -> Self {
+        let rate_limiter = RateLimiter::from_config(&env.rate_limit, &db);
+
+        Self { db, env, mailer, rate_limiter, keys, storage }
+    }
+}
+
+/// Builds the application: every route with its middleware, ready to be served.
+pub fn app(state: AppState) -> Router {
+    let cors = CorsLayer::new()
+        // Origins are checked to be valid header values when the configuration is loaded
+        .allow_origin(state.env.server.cors_origins.iter().map(|origin| origin.parse::<HeaderValue>().unwrap()).collect::<Vec<_>>())
+        .allow_credentials(true)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+        .allow_headers([ACCEPT, AUTHORIZATION, CONTENT_TYPE]);
+
+    // Each router declares the authentication its routes need
+    let mut app = routes::api_routes::api_routes();
+
+    // Public files in the local store are served by us, S3 serves its own. Audio is only streamed
+    // to logged in users, so the rest of the store isn't served.
+    if state.env.storage.backend == "local" {
+        let public_dir = storage::PUBLIC_PREFIX.trim_end_matches('/');
+        app = app.nest_service(
+            &format!("/uploads/{}", public_dir),
+            ServeDir::new(Path::new(&state.env.storage.dir).join(public_dir)),
+        );
+    }
+
+    app
+        .layer(cors)
+        // Fetched by other services rather than the client, so it has its own CORS policy
+        .merge(routes::well_known_routes::well_known_routes())
+        // middleware
+        .layer(
+            TraceLayer::new_for_http().on_request(|req: &Request<Body>, _span: &Span| {
+                let addr = req
+                    .headers()
+                    .get("X-Real-IP")
+                    .and_then(|ip| ip.to_str().ok());
+                info!(
+                    "[{}] {} {}",
+                    addr.unwrap_or("unknown"),
+                    req.method(),
+                    req.uri()
+                );
+            }),
+        )
+        // Audio is compressed already, and compressing it would break `Range` requests
+        .layer(CompressionLayer::new().compress_when(DefaultPredicate::new().and(NotForContentType::const_new("audio/"))))
+        .layer(Extension(Arc::new(RwLock::new(state))))
+}
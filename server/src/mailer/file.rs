@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use axum::async_trait;
+use chrono::Utc;
+use tracing::info;
+
+use crate::config::MailConfig;
+use super::{Email, Mailer, MailerError};
+
+/// Writes every email to `MAIL_DIR` as an `.eml` file instead of sending it.
+/// Used in development and tests so links can be picked up from disk.
+#[derive(Debug, Clone)]
+pub struct FileMailer {
+    from: String,
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(config: &MailConfig) -> Self {
+        Self {
+            from: config.from.clone(),
+            dir: PathBuf::from(&config.dir),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| MailerError::Transport(e.to_string()))?;
+
+        let now = Utc::now();
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            now.format("%Y%m%d%H%M%S%3f"),
+            uuid::Uuid::new_v4()
+        ));
+
+        let contents = format!(
+            "From: {}\nTo: {}\nDate: {}\nSubject: {}\n\n{}",
+            self.from,
+            email.to,
+            now.to_rfc2822(),
+            email.subject,
+            email.body
+        );
+
+        tokio::fs::write(&path, contents)
+            .await
+            .map_err(|e| MailerError::Transport(e.to_string()))?;
+
+        info!("📧 Email to {} written to {}", email.to, path.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+pub mod file;
+pub mod smtp;
+
+use std::{fmt, sync::Arc};
+
+use axum::async_trait;
+
+use crate::config::MailConfig;
+
+pub use file::FileMailer;
+pub use smtp::SmtpMailer;
+
+/// A plain-text email ready to be handed to a `Mailer`.
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Email {
+    /// Builds the email sent after registration, linking to the verification page.
+    pub fn verification(to: &str, name: &str, link: &str) -> Self {
+        Self {
+            to: to.to_string(),
+            subject: "Verify your Rusty Melody account".to_string(),
+            body: format!(
+                "Hi {},\n\nPlease confirm your email address by opening the link below:\n\n{}\n\nThe link expires in 24 hours. If you did not create an account you can ignore this email.\n",
+                name, link
+            ),
+        }
+    }
+
+    /// Builds the email sent when a user asks to reset their password.
+    pub fn password_reset(to: &str, name: &str, link: &str) -> Self {
+        Self {
+            to: to.to_string(),
+            subject: "Reset your Rusty Melody password".to_string(),
+            body: format!(
+                "Hi {},\n\nSomeone requested a password reset for your account. Open the link below to choose a new password:\n\n{}\n\nThe link expires in 1 hour. If you did not request a reset you can ignore this email.\n",
+                name, link
+            ),
+        }
+    }
+
+    /// Builds the email sent to a new address when a user changes their email.
+    pub fn email_change(to: &str, name: &str, link: &str) -> Self {
+        Self {
+            to: to.to_string(),
+            subject: "Confirm your new Rusty Melody email".to_string(),
+            body: format!(
+                "Hi {},\n\nPlease confirm this is your new email address by opening the link below:\n\n{}\n\nThe link expires in 24 hours. Until then you can keep logging in with your current email.\n",
+                name, link
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MailerError {
+    /// The message could not be built (bad address, invalid header, ...)
+    Message(String),
+    /// The transport failed to deliver the message
+    Transport(String),
+}
+
+impl fmt::Display for MailerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailerError::Message(e) => write!(f, "Invalid email: {}", e),
+            MailerError::Transport(e) => write!(f, "Failed to send email: {}", e),
+        }
+    }
+}
+
+/// Anything that can deliver an `Email`.
+#[async_trait]
+pub trait Mailer: Send + Sync + fmt::Debug {
+    async fn send(&self, email: Email) -> Result<(), MailerError>;
+}
+
+/// Builds the mailer selected by `MAIL_TRANSPORT`, falling back to the file mailer.
+pub fn from_config(config: &MailConfig) -> Arc<dyn Mailer> {
+    match config.transport.as_str() {
+        "smtp" => Arc::new(SmtpMailer::new(config)),
+        _ => Arc::new(FileMailer::new(config)),
+    }
+}
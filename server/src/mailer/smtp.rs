@@ -0,0 +1,58 @@
+use axum::async_trait;
+use lettre::{
+    message::header::ContentType,
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::config::MailConfig;
+use super::{Email, Mailer, MailerError};
+
+/// Sends email through an SMTP relay using STARTTLS.
+#[derive(Debug, Clone)]
+pub struct SmtpMailer {
+    from: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &MailConfig) -> Self {
+        let host = config.smtp_host.as_deref().expect("SMTP_HOST must be set when MAIL_TRANSPORT=smtp");
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .expect("SMTP_HOST must be a valid hostname");
+
+        if let Some(port) = config.smtp_port {
+            builder = builder.port(port);
+        }
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.to_owned(), password.to_owned()));
+        }
+
+        Self {
+            from: config.from.clone(),
+            transport: builder.build(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        let message = Message::builder()
+            .from(self.from.parse().map_err(|e| MailerError::Message(format!("{}", e)))?)
+            .to(email.to.parse().map_err(|e| MailerError::Message(format!("{}", e)))?)
+            .subject(email.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body)
+            .map_err(|e| MailerError::Message(e.to_string()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| MailerError::Transport(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -1,49 +1,86 @@
-mod handlers;
-mod model;
-mod routes;
-mod config;
-mod utils;
-mod middleware;
-
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use config::Config;
-
-use axum::{
-    http::{
-        header::{ ACCEPT, AUTHORIZATION, CONTENT_TYPE },
-        HeaderValue, 
-        Method,
-        Request
-    }, body::Body, middleware::from_fn, Extension, Router
-};
+use std::{net::SocketAddr, path::PathBuf, process::ExitCode, sync::Arc};
+
+use clap::{Args, Parser, Subcommand};
 use dotenv::{dotenv, from_filename};
-use tower_http::{
-    compression::CompressionLayer, cors::{Any, CorsLayer}, trace::TraceLayer
-};
-use crate::middleware::auth;
-use tracing::{info, Span};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
 
-#[derive(Debug)]
-pub struct AppState {
-    db: Pool<Postgres>,
-    env: Config,
+use server::{
+    app,
+    audio,
+    catalog::{self, dataset::Format},
+    config::Config,
+    mailer,
+    storage::{self, BlobStore},
+    utils::keys::JwtKeys,
+    AppState
+};
+
+#[derive(Parser)]
+#[command(about = "The Rusty Melody API server")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve the API, the default
+    Serve,
+    /// Bulk-load songs, artists and albums from a CSV or JSON dataset
+    Import(ImportArgs),
+    /// Store a WAV, FLAC or MP3 file as a song's audio and fill in its features from it
+    Analyze(AnalyzeArgs),
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    /// The dataset to import
+    path: PathBuf,
+    /// Defaults to the file extension
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// Report what would be imported without writing anything
+    #[arg(long)]
+    dry_run: bool,
+    /// Exit successfully even if some rows were rejected
+    #[arg(long)]
+    allow_rejects: bool,
 }
 
+#[derive(Args)]
+struct AnalyzeArgs {
+    /// Id of the song the audio belongs to
+    song_id: uuid::Uuid,
+    /// The audio file
+    path: PathBuf,
+}
+
+/// Exits with a failure status whenever the command can't do its work, so scripts and process
+/// managers don't mistake it for a clean shutdown.
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
     if cfg!(debug_assertions) {
         // Load from `.env.local` in development
-        from_filename(".env.local").ok().expect("Failed to load .env.local file");
+        from_filename(".env.local").ok();
     } else {
         // Load from `.env` in production
-        dotenv().ok().expect("Failed to load .env file");
+        dotenv().ok();
     }
 
-    let config = Config::init();
+    let config = match Config::load() {
+        Ok(config) => {
+            println!("✅Configuration loaded");
+            config
+        }
+        Err(err) => {
+            println!("❌Invalid configuration:\n{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
 
     tracing_subscriber::registry()
         .with(
@@ -53,9 +90,20 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    let Some(pool) = connect(&config).await else { return ExitCode::FAILURE };
+
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(config, pool).await,
+        Command::Import(args) => import(pool, args).await,
+        Command::Analyze(args) => analyze(config, pool, args).await,
+    }
+}
+
+/// Connects to the database and brings its schema up to date.
+async fn connect(config: &Config) -> Option<Pool<Postgres>> {
     let pool = match PgPoolOptions::new()
-        .max_connections(10)
-        .connect(&config.database_url)
+        .max_connections(config.database.max_connections)
+        .connect(&config.database.url)
         .await
     {
         Ok(pool) => {
@@ -64,63 +112,138 @@ async fn main() {
         }
         Err(err) => {
             println!("❌Failed to connect to the database: {}", err);
-            return;
+            return None;
         }
     };
 
-    let cors = CorsLayer::new()
-        .allow_origin(std::env::var("CLIENT_URL").unwrap_or_else(|_| "http://localhost:3000".to_string()).parse::<HeaderValue>().unwrap())
-        .allow_credentials(true)
-        .allow_headers([ACCEPT, AUTHORIZATION, CONTENT_TYPE]);
-
-    let app_state = Arc::new(RwLock::new(AppState {
-        db: pool.clone(),
-        env: config.clone(),
-    }));
-
     // sqlx::migrate!("./migrations")
     //     .run(&pool)
     //     .await
     //     .unwrap_or_else(|err| panic!("Failed to run migrations: {}", err));
-    let mut migrations = match sqlx::migrate!("./migrations")
+    match sqlx::migrate!("./migrations")
         .run(&pool)
         .await
         {
-            Ok(migrations) => {
+            Ok(()) => {
                 println!("✅Migrations ran successfully");
-                migrations
+                Some(pool)
             },
             Err(err) => {
                 println!("❌Failed to run migrations: {}", err);
-                return;
+                None
             }
-        };
-
-    let app = Router::new()
-        .merge(routes::user_routes::user_routes())
-        .merge(routes::auth_routes::auth_routes())
-        // middleware
-        .layer(from_fn(auth))
-        .layer(cors)
-        .layer(
-            TraceLayer::new_for_http().on_request(|req: &Request<Body>, _span: &Span| {
-                let addr = req
-                    .headers()
-                    .get("X-Real-IP")
-                    .and_then(|ip| ip.to_str().ok());
-                info!(
-                    "[{}] {} {}",
-                    addr.unwrap_or("unknown"),
-                    req.method(),
-                    req.uri()
-                );
-            }),
-        )
-        .layer(CompressionLayer::new())
-        .layer(Extension(app_state));
+        }
+}
+
+fn open_storage(config: &Config) -> Option<Arc<dyn BlobStore>> {
+    match storage::from_config(&config.storage) {
+        Ok(storage) => {
+            println!("✅Using {} storage for uploads", config.storage.backend);
+            Some(storage)
+        }
+        Err(err) => {
+            println!("❌Failed to set up storage: {}", err);
+            None
+        }
+    }
+}
+
+async fn serve(config: Config, pool: Pool<Postgres>) -> ExitCode {
+    let keys = match JwtKeys::from_config(&config.jwt) {
+        Ok(keys) => {
+            println!("✅Loaded JWT signing keys, active key: {}", keys.active().kid);
+            keys
+        }
+        Err(err) => {
+            println!("❌Failed to load JWT signing keys: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(storage) = open_storage(&config) else { return ExitCode::FAILURE };
+
+    let app = app(AppState::new(pool, config.clone(), mailer::from_config(&config.mail), keys, storage));
 
     println!("🚀 Server started succesfully");
-    let port = std::env::var("SERVER_PORT").unwrap_or_else(|_| "8000".to_string());
-    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    let listener = match tokio::net::TcpListener::bind(format!("0.0.0.0:{}", config.server.port)).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            println!("❌Failed to listen on port {}: {}", config.server.port, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    // The rate limiter keys on the peer address unless `X-Real-IP` from a trusted proxy is used
+    match axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            println!("❌Server error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn import(pool: Pool<Postgres>, args: ImportArgs) -> ExitCode {
+    let Some(format) = args.format.or_else(|| Format::from_path(&args.path)) else {
+        println!("❌Can't tell the format of {}, pass --format", args.path.display());
+        return ExitCode::FAILURE;
+    };
+
+    let report = match catalog::import(&pool, &args.path, format, args.dry_run).await {
+        Ok(report) => report,
+        Err(err) => {
+            println!("❌Import failed: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for rejection in &report.rejected {
+        println!("Rejected row {}: {}", rejection.row, rejection.reason);
+    }
+    println!(
+        "{}{} songs, {} artists and {} albums, skipped {} duplicates and rejected {} rows",
+        if args.dry_run { "✅Would import " } else { "✅Imported " },
+        report.songs,
+        report.artists,
+        report.albums,
+        report.duplicates,
+        report.rejected.len()
+    );
+
+    if report.rejected.is_empty() || args.allow_rejects {
+        ExitCode::SUCCESS
+    } else {
+        println!("❌Some rows were rejected, pass --allow-rejects to accept that");
+        ExitCode::FAILURE
+    }
+}
+
+async fn analyze(config: Config, pool: Pool<Postgres>, args: AnalyzeArgs) -> ExitCode {
+    let Some(storage) = open_storage(&config) else { return ExitCode::FAILURE };
+
+    let bytes = match tokio::fs::read(&args.path).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            println!("❌Failed to read {}: {}", args.path.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match audio::ingest(&pool, &storage, args.song_id, bytes, None).await {
+        Ok(features) => {
+            println!(
+                "✅Analyzed {}: {} s, {} BPM, key {}, {:.1} dB, speechiness {:.2}",
+                args.path.display(),
+                features.duration,
+                features.tempo.map_or("unknown".to_string(), |tempo| format!("{:.1}", tempo)),
+                features.key.map_or("unknown".to_string(), |key| key.to_string()),
+                features.loudness,
+                features.speechiness
+            );
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            println!("❌Analysis failed: {}", err);
+            ExitCode::FAILURE
+        }
+    }
 }
\ No newline at end of file
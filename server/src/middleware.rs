@@ -1,30 +1,70 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
-    body::Body, extract::Request, http::{header, StatusCode}, middleware::Next, response::IntoResponse, Extension, Json
+    body::Body, extract::{ConnectInfo, Request, State}, http::{header, HeaderValue, Method}, middleware::Next, response::{IntoResponse, Response}, Extension
 };
 
 use axum_extra::extract::cookie::CookieJar;
-use common::schema::feedback::ErrorResponse;
-use jsonwebtoken::{decode, DecodingKey, Validation};
 use tokio::sync::RwLock;
-use regex::Regex;
 
-use crate::{model::Users, utils::jwt::AccessClaims, AppState};
+use crate::{
+    error::AppError,
+    model::{ApiTokenScope, Users},
+    rate_limit::{Decision, RouteGroup},
+    utils::{api_token::{authenticate_api_token, ApiTokenAuth, API_TOKEN_PREFIX}, jwt::{decode_token, AccessClaims}},
+    AppState
+};
+
+/// Who may call the routes of a router. Declared where each router is built, with
+/// `.route_layer(from_fn_with_state(Access::User, auth))`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Anyone, the caller isn't looked up
+    Public,
+    /// Anyone, but a logged in caller is looked up so the handler can show them more
+    Optional,
+    /// A logged in user, or a personal API token with the scope the method needs
+    User,
+    /// A logged in user. API tokens can't be used to manage tokens, two-factor authentication,
+    /// credentials, export the account's data or delete the account.
+    Session,
+}
 
 /// Axum JWT Authentication Middleware.
+/// Inserts the caller's `Users` into the request extensions, as required by the router's `Access`.
 pub async fn auth(
+    State(access): State<Access>,
     cookie_jar: CookieJar,
     Extension(app_state): Extension<Arc<RwLock<AppState>>>,
     mut req: Request<Body>,
     next: Next,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    let path = Regex::new(r"^/api/healthchecker$|^/api/auth/.*").unwrap();
-    if path.is_match(req.uri().path()) {
-        // If the request is for the healthchecker, we call the next middleware.
-        return Ok(next.run(req).await);
+) -> Result<impl IntoResponse, AppError> {
+    match access {
+        Access::Public => {}
+        Access::Optional => {
+            if let Ok(user) = authenticate(&cookie_jar, &app_state, &mut req).await {
+                req.extensions_mut().insert(user);
+            }
+        }
+        Access::User | Access::Session => {
+            let user = authenticate(&cookie_jar, &app_state, &mut req).await?;
+            if access == Access::Session && req.extensions().get::<ApiTokenAuth>().is_some() {
+                return Err(AppError::Forbidden("API tokens cannot be used to manage account security, please log in".to_string()));
+            }
+
+            req.extensions_mut().insert(user);
+        }
     }
 
+    Ok(next.run(req).await)
+}
+
+/// Finds the user a request was made by, from the access token cookie or the `Authorization` header.
+async fn authenticate(
+    cookie_jar: &CookieJar,
+    app_state: &Arc<RwLock<AppState>>,
+    req: &mut Request<Body>,
+) -> Result<Users, AppError> {
     let token = cookie_jar
         .get("access_token") // We try to get the token from the cookie
         .map(|cookie| cookie.value().to_string())
@@ -43,63 +83,120 @@ pub async fn auth(
         });
 
     // If the token is none, we return UNAUTHORIZED.
-    let token = token.ok_or_else(|| {
-        let json_error = ErrorResponse {
-            status: "fail".to_string(),
-            message: "You are not logged in, please provide token".to_string(),
-        };
-
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?;
-
-    let claims = decode::<AccessClaims>(
-        &token,
-        &DecodingKey::from_secret(app_state.clone().read().await.env.jwt_secret.as_ref()),
-        &Validation::default(),
-    )
-    .map_err(|_| {
+    let token = token.ok_or_else(|| AppError::Unauthenticated("You are not logged in, please provide token".to_string()))?;
+
+    let client = app_state.read().await.db.clone();
+
+    // Personal API tokens are looked up in the database, everything else is a JWT
+    let user_id = if token.starts_with(API_TOKEN_PREFIX) {
+        let (user_id, api_token) = authenticate_api_token(&client, &token)
+            .await?
+            .ok_or_else(|| AppError::Unauthenticated("Invalid or expired API token".to_string()))?;
+
+        check_api_token_access(req, &api_token)?;
+        req.extensions_mut().insert(api_token);
+
+        user_id
+    } else {
+        let claims = decode_token::<AccessClaims>(&app_state.read().await.keys, &token)
         // We return UNAUTHORIZED if the token fails validation for some reason.
-        let json_error = ErrorResponse {
-            status: "fail".to_string(),
-            message: "Invalid token".to_string(),
-        };
-
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?
-    .claims;
-
-    // We get the user ID from the token.
-    // We try to parse the ID, stored in the token as a String, as a Uuid.
-    let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| {
-        // If the id is incorrectly formed, we return an error.
-        let json_error = ErrorResponse {
-            status: "fail".to_string(),
-            message: "Invalid token".to_string(),
-        };
+        .map_err(|_| AppError::Unauthenticated("Invalid token".to_string()))?;
 
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?;
+        // We get the user ID from the token.
+        // We try to parse the ID, stored in the token as a String, as a Uuid.
+        // If the id is incorrectly formed, we return an error.
+        uuid::Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::Unauthenticated("Invalid token".to_string()))?
+    };
 
     // With a valid user_id we verify that the user still exists in the database.
-    let client = app_state.clone().read().await.db.clone();
-    let user_response = sqlx::query_as::<_, Users>(
+    sqlx::query_as::<_, Users>(
         "SELECT * FROM users WHERE user_id = $1",
     )
-    .bind(&user_id)
-    .fetch_one(&client)
-    .await
-    .map_err(|err| {
-        let json_error = ErrorResponse {
-            status: "fail".to_string(),
-            message: format!("Error querying the database: {}", err),
-        };
-
-        (StatusCode::UNAUTHORIZED, Json(json_error))
-    })?;
-
-    // We insert the user_response into the request extensions.
-    req.extensions_mut().insert(user_response);
-
-    // If everything is successful, we call the next middleware.
-    Ok(next.run(req).await)
-}
\ No newline at end of file
+    .bind(user_id)
+    .fetch_optional(&client)
+    .await?
+    .ok_or_else(|| AppError::Unauthenticated("The user belonging to this token no longer exists".to_string()))
+}
+
+/// Checks that a request made with a personal API token is within the token's scopes.
+fn check_api_token_access(req: &Request<Body>, api_token: &ApiTokenAuth) -> Result<(), AppError> {
+    let (required_scope, scope_name) = match *req.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => (ApiTokenScope::Read, "READ"),
+        _ => (ApiTokenScope::Write, "WRITE"),
+    };
+
+    if !api_token.scopes.contains(&required_scope) {
+        return Err(AppError::Forbidden(format!("This API token is missing the {} scope", scope_name)));
+    }
+
+    Ok(())
+}
+
+/// The client's IP: the address of the connection, or the `X-Real-IP` header set by the reverse
+/// proxy when `trust_proxy` is on.
+pub fn client_ip(req: &Request<Body>, trust_proxy: bool) -> String {
+    trust_proxy
+        .then(|| req.headers().get("X-Real-IP"))
+        .flatten()
+        .and_then(|ip| ip.to_str().ok())
+        .map(|ip| ip.to_string())
+        .or_else(|| {
+            req.extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Axum Rate Limiting Middleware.
+/// Applied per router with the `RouteGroup` whose limit should be used, keyed by client IP.
+pub async fn rate_limit(
+    State(group): State<RouteGroup>,
+    Extension(app_state): Extension<Arc<RwLock<AppState>>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (rate_limiter, trust_proxy) = {
+        let state = app_state.read().await;
+        (state.rate_limiter.clone(), state.env.server.trust_proxy)
+    };
+    let ip = client_ip(&req, trust_proxy);
+
+    match rate_limiter.check_ip(group, &ip).await {
+        Ok(Decision::Allowed) => next.run(req).await,
+        Ok(Decision::Limited { retry_after }) => AppError::TooManyRequests {
+            message: format!("Too many requests, please try again in {} seconds", retry_after),
+            retry_after,
+        }
+        .into_response(),
+        Err(err) => AppError::Internal(format!("Error checking rate limit: {}", err)).into_response(),
+    }
+}
+
+/// Axum Deprecation Middleware.
+/// Applied to the routers of a deprecated API version, with the prefix of the version replacing it.
+/// Marks every response with a `Deprecation` header, a `Link` to the same route in the new version
+/// and, once a removal date is configured, a `Sunset` header.
+pub async fn deprecated(
+    State(successor): State<&'static str>,
+    Extension(app_state): Extension<Arc<RwLock<AppState>>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    // Nested routers see the path without their prefix, which is the same in every version
+    let successor_link = format!("<{}{}>; rel=\"successor-version\"", successor, req.uri().path());
+    let sunset = app_state.read().await.env.legacy_api_sunset.clone();
+
+    let mut response = next.run(req).await;
+    let headers = response.headers_mut();
+    headers.insert("Deprecation", HeaderValue::from_static("true"));
+    if let Ok(link) = HeaderValue::from_str(&successor_link) {
+        headers.append(header::LINK, link);
+    }
+    if let Some(sunset) = sunset.and_then(|sunset| HeaderValue::from_str(&sunset).ok()) {
+        headers.insert("Sunset", sunset);
+    }
+
+    response
+}
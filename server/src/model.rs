@@ -1,5 +1,5 @@
 use chrono::prelude::*;
-use common::schema::select::SelectItem;
+use common::schema::{select::SelectItem, song::SongSummary};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use strum::IntoEnumIterator; 
@@ -91,12 +91,66 @@ pub struct Users {
     pub password: String,
     pub preferred_platform: Option<String>,
     pub photo: Option<String>,
+    pub verified: bool,
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    #[serde(skip_serializing)]
+    pub totp_last_step: Option<i64>,
+    /// New email address awaiting confirmation
+    pub pending_email: Option<String>,
+    /// Whether top artists are shown on the public profile
+    pub show_top_artists: bool,
+    /// Who can see likes and plays, one of `PUBLIC`, `FOLLOWERS` or `PRIVATE`
+    pub history_visibility: String,
+    /// Who can see the public profile, one of `PUBLIC`, `FOLLOWERS` or `PRIVATE`
+    pub profile_visibility: String,
+    /// Whether recommendations may use what followed users listen to
+    pub allow_social_recommendations: bool,
+    /// Whether the user can upload audio for any song, not just those they added
+    pub is_admin: bool,
     #[serde(rename = "createdAt")]
     pub created_at: Option<DateTime<Utc>>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, sqlx::Type)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "token_purpose", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TokenPurpose {
+    EmailVerification,
+    PasswordReset,
+    EmailChange,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, sqlx::Type)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(type_name = "api_token_scope", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApiTokenScope {
+    Read,
+    Write,
+}
+
+impl sqlx::postgres::PgHasArrayType for ApiTokenScope {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_api_token_scope")
+    }
+}
+
+/// A row of `api_tokens` without `token_hash`, which is only ever compared in SQL
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct ApiTokens {
+    pub token_id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
 pub struct Platforms {
     pub platform_id: uuid::Uuid,
@@ -121,6 +175,36 @@ pub struct Songs {
     external_url: Vec<String>,
 }
 
+/// The columns of a `SongSummary`, as selected from `songs` joined with its artist and album
+#[derive(Debug, sqlx::FromRow, Clone)]
+pub struct SongSummaryRow {
+    pub song_id: uuid::Uuid,
+    pub title: String,
+    pub artist_id: uuid::Uuid,
+    pub artist: String,
+    pub album_id: uuid::Uuid,
+    pub album: String,
+    pub cover: Option<String>,
+    pub duration: i16,
+    pub has_audio: bool,
+}
+
+impl From<SongSummaryRow> for SongSummary {
+    fn from(row: SongSummaryRow) -> Self {
+        SongSummary {
+            song_id: row.song_id,
+            title: row.title,
+            artist_id: row.artist_id,
+            artist: row.artist,
+            album_id: row.album_id,
+            album: row.album,
+            cover: row.cover,
+            duration: row.duration.max(0) as u16,
+            has_audio: row.has_audio,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
 pub struct Artists {
     artist_id: uuid::Uuid,
@@ -135,7 +219,7 @@ pub struct Albums {
     album_id: uuid::Uuid,
     title: String,
     artist_id: uuid::Uuid,
-    release_date: DateTime<Utc>,
+    release_date: Option<DateTime<Utc>>,
     genre: Genre,
     cover: String, // URL to album cover
     tracks: Vec<uuid::Uuid>, // List of song ids
@@ -0,0 +1,248 @@
+use utoipa::{
+    openapi::{
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+        Components
+    },
+    Modify, OpenApi
+};
+
+use crate::handlers::{
+    album_handler, api_token_handler, artist_handler, auth_handler, avatar_handler, export_handler, profile_handler,
+    recommendation_handler, search_handler, social_handler, song_handler, two_factor_handler, user_handler
+};
+use common::schema::{
+    album::{AlbumDetails, AlbumDetailsResponse, AlbumSummary},
+    api_token::{ApiToken, ApiTokenListResponse, ApiTokenScope, CreateApiTokenResponse, CreateApiTokenSchema},
+    artist::{ArtistDetails, ArtistDetailsResponse, ArtistSummary},
+    error::{ErrorCode, ErrorResponse},
+    message::MessageResponse,
+    platform::Platform,
+    privacy::{PrivacySettings, UpdatePrivacySchema},
+    profile::{PublicPlaylist, PublicProfile, PublicProfileResponse, RecentPlay, TopArtist},
+    recommendation::{
+        Recommendation, RecommendationFeedback, RecommendationFeedbackSchema, RecommendationListResponse,
+        RecommendationReason
+    },
+    search::{PlaylistSummary, SearchResponse, SearchResults},
+    social::{
+        FeedItem, FeedItemKind, FeedPlaylist, FeedResponse, FeedSong, FollowListResponse, FollowStatusResponse,
+        RecordPlaySchema, UserSummary, Visibility
+    },
+    song::{
+        AudioFeatures, AudioFeaturesResponse, PlatformLink, SongDetails, SongDetailsResponse, SongFeatures, SongSummary
+    },
+    two_factor::{
+        DisableTwoFactorSchema, RecoveryCodesResponse, TwoFactorChallengeResponse, TwoFactorCodeSchema,
+        TwoFactorEnrollResponse, TwoFactorVerifySchema
+    },
+    user::{
+        ChangeEmailSchema, ChangePasswordSchema, DeleteAccountSchema, EmailRequestSchema, FilteredUser, LoginResponse,
+        LoginUserSchema, ResetPasswordSchema, SignupUserSchema, UpdateProfileSchema, UserData, UserLoginResponse,
+        UserResponse, VerifyEmailSchema
+    }
+};
+
+/// The OpenAPI document of the API, served at `/api/v1/openapi.json`.
+/// Every route needs its handler listed in `paths`, the test below fails otherwise.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Rusty Melody API",
+        description = "Errors are returned as an `ErrorResponse`, branch on its `code` rather than the message.\n\n\
+            The unversioned `/api/...` paths are deprecated aliases of `/api/v1/...`."
+    ),
+    paths(
+        user_handler::health_check_handler,
+        auth_handler::register_user_handler,
+        auth_handler::login_user_handler,
+        auth_handler::logout_handler,
+        auth_handler::refresh_token_handler,
+        auth_handler::verify_email_handler,
+        auth_handler::request_verification_handler,
+        auth_handler::confirm_email_change_handler,
+        auth_handler::forgot_password_handler,
+        auth_handler::reset_password_handler,
+        auth_handler::jwks_handler,
+        two_factor_handler::verify_two_factor_handler,
+        two_factor_handler::enroll_two_factor_handler,
+        two_factor_handler::enable_two_factor_handler,
+        two_factor_handler::disable_two_factor_handler,
+        two_factor_handler::regenerate_recovery_codes_handler,
+        user_handler::get_user_handler,
+        user_handler::get_user_preferences_handler,
+        user_handler::update_user_preferences_handler,
+        user_handler::update_profile_handler,
+        user_handler::update_privacy_handler,
+        user_handler::change_password_handler,
+        user_handler::change_email_handler,
+        user_handler::delete_account_handler,
+        avatar_handler::upload_avatar_handler,
+        export_handler::export_user_data_handler,
+        profile_handler::get_public_profile_handler,
+        social_handler::get_follow_status_handler,
+        social_handler::follow_user_handler,
+        social_handler::unfollow_user_handler,
+        social_handler::list_followers_handler,
+        social_handler::list_following_handler,
+        social_handler::get_feed_handler,
+        social_handler::like_song_handler,
+        social_handler::unlike_song_handler,
+        social_handler::record_play_handler,
+        recommendation_handler::get_recommendations_handler,
+        recommendation_handler::put_recommendation_feedback_handler,
+        recommendation_handler::delete_recommendation_feedback_handler,
+        api_token_handler::list_api_tokens_handler,
+        api_token_handler::create_api_token_handler,
+        api_token_handler::revoke_api_token_handler,
+        song_handler::get_song_handler,
+        song_handler::upload_song_audio_handler,
+        song_handler::stream_song_handler,
+        song_handler::stream_preview_handler,
+        artist_handler::get_artist_handler,
+        album_handler::get_album_handler,
+        search_handler::search_handler,
+        search_handler::search_suggestions_handler,
+    ),
+    components(schemas(
+        ErrorCode, ErrorResponse, MessageResponse,
+        SignupUserSchema, LoginUserSchema, LoginResponse, UserLoginResponse, EmailRequestSchema, VerifyEmailSchema,
+        ResetPasswordSchema, UpdateProfileSchema, ChangePasswordSchema, ChangeEmailSchema, DeleteAccountSchema,
+        FilteredUser, UserData, UserResponse, Platform, PrivacySettings, UpdatePrivacySchema, Visibility,
+        TwoFactorChallengeResponse, TwoFactorVerifySchema, TwoFactorCodeSchema, DisableTwoFactorSchema,
+        TwoFactorEnrollResponse, RecoveryCodesResponse,
+        avatar_handler::AvatarUpload,
+        PublicProfileResponse, PublicProfile, TopArtist, PublicPlaylist, RecentPlay,
+        UserSummary, FollowStatusResponse, FollowListResponse, FeedResponse, FeedItem, FeedItemKind, FeedSong,
+        FeedPlaylist, RecordPlaySchema,
+        Recommendation, RecommendationReason, RecommendationFeedback, RecommendationFeedbackSchema,
+        RecommendationListResponse,
+        ApiTokenScope, ApiToken, ApiTokenListResponse, CreateApiTokenSchema, CreateApiTokenResponse,
+        song_handler::AudioUpload, AudioFeatures, AudioFeaturesResponse,
+        SongSummary, SongFeatures, PlatformLink, SongDetails, SongDetailsResponse, ArtistSummary, ArtistDetails,
+        ArtistDetailsResponse, AlbumSummary, AlbumDetails, AlbumDetailsResponse,
+        SearchResponse, SearchResults, PlaylistSummary,
+    )),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "auth", description = "Registration, login and account recovery"),
+        (name = "two-factor", description = "Managing two-factor authentication"),
+        (name = "user", description = "The logged in user's account"),
+        (name = "profiles", description = "Public profiles"),
+        (name = "social", description = "Follows, likes, listening history and the activity feed"),
+        (name = "recommendations", description = "Songs recommended to the logged in user, and their feedback on them"),
+        (name = "api-tokens", description = "Personal API tokens"),
+        (name = "songs", description = "The song catalog: songs, artists and albums"),
+        (name = "search", description = "Searching the catalog and public playlists"),
+        (name = "well-known", description = "Discovery documents for other services"),
+        (name = "health"),
+    )
+)]
+pub struct ApiDoc;
+
+/// The two ways `middleware::auth` accepts a login.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Components::new);
+
+        components.add_security_scheme(
+            "session_cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::with_description(
+                "access_token",
+                "Set by logging in"
+            )))
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("An access token, or a personal API token starting with `rm_pat_`"))
+                    .build()
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use regex::Regex;
+    use utoipa::OpenApi;
+
+    use super::ApiDoc;
+    use crate::routes::api_routes::API_V1;
+
+    /// Every `.route(...)` in the route modules, as `METHOD /path` with parameters written the OpenAPI way.
+    /// Paths are prefixed with where `api_routes` nests the module.
+    fn routed() -> BTreeSet<String> {
+        let sources = [
+            (API_V1, include_str!("routes/auth_routes.rs")),
+            (API_V1, include_str!("routes/user_routes.rs")),
+            (API_V1, include_str!("routes/catalog_routes.rs")),
+            ("", include_str!("routes/well_known_routes.rs")),
+        ];
+
+        let route = Regex::new(r#"(?m)\.route\("([^"]+)",\s*(.*)\)$"#).unwrap();
+        let method = Regex::new(r"(?:^|\.)(get|post|put|patch|delete)\(").unwrap();
+        let param = Regex::new(r":(\w+)").unwrap();
+
+        let mut routes = BTreeSet::new();
+        for (prefix, source) in sources {
+            for captures in route.captures_iter(source) {
+                let path = format!("{}{}", prefix, param.replace_all(&captures[1], "{$1}"));
+                for handler in method.captures_iter(&captures[2]) {
+                    routes.insert(format!("{} {}", handler[1].to_uppercase(), path));
+                }
+            }
+        }
+
+        routes
+    }
+
+    fn documented() -> BTreeSet<String> {
+        ApiDoc::openapi()
+            .paths
+            .paths
+            .iter()
+            .flat_map(|(path, item)| {
+                item.operations
+                    .keys()
+                    .map(move |method| format!("{} {}", serde_json::to_value(method).unwrap().as_str().unwrap().to_uppercase(), path))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spec_matches_routes() {
+        let routed = routed();
+        let documented = documented();
+        assert!(!routed.is_empty(), "No routes found, has the route syntax changed?");
+
+        let undocumented = routed.difference(&documented).collect::<Vec<_>>();
+        let stale = documented.difference(&routed).collect::<Vec<_>>();
+        assert!(
+            undocumented.is_empty() && stale.is_empty(),
+            "OpenAPI document is out of date.\nRoutes missing from it: {:?}\nDocumented routes that don't exist: {:?}",
+            undocumented,
+            stale
+        );
+    }
+
+    #[test]
+    fn spec_references_resolve() {
+        let spec = ApiDoc::openapi().to_json().unwrap();
+        let schemas = ApiDoc::openapi().components.unwrap().schemas;
+
+        let reference = Regex::new(r##""\$ref":"#/components/schemas/([^"]+)""##).unwrap();
+        let missing = reference
+            .captures_iter(&spec)
+            .map(|captures| captures[1].to_string())
+            .filter(|name| !schemas.contains_key(name))
+            .collect::<BTreeSet<_>>();
+
+        assert!(missing.is_empty(), "Schemas used but not listed in `components`: {:?}", missing);
+    }
+}
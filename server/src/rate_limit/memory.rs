@@ -0,0 +1,95 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::config::{LockoutPolicy, RateLimit};
+use super::{lockout_duration, take_token, Decision, RateLimitStore};
+
+/// Number of tracked keys after which idle entries are pruned.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    locked_until: Option<DateTime<Utc>>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Keeps limiter state in process memory. Limits are per server instance.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+    failures: Mutex<HashMap<String, Failures>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn acquire(&self, key: &str, limit: RateLimit) -> Result<Decision, sqlx::Error> {
+        let now = Utc::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            // Buckets idle for an hour have refilled completely and can be recreated on demand
+            buckets.retain(|_, bucket| (now - bucket.updated_at).num_hours() < 1);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: limit.burst as f64,
+            updated_at: now,
+        });
+
+        let (tokens, decision) = take_token(bucket.tokens, now - bucket.updated_at, limit);
+        *bucket = Bucket { tokens, updated_at: now };
+
+        Ok(decision)
+    }
+
+    async fn locked_until(&self, account: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        let failures = self.failures.lock().unwrap();
+
+        Ok(failures
+            .get(account)
+            .and_then(|failures| failures.locked_until)
+            .filter(|locked_until| *locked_until > Utc::now()))
+    }
+
+    async fn record_failure(&self, account: &str, policy: LockoutPolicy) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        let now = Utc::now();
+        let mut failures = self.failures.lock().unwrap();
+
+        if failures.len() > PRUNE_THRESHOLD {
+            failures.retain(|_, entry| (now - entry.updated_at).num_days() < 1);
+        }
+
+        let entry = failures.entry(account.to_string()).or_insert(Failures {
+            count: 0,
+            locked_until: None,
+            updated_at: now,
+        });
+
+        entry.count += 1;
+        entry.updated_at = now;
+        entry.locked_until = lockout_duration(entry.count, policy).map(|duration| now + duration);
+
+        Ok(entry.locked_until)
+    }
+
+    async fn reset_failures(&self, account: &str) -> Result<(), sqlx::Error> {
+        self.failures.lock().unwrap().remove(account);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+pub mod memory;
+pub mod postgres;
+
+use std::{fmt, sync::Arc};
+
+use axum::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Pool, Postgres};
+
+use crate::config::{LockoutPolicy, RateLimit, RateLimitConfig};
+
+pub use memory::MemoryStore;
+pub use postgres::PostgresStore;
+
+/// Route groups that get their own per-IP limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RouteGroup {
+    Auth,
+    Api,
+}
+
+/// The outcome of taking a token from a bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    Allowed,
+    /// The bucket is empty; a token will be available after the given number of seconds
+    Limited { retry_after: u64 },
+}
+
+/// Backing storage for token buckets and login failure counters.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync + fmt::Debug {
+    /// Takes one token from the bucket identified by `key`.
+    async fn acquire(&self, key: &str, limit: RateLimit) -> Result<Decision, sqlx::Error>;
+
+    /// Returns when the account's lockout ends, if it is currently locked.
+    async fn locked_until(&self, account: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error>;
+
+    /// Records a failed login and returns when the resulting lockout ends, if any.
+    async fn record_failure(&self, account: &str, policy: LockoutPolicy) -> Result<Option<DateTime<Utc>>, sqlx::Error>;
+
+    /// Clears the failure counter after a successful login.
+    async fn reset_failures(&self, account: &str) -> Result<(), sqlx::Error>;
+}
+
+/// Refills a bucket holding `tokens` that was last touched `elapsed` ago, then tries to take a token.
+/// Returns the new token count and the decision.
+pub fn take_token(tokens: f64, elapsed: Duration, limit: RateLimit) -> (f64, Decision) {
+    let rate = limit.per_minute as f64 / 60.0;
+    let elapsed = elapsed.num_milliseconds().max(0) as f64 / 1000.0;
+    let tokens = (tokens + elapsed * rate).min(limit.burst as f64);
+
+    if tokens >= 1.0 {
+        (tokens - 1.0, Decision::Allowed)
+    } else if rate > 0.0 {
+        let retry_after = ((1.0 - tokens) / rate).ceil() as u64;
+        (tokens, Decision::Limited { retry_after: retry_after.max(1) })
+    } else {
+        (tokens, Decision::Limited { retry_after: 60 })
+    }
+}
+
+/// How long an account stays locked after its `failures`-th consecutive failed login.
+pub fn lockout_duration(failures: u32, policy: LockoutPolicy) -> Option<Duration> {
+    if policy.max_failures == 0 || failures < policy.max_failures {
+        return None;
+    }
+
+    // Double the lockout for every failure past the threshold, without overflowing the shift
+    let exponent = (failures - policy.max_failures).min(16);
+    let seconds = (policy.base_seconds as u64) << exponent;
+
+    Duration::try_seconds(seconds.min(policy.max_seconds as u64) as i64)
+}
+
+/// Applies the configured limits on top of a `RateLimitStore`.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    config: RateLimitConfig,
+}
+
+impl RateLimiter {
+    pub fn new(store: Arc<dyn RateLimitStore>, config: RateLimitConfig) -> Self {
+        Self { store, config }
+    }
+
+    /// Builds the limiter selected by `RATE_LIMIT_STORE`, falling back to the in-memory store.
+    pub fn from_config(config: &RateLimitConfig, db: &Pool<Postgres>) -> Self {
+        let store: Arc<dyn RateLimitStore> = match config.store.as_str() {
+            "postgres" => Arc::new(PostgresStore::new(db.clone())),
+            _ => Arc::new(MemoryStore::new()),
+        };
+
+        Self::new(store, config.clone())
+    }
+
+    /// Takes a token from the bucket for the client IP within a route group.
+    pub async fn check_ip(&self, group: RouteGroup, ip: &str) -> Result<Decision, sqlx::Error> {
+        let (prefix, limit) = match group {
+            RouteGroup::Auth => ("auth", self.config.auth),
+            RouteGroup::Api => ("api", self.config.api),
+        };
+
+        self.store.acquire(&format!("{}:ip:{}", prefix, ip), limit).await
+    }
+
+    /// Takes a token from the login bucket for an account, whichever IP the attempt comes from.
+    pub async fn check_account(&self, account: &str) -> Result<Decision, sqlx::Error> {
+        self.store
+            .acquire(&format!("login:account:{}", account), self.config.login_account)
+            .await
+    }
+
+    pub async fn locked_until(&self, account: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        self.store.locked_until(account).await
+    }
+
+    pub async fn record_failure(&self, account: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        self.store.record_failure(account, self.config.lockout).await
+    }
+
+    pub async fn reset_failures(&self, account: &str) -> Result<(), sqlx::Error> {
+        self.store.reset_failures(account).await
+    }
+}
+
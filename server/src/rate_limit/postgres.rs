@@ -0,0 +1,140 @@
+use std::sync::{Arc, Mutex};
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+use crate::config::{LockoutPolicy, RateLimit};
+use super::{lockout_duration, take_token, Decision, RateLimitStore};
+
+/// How often each instance deletes rows that no longer matter, in minutes.
+const PRUNE_INTERVAL_MINUTES: i64 = 10;
+
+/// Keeps limiter state in Postgres so every server instance shares the same limits.
+#[derive(Debug, Clone)]
+pub struct PostgresStore {
+    db: Pool<Postgres>,
+    last_pruned: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl PostgresStore {
+    pub fn new(db: Pool<Postgres>) -> Self {
+        Self { db, last_pruned: Arc::new(Mutex::new(DateTime::<Utc>::MIN_UTC)) }
+    }
+
+    /// Deletes idle buckets and stale failure counters, at most every `PRUNE_INTERVAL_MINUTES`.
+    /// Expires them like `MemoryStore` does: buckets idle for an hour have refilled and are
+    /// recreated on demand, failures are forgotten a day after the last one unless still locked.
+    async fn prune(&self) -> Result<(), sqlx::Error> {
+        {
+            let now = Utc::now();
+            let mut last_pruned = self.last_pruned.lock().unwrap();
+            if (now - *last_pruned).num_minutes() < PRUNE_INTERVAL_MINUTES {
+                return Ok(());
+            }
+            *last_pruned = now;
+        }
+
+        sqlx::query!("DELETE FROM rate_limit_buckets WHERE updated_at < NOW() - INTERVAL '1 hour'")
+            .execute(&self.db)
+            .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM login_failures
+            WHERE updated_at < NOW() - INTERVAL '1 day' AND (locked_until IS NULL OR locked_until < NOW())
+            "#
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for PostgresStore {
+    async fn acquire(&self, key: &str, limit: RateLimit) -> Result<Decision, sqlx::Error> {
+        self.prune().await?;
+
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query!(
+            "INSERT INTO rate_limit_buckets (key, tokens) VALUES ($1, $2) ON CONFLICT (key) DO NOTHING",
+            key,
+            limit.burst as f64
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Lock the row so concurrent requests from other instances see each other's updates
+        let bucket = sqlx::query!(
+            r#"SELECT tokens, updated_at, NOW() AS "now!" FROM rate_limit_buckets WHERE key = $1 FOR UPDATE"#,
+            key
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let (tokens, decision) = take_token(bucket.tokens, bucket.now - bucket.updated_at, limit);
+
+        sqlx::query!(
+            "UPDATE rate_limit_buckets SET tokens = $1, updated_at = $2 WHERE key = $3",
+            tokens,
+            bucket.now,
+            key
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(decision)
+    }
+
+    async fn locked_until(&self, account: &str) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        let locked_until = sqlx::query_scalar!(
+            "SELECT locked_until FROM login_failures WHERE account = $1 AND locked_until > NOW()",
+            account
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(locked_until.flatten())
+    }
+
+    async fn record_failure(&self, account: &str, policy: LockoutPolicy) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        self.prune().await?;
+
+        let failures = sqlx::query_scalar!(
+            r#"
+            INSERT INTO login_failures (account, failures, updated_at) VALUES ($1, 1, NOW())
+            ON CONFLICT (account) DO UPDATE SET failures = login_failures.failures + 1, updated_at = NOW()
+            RETURNING failures
+            "#,
+            account
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        let locked_until = lockout_duration(failures.max(0) as u32, policy).map(|duration| Utc::now() + duration);
+
+        if locked_until.is_some() {
+            sqlx::query!(
+                "UPDATE login_failures SET locked_until = $1 WHERE account = $2",
+                locked_until,
+                account
+            )
+            .execute(&self.db)
+            .await?;
+        }
+
+        Ok(locked_until)
+    }
+
+    async fn reset_failures(&self, account: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM login_failures WHERE account = $1", account)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+//! Personalized song recommendations, see `recommend`.
+
+use common::schema::{
+    recommendation::{Recommendation, RecommendationReason},
+    song::SongSummary
+};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::model::SongSummaryRow;
+
+/// Songs skipped with "not now" come back after this many days.
+pub const NOT_NOW_DAYS: i32 = 7;
+/// Plays older than this no longer say anything about the user's taste.
+const HISTORY_DAYS: i32 = 90;
+/// Only the songs that say the most about the user's taste are compared with the catalog.
+const MAX_SEEDS: i64 = 50;
+
+#[derive(sqlx::FromRow)]
+struct ScoredRow {
+    #[sqlx(flatten)]
+    song: SongSummaryRow,
+    match_score: f32,
+    seed_id: Option<Uuid>,
+    seed_title: Option<String>,
+    followers: i64,
+}
+
+impl From<ScoredRow> for Recommendation {
+    fn from(row: ScoredRow) -> Self {
+        let reason = match (row.seed_id, row.seed_title) {
+            (Some(song_id), Some(title)) => RecommendationReason::SimilarTo { song_id, title },
+            _ if row.followers > 0 => RecommendationReason::LikedByFollowing { count: row.followers },
+            _ => RecommendationReason::Popular,
+        };
+
+        Recommendation {
+            song: SongSummary::from(row.song),
+            match_score: row.match_score,
+            reason,
+        }
+    }
+}
+
+/// Recommends up to `limit` songs to the user. See `store` to keep them.
+///
+/// Songs the user liked, gave a thumbs up and played recently are the seeds, weighted in that
+/// order, and songs they gave a thumbs down count against. A song's score adds up how much it's
+/// like each seed: by the same artist, of the same genre and with close audio features.
+/// Songs liked by followed users score higher, if the user allows it and the followed users
+/// don't keep their history private.
+///
+/// Liked songs and those the user gave feedback on aren't recommended, except those skipped
+/// more than `NOT_NOW_DAYS` ago. When the seeds find too few songs, popular ones fill the batch.
+pub async fn recommend(db: &Pool<Postgres>, user_id: Uuid, limit: i64) -> Result<Vec<Recommendation>, sqlx::Error> {
+    let mut recommendations = sqlx::query_as::<_, ScoredRow>(
+        r#"
+        WITH signals AS (
+            SELECT song_id, 1.0 AS weight FROM song_likes WHERE user_id = $1
+            UNION ALL
+            SELECT song_id, CASE feedback WHEN 'THUMBS_UP' THEN 1.0 ELSE -1.0 END FROM recommendation_feedback
+            WHERE user_id = $1 AND feedback IN ('THUMBS_UP', 'THUMBS_DOWN')
+            UNION ALL
+            SELECT song_id, 0.25 FROM listening_history
+            WHERE user_id = $1 AND played_at > NOW() - make_interval(days => $3)
+        ),
+        seeds AS (
+            SELECT songs.song_id, songs.title, songs.artist_id, songs.genre, songs.tempo, songs.loudness,
+                songs.speechiness, songs.danceability, GREATEST(LEAST(SUM(signals.weight), 2.0), -2.0) AS weight
+            FROM signals
+            JOIN songs ON songs.song_id = signals.song_id
+            GROUP BY songs.song_id
+            HAVING SUM(signals.weight) <> 0
+            ORDER BY ABS(SUM(signals.weight)) DESC
+            LIMIT $4
+        ),
+        excluded AS (
+            SELECT song_id FROM song_likes WHERE user_id = $1
+            UNION
+            SELECT song_id FROM recommendation_feedback
+            WHERE user_id = $1 AND (feedback <> 'NOT_NOW' OR created_at > NOW() - make_interval(days => $5))
+            UNION
+            SELECT song_id FROM seeds
+        ),
+        pairs AS (
+            SELECT songs.song_id, seeds.song_id AS seed_id, seeds.title AS seed_title, seeds.weight * (
+                0.5 * (songs.artist_id = seeds.artist_id)::INT
+                + 0.3 * (songs.genre = seeds.genre)::INT
+                + 0.2 / (1
+                    + COALESCE(ABS(songs.tempo - seeds.tempo) / 30, 1)
+                    + COALESCE(ABS(songs.loudness - seeds.loudness) / 6, 1)
+                    + COALESCE(ABS(songs.speechiness - seeds.speechiness) / 0.33, 1)
+                    + COALESCE(ABS(songs.danceability - seeds.danceability) / 0.33, 1))
+            ) AS score
+            FROM songs
+            JOIN seeds ON songs.artist_id = seeds.artist_id OR songs.genre = seeds.genre
+            WHERE songs.song_id NOT IN (SELECT song_id FROM excluded)
+        ),
+        likeness AS (
+            SELECT song_id, SUM(score) AS score,
+                (array_agg(seed_id ORDER BY score DESC))[1] AS seed_id,
+                (array_agg(seed_title ORDER BY score DESC))[1] AS seed_title,
+                MAX(score) AS best_score
+            FROM pairs
+            GROUP BY song_id
+        ),
+        social AS (
+            SELECT song_likes.song_id, COUNT(*) AS followers
+            FROM follows
+            JOIN users AS follower ON follower.user_id = follows.follower_id
+            JOIN users AS followee ON followee.user_id = follows.followee_id
+            JOIN song_likes ON song_likes.user_id = follows.followee_id
+            WHERE follows.follower_id = $1
+                AND follower.allow_social_recommendations
+                AND followee.history_visibility <> 'PRIVATE'
+                AND song_likes.song_id NOT IN (SELECT song_id FROM excluded)
+            GROUP BY song_likes.song_id
+        ),
+        total AS (
+            SELECT COALESCE(SUM(weight) FILTER (WHERE weight > 0), 0) AS weight FROM seeds
+        ),
+        scored AS (
+            SELECT song_id,
+                LEAST(
+                    COALESCE(likeness.score, 0) / GREATEST(total.weight, 1)
+                    + 0.1 * LEAST(COALESCE(social.followers, 0), 5),
+                    1
+                ) AS match_score,
+                CASE WHEN likeness.best_score > 0 THEN likeness.seed_id END AS seed_id,
+                CASE WHEN likeness.best_score > 0 THEN likeness.seed_title END AS seed_title,
+                COALESCE(social.followers, 0) AS followers
+            FROM likeness
+            FULL JOIN social USING (song_id)
+            CROSS JOIN total
+        )
+        SELECT songs.song_id, songs.title, songs.artist_id, artists.name AS artist, songs.album_id,
+            albums.title AS album, albums.cover, songs.duration, songs.audio_key IS NOT NULL AS has_audio,
+            scored.match_score::REAL AS match_score, scored.seed_id, scored.seed_title, scored.followers
+        FROM scored
+        JOIN songs ON songs.song_id = scored.song_id
+        JOIN artists ON artists.artist_id = songs.artist_id
+        JOIN albums ON albums.album_id = songs.album_id
+        WHERE scored.match_score > 0
+        ORDER BY scored.match_score DESC, songs.title
+        LIMIT $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .bind(HISTORY_DAYS)
+    .bind(MAX_SEEDS)
+    .bind(NOT_NOW_DAYS)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(Recommendation::from)
+    .collect::<Vec<_>>();
+
+    if (recommendations.len() as i64) < limit {
+        let found = recommendations.iter().map(|recommendation| recommendation.song.song_id).collect::<Vec<_>>();
+        let popular = popular(db, user_id, &found, limit - recommendations.len() as i64).await?;
+        recommendations.extend(popular);
+    }
+
+    Ok(recommendations)
+}
+
+/// The songs most liked and played in the last `HISTORY_DAYS`, other than those in `found`, those
+/// the user played and those excluded from the user's recommendations. Scored against the most popular, at most 0.5 so
+/// they rank below songs picked for the user.
+async fn popular(
+    db: &Pool<Postgres>,
+    user_id: Uuid,
+    found: &[Uuid],
+    limit: i64,
+) -> Result<Vec<Recommendation>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, ScoredRow>(
+        r#"
+        WITH activity AS (
+            SELECT song_id FROM song_likes WHERE created_at > NOW() - make_interval(days => $3)
+            UNION ALL
+            SELECT song_id FROM listening_history WHERE played_at > NOW() - make_interval(days => $3)
+        ),
+        popularity AS (
+            SELECT song_id, COUNT(*) AS count FROM activity
+            WHERE song_id <> ALL($4)
+                AND song_id NOT IN (SELECT song_id FROM song_likes WHERE user_id = $1)
+                AND song_id NOT IN (SELECT song_id FROM listening_history WHERE user_id = $1)
+                AND song_id NOT IN (
+                    SELECT song_id FROM recommendation_feedback
+                    WHERE user_id = $1 AND (feedback <> 'NOT_NOW' OR created_at > NOW() - make_interval(days => $5))
+                )
+            GROUP BY song_id
+        )
+        SELECT songs.song_id, songs.title, songs.artist_id, artists.name AS artist, songs.album_id,
+            albums.title AS album, albums.cover, songs.duration, songs.audio_key IS NOT NULL AS has_audio,
+            (0.5 * popularity.count / MAX(popularity.count) OVER ())::REAL AS match_score,
+            NULL::UUID AS seed_id, NULL::TEXT AS seed_title, 0::BIGINT AS followers
+        FROM popularity
+        JOIN songs ON songs.song_id = popularity.song_id
+        JOIN artists ON artists.artist_id = songs.artist_id
+        JOIN albums ON albums.album_id = songs.album_id
+        ORDER BY popularity.count DESC, songs.title
+        LIMIT $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .bind(HISTORY_DAYS)
+    .bind(found)
+    .bind(NOT_NOW_DAYS)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows.into_iter().map(Recommendation::from).collect())
+}
+
+/// Replaces the user's stored recommendations, which are included in their data export.
+///
+/// Locks the user's row first, so concurrent requests for the same user replace the batch one
+/// after the other instead of both inserting theirs.
+pub async fn store(db: &Pool<Postgres>, user_id: Uuid, recommendations: &[Recommendation]) -> Result<(), sqlx::Error> {
+    let ids = recommendations.iter().map(|_| Uuid::new_v4()).collect::<Vec<_>>();
+    let song_ids = recommendations.iter().map(|recommendation| recommendation.song.song_id).collect::<Vec<_>>();
+    let scores = recommendations.iter().map(|recommendation| recommendation.match_score).collect::<Vec<_>>();
+
+    let mut tx = db.begin().await?;
+    sqlx::query("SELECT user_id FROM users WHERE user_id = $1 FOR UPDATE")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM recommendations WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO recommendations (recommendation_id, user_id, song_id, match_score)
+        SELECT id, $1, song_id, match_score FROM UNNEST($2::UUID[], $3::UUID[], $4::REAL[]) AS new (id, song_id, match_score)
+        "#,
+    )
+    .bind(user_id)
+    .bind(&ids)
+    .bind(&song_ids)
+    .bind(&scores)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
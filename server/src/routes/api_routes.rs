@@ -0,0 +1,27 @@
+use axum::{middleware::from_fn_with_state, Router};
+
+use crate::middleware::deprecated;
+use crate::routes::{
+    auth_routes::auth_routes, catalog_routes::catalog_routes, docs_routes::{docs_routes, legacy_docs_routes},
+    user_routes::user_routes
+};
+
+/// Where version 1 of the API is served
+pub const API_V1: &str = "/api/v1";
+
+/// Every version of the API, with its documentation.
+/// The unversioned `/api/...` paths predate versioning. They keep serving v1 for clients that haven't
+/// moved yet, with headers marking them as deprecated.
+pub fn api_routes() -> Router {
+    Router::new()
+    .nest(API_V1, v1_routes())
+    .nest("/api", v1_routes().merge(legacy_docs_routes()).layer(from_fn_with_state(API_V1, deprecated)))
+    .merge(docs_routes())
+}
+
+fn v1_routes() -> Router {
+    Router::new()
+    .merge(auth_routes())
+    .merge(user_routes())
+    .merge(catalog_routes())
+}
\ No newline at end of file
@@ -1,27 +1,34 @@
-use axum::http::HeaderValue;
-use axum::routing::{ post, get };
-use axum::{Router, http::Method};
-use tower_http::cors::{AllowCredentials, Any, CorsLayer};
+use axum::routing::post;
+use axum::{Router, middleware::from_fn_with_state};
 
+use crate::middleware::{auth, rate_limit, Access};
+use crate::rate_limit::RouteGroup;
 use crate::handlers::auth_handler::{
     login_user_handler, 
     register_user_handler,
     logout_handler,
-    refresh_token_handler
+    refresh_token_handler,
+    request_verification_handler,
+    verify_email_handler,
+    confirm_email_change_handler,
+    forgot_password_handler,
+    reset_password_handler
 };
+use crate::handlers::two_factor_handler::verify_two_factor_handler;
 
+/// Logging in and account recovery, relative to the API version's prefix
 pub fn auth_routes() -> Router {
-
-    let cors = CorsLayer::new()
-    .allow_credentials(true)
-    .allow_methods([Method::POST]);
-
-    let router = Router::new()
-    .route("/api/auth/login", post(login_user_handler))
-    .route("/api/auth/register", post(register_user_handler))
-    .route("/api/auth/logout", post(logout_handler))
-    .route("/api/auth/refresh", post(refresh_token_handler))
-    .layer(cors);
-
-    router
+    Router::new()
+    .route("/auth/login", post(login_user_handler))
+    .route("/auth/register", post(register_user_handler))
+    .route("/auth/logout", post(logout_handler))
+    .route("/auth/refresh", post(refresh_token_handler))
+    .route("/auth/verify-email", post(verify_email_handler))
+    .route("/auth/verify-email/resend", post(request_verification_handler))
+    .route("/auth/confirm-email-change", post(confirm_email_change_handler))
+    .route("/auth/forgot-password", post(forgot_password_handler))
+    .route("/auth/reset-password", post(reset_password_handler))
+    .route("/auth/2fa/verify", post(verify_two_factor_handler))
+    .route_layer(from_fn_with_state(Access::Public, auth))
+    .layer(from_fn_with_state(RouteGroup::Auth, rate_limit))
 }
\ No newline at end of file
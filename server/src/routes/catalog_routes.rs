@@ -0,0 +1,37 @@
+use axum::routing::{get, post};
+use axum::{Router, extract::DefaultBodyLimit, middleware::from_fn_with_state};
+
+use crate::middleware::{auth, rate_limit, Access};
+use crate::rate_limit::RouteGroup;
+use crate::handlers::album_handler::get_album_handler;
+use crate::handlers::artist_handler::get_artist_handler;
+use crate::handlers::search_handler::{search_handler, search_suggestions_handler};
+use crate::handlers::song_handler::{
+    get_song_handler,
+    upload_song_audio_handler,
+    stream_song_handler,
+    stream_preview_handler
+};
+
+/// Songs, artists, albums and search, relative to the API version's prefix
+pub fn catalog_routes() -> Router {
+    let public = Router::new()
+    .route("/songs/:song_id", get(get_song_handler))
+    .route("/songs/:song_id/preview", get(stream_preview_handler))
+    .route("/artists/:artist_id", get(get_artist_handler))
+    .route("/albums/:album_id", get(get_album_handler))
+    .route("/search", get(search_handler))
+    .route("/search/suggestions", get(search_suggestions_handler))
+    .route_layer(from_fn_with_state(Access::Public, auth));
+
+    let user = Router::new()
+    .route("/songs/:song_id/stream", get(stream_song_handler))
+    // The handler enforces `AUDIO_MAX_BYTES` itself while reading the upload
+    .route("/songs/:song_id/audio", post(upload_song_audio_handler).layer(DefaultBodyLimit::disable()))
+    .route_layer(from_fn_with_state(Access::User, auth));
+
+    Router::new()
+    .merge(public)
+    .merge(user)
+    .layer(from_fn_with_state(RouteGroup::Api, rate_limit))
+}
\ No newline at end of file
@@ -0,0 +1,18 @@
+use axum::{response::Redirect, routing::get, Router};
+use utoipa::OpenApi;
+use utoipa_rapidoc::RapiDoc;
+
+use crate::openapi::ApiDoc;
+
+/// Serves the OpenAPI document at `/api/v1/openapi.json`, and a page to browse and try it at `/api/v1/docs`.
+pub fn docs_routes() -> Router {
+    Router::new()
+    .merge(RapiDoc::with_openapi("/api/v1/openapi.json", ApiDoc::openapi()).path("/api/v1/docs"))
+}
+
+/// Redirects the documentation's unversioned URLs, relative to `/api`, to the versioned ones.
+pub fn legacy_docs_routes() -> Router {
+    Router::new()
+    .route("/openapi.json", get(|| async { Redirect::permanent("/api/v1/openapi.json") }))
+    .route("/docs", get(|| async { Redirect::permanent("/api/v1/docs") }))
+}
@@ -1,2 +1,6 @@
 pub mod user_routes;
-pub mod auth_routes;
\ No newline at end of file
+pub mod auth_routes;
+pub mod well_known_routes;
+pub mod docs_routes;
+pub mod api_routes;
+pub mod catalog_routes;
\ No newline at end of file
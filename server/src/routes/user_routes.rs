@@ -1,27 +1,95 @@
-use axum::http::HeaderValue;
-use axum::routing::{delete, put, get, post};
-use axum::{Router, http::Method};
-use tower_http::cors::{CorsLayer, AllowCredentials};
+use axum::routing::{delete, patch, put, get, post};
+use axum::{Router, extract::DefaultBodyLimit, middleware::from_fn_with_state};
 
+use crate::middleware::{auth, rate_limit, Access};
+use crate::rate_limit::RouteGroup;
 use crate::handlers::user_handler::{
     get_user_preferences_handler, 
     update_user_preferences_handler,
     health_check_handler,
-    get_user_handler
+    get_user_handler,
+    update_profile_handler,
+    update_privacy_handler,
+    change_password_handler,
+    change_email_handler,
+    delete_account_handler
+};
+use crate::handlers::two_factor_handler::{
+    enroll_two_factor_handler,
+    enable_two_factor_handler,
+    disable_two_factor_handler,
+    regenerate_recovery_codes_handler
+};
+use crate::handlers::avatar_handler::upload_avatar_handler;
+use crate::handlers::export_handler::export_user_data_handler;
+use crate::handlers::profile_handler::get_public_profile_handler;
+use crate::handlers::social_handler::{
+    get_follow_status_handler,
+    follow_user_handler,
+    unfollow_user_handler,
+    list_followers_handler,
+    list_following_handler,
+    get_feed_handler,
+    like_song_handler,
+    unlike_song_handler,
+    record_play_handler
+};
+use crate::handlers::recommendation_handler::{
+    get_recommendations_handler,
+    put_recommendation_feedback_handler,
+    delete_recommendation_feedback_handler
+};
+use crate::handlers::api_token_handler::{
+    list_api_tokens_handler,
+    create_api_token_handler,
+    revoke_api_token_handler
 };
 
+/// Everything but logging in, relative to the API version's prefix
 pub fn user_routes() -> Router {
-    
-    let cors = CorsLayer::new()
-    .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-    .allow_credentials(true);
+    let public = Router::new()
+    .route("/healthchecker", get(health_check_handler))
+    .route_layer(from_fn_with_state(Access::Public, auth));
+
+    // Profiles show more to a logged in viewer, depending on the owner's privacy settings
+    let profiles = Router::new()
+    .route("/users/:username", get(get_public_profile_handler))
+    .route("/users/:username/followers", get(list_followers_handler))
+    .route("/users/:username/following", get(list_following_handler))
+    .route_layer(from_fn_with_state(Access::Optional, auth));
+
+    let user = Router::new()
+    .route("/user/preferences", get(get_user_preferences_handler).put(update_user_preferences_handler))
+    .route("/user/info", get(get_user_handler))
+    .route("/user/profile", patch(update_profile_handler))
+    .route("/user/privacy", patch(update_privacy_handler))
+    // The handler enforces `AVATAR_MAX_BYTES` itself while reading the upload
+    .route("/user/avatar", post(upload_avatar_handler).layer(DefaultBodyLimit::disable()))
+    .route("/users/:username/follow", get(get_follow_status_handler).post(follow_user_handler).delete(unfollow_user_handler))
+    .route("/user/feed", get(get_feed_handler))
+    .route("/user/likes/:song_id", put(like_song_handler).delete(unlike_song_handler))
+    .route("/user/history", post(record_play_handler))
+    .route("/user/recommendations", get(get_recommendations_handler))
+    .route("/user/recommendations/:song_id/feedback", put(put_recommendation_feedback_handler).delete(delete_recommendation_feedback_handler))
+    .route_layer(from_fn_with_state(Access::User, auth));
 
-    let router = Router::new()
-    .route("/api/healthchecker", get(health_check_handler))
-    .route("/api/user/preferences", get(get_user_preferences_handler))
-    .route("/api/user/info", get(get_user_handler))
-    .route("/api/user/preferences", put(update_user_preferences_handler))
-    .layer(cors);
+    let account_security = Router::new()
+    .route("/user", delete(delete_account_handler))
+    .route("/user/export", get(export_user_data_handler))
+    .route("/user/password", post(change_password_handler))
+    .route("/user/email", post(change_email_handler))
+    .route("/user/2fa/enroll", post(enroll_two_factor_handler))
+    .route("/user/2fa/enable", post(enable_two_factor_handler))
+    .route("/user/2fa/disable", post(disable_two_factor_handler))
+    .route("/user/2fa/recovery-codes", post(regenerate_recovery_codes_handler))
+    .route("/user/tokens", get(list_api_tokens_handler).post(create_api_token_handler))
+    .route("/user/tokens/:token_id", delete(revoke_api_token_handler))
+    .route_layer(from_fn_with_state(Access::Session, auth));
 
-    router
+    Router::new()
+    .merge(public)
+    .merge(profiles)
+    .merge(user)
+    .merge(account_security)
+    .layer(from_fn_with_state(RouteGroup::Api, rate_limit))
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+use axum::routing::get;
+use axum::{Router, http::Method};
+use tower_http::cors::{Any, CorsLayer};
+
+use crate::handlers::auth_handler::jwks_handler;
+
+pub fn well_known_routes() -> Router {
+
+    // Fetched by other services rather than the client, so any origin may read it
+    let cors = CorsLayer::new()
+    .allow_origin(Any)
+    .allow_methods([Method::GET]);
+
+    Router::new()
+    .route("/.well-known/jwks.json", get(jwks_handler))
+    .layer(cors)
+}
\ No newline at end of file
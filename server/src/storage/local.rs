@@ -0,0 +1,90 @@
+use std::{io::{ErrorKind, SeekFrom}, ops::Range, path::{Component, Path, PathBuf}};
+
+use axum::{async_trait, body::{Body, Bytes}};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+use crate::config::StorageConfig;
+use super::{public_url, BlobMeta, BlobStore, StorageError};
+
+/// Stores blobs as files under `STORAGE_DIR`. The public ones are served by the server itself at `/uploads`.
+#[derive(Debug, Clone)]
+pub struct LocalBlobStore {
+    dir: PathBuf,
+    public_url: String,
+}
+
+impl LocalBlobStore {
+    pub fn new(config: &StorageConfig) -> Self {
+        Self {
+            dir: PathBuf::from(&config.dir),
+            public_url: config.public_url.clone(),
+        }
+    }
+
+    /// Resolves a key to a path inside the storage directory, refusing keys that would escape it.
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(key);
+        if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(StorageError::Backend(format!("Invalid key {}", key)));
+        }
+
+        Ok(self.dir.join(relative))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| StorageError::Backend(e.to_string()))?;
+        }
+
+        // Write next to the target and rename, so a half-written file is never served
+        let tmp_path = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        tokio::fs::write(&tmp_path, &bytes)
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))
+    }
+
+    async fn head(&self, key: &str) -> Result<Option<BlobMeta>, StorageError> {
+        let metadata = match tokio::fs::metadata(self.path(key)?).await {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(StorageError::Backend(e.to_string())),
+        };
+        let modified = metadata.modified().map_err(|e| StorageError::Backend(e.to_string()))?;
+
+        Ok(Some(BlobMeta { size: metadata.len(), last_modified: modified.into() }))
+    }
+
+    async fn get_range(&self, key: &str, range: Range<u64>) -> Result<Body, StorageError> {
+        let mut file = tokio::fs::File::open(self.path(key)?)
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+        file.seek(SeekFrom::Start(range.start))
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+
+        Ok(Body::from_stream(ReaderStream::new(file.take(range.end - range.start))))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(StorageError::Backend(e.to_string())),
+        }
+    }
+
+    fn url(&self, key: &str) -> Option<String> {
+        public_url(&self.public_url, key)
+    }
+}
@@ -0,0 +1,72 @@
+mod local;
+mod s3;
+
+use std::{fmt, ops::Range, sync::Arc};
+
+use axum::{async_trait, body::{Body, Bytes}};
+use chrono::{DateTime, Utc};
+
+use crate::config::StorageConfig;
+pub use local::LocalBlobStore;
+pub use s3::S3BlobStore;
+
+#[derive(Debug)]
+pub enum StorageError {
+    /// The backend is misconfigured (missing bucket, bad endpoint, ...)
+    Config(String),
+    /// Reading or writing the blob failed
+    Backend(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Config(e) => write!(f, "Invalid storage configuration: {}", e),
+            StorageError::Backend(e) => write!(f, "Storage error: {}", e),
+        }
+    }
+}
+
+/// Size and modification time of a stored blob.
+#[derive(Debug, Clone)]
+pub struct BlobMeta {
+    /// In bytes
+    pub size: u64,
+    pub last_modified: DateTime<Utc>,
+}
+
+/// Blobs whose key starts with this are public and served straight from the store. Everything
+/// else, song audio and previews, is private and only streamed by the server to logged in users.
+pub const PUBLIC_PREFIX: &str = "avatars/";
+
+/// Somewhere uploaded files can be stored and served from.
+/// Keys are `/` separated paths such as `avatars/<user_id>/256.png`.
+#[async_trait]
+pub trait BlobStore: Send + Sync + fmt::Debug {
+    /// Stores `bytes` under `key`, replacing anything already there.
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError>;
+    /// The size and modification time of the blob stored under `key`, `None` if there is none.
+    async fn head(&self, key: &str) -> Result<Option<BlobMeta>, StorageError>;
+    /// Streams the bytes in `range` of the blob stored under `key`. The range must be within the blob.
+    async fn get_range(&self, key: &str, range: Range<u64>) -> Result<Body, StorageError>;
+    /// Removes the blob stored under `key`. Deleting a missing blob is not an error.
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+    /// The public URL the blob stored under `key` is served from, `None` for private blobs.
+    fn url(&self, key: &str) -> Option<String>;
+}
+
+/// Builds the store selected by `STORAGE_BACKEND`, falling back to the local filesystem.
+pub fn from_config(config: &StorageConfig) -> Result<Arc<dyn BlobStore>, StorageError> {
+    match config.backend.as_str() {
+        "s3" => Ok(Arc::new(S3BlobStore::new(config)?)),
+        _ => Ok(Arc::new(LocalBlobStore::new(config))),
+    }
+}
+
+/// The public URL of `key` under `base`, without doubling up on slashes. `None` for private keys.
+fn public_url(base: &str, key: &str) -> Option<String> {
+    let key = key.trim_start_matches('/');
+
+    key.starts_with(PUBLIC_PREFIX)
+        .then(|| format!("{}/{}", base.trim_end_matches('/'), key))
+}
@@ -0,0 +1,116 @@
+use std::ops::Range;
+
+use axum::{async_trait, body::{Body, Bytes}};
+use object_store::{
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+    ClientOptions, GetOptions, ObjectStore,
+};
+
+use crate::config::StorageConfig;
+use super::{public_url, BlobMeta, BlobStore, StorageError, PUBLIC_PREFIX};
+
+/// Stores public blobs in the `S3_BUCKET` bucket and private ones in `S3_PRIVATE_BUCKET`, so
+/// making avatars readable by anyone doesn't expose song audio. Works with any S3-compatible
+/// service by setting `S3_ENDPOINT`, e.g. a local MinIO from `docker-compose.dev.yml`.
+#[derive(Debug)]
+pub struct S3BlobStore {
+    public: AmazonS3,
+    private: AmazonS3,
+    public_url: String,
+}
+
+impl S3BlobStore {
+    pub fn new(config: &StorageConfig) -> Result<Self, StorageError> {
+        let bucket = config.s3_bucket.as_ref()
+            .ok_or_else(|| StorageError::Config("S3_BUCKET must be set".to_string()))?;
+        let private_bucket = config.s3_private_bucket.as_ref()
+            .ok_or_else(|| StorageError::Config("S3_PRIVATE_BUCKET must be set".to_string()))?;
+
+        // Without a content type, S3 serves everything as binary/octet-stream
+        let client_options = ClientOptions::new()
+            .with_content_type_for_suffix("png", "image/png")
+            .with_content_type_for_suffix("wav", "audio/wav")
+            .with_content_type_for_suffix("flac", "audio/flac")
+            .with_content_type_for_suffix("mp3", "audio/mpeg");
+
+        let mut builder = AmazonS3Builder::new()
+            .with_region(&config.s3_region)
+            .with_client_options(client_options);
+
+        if let Some(endpoint) = &config.s3_endpoint {
+            builder = builder
+                .with_endpoint(endpoint)
+                .with_allow_http(endpoint.starts_with("http://"));
+        }
+        if let Some(access_key_id) = &config.s3_access_key_id {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Some(secret_access_key) = &config.s3_secret_access_key {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+
+        let build = |bucket: &str| {
+            builder.clone()
+                .with_bucket_name(bucket)
+                .build()
+                .map_err(|e| StorageError::Config(e.to_string()))
+        };
+
+        Ok(Self {
+            public: build(bucket)?,
+            private: build(private_bucket)?,
+            public_url: config.public_url.clone(),
+        })
+    }
+
+    /// The bucket the blob stored under `key` belongs in.
+    fn store(&self, key: &str) -> &AmazonS3 {
+        if key.trim_start_matches('/').starts_with(PUBLIC_PREFIX) {
+            &self.public
+        } else {
+            &self.private
+        }
+    }
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError> {
+        self.store(key).put(&Path::from(key), bytes)
+            .await
+            .map(|_| ())
+            .map_err(|e| StorageError::Backend(e.to_string()))
+    }
+
+    async fn head(&self, key: &str) -> Result<Option<BlobMeta>, StorageError> {
+        match self.store(key).head(&Path::from(key)).await {
+            Ok(meta) => Ok(Some(BlobMeta { size: meta.size as u64, last_modified: meta.last_modified })),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(StorageError::Backend(e.to_string())),
+        }
+    }
+
+    async fn get_range(&self, key: &str, range: Range<u64>) -> Result<Body, StorageError> {
+        let options = GetOptions {
+            range: Some((range.start as usize..range.end as usize).into()),
+            ..Default::default()
+        };
+        let result = self.store(key).get_opts(&Path::from(key), options)
+            .await
+            .map_err(|e| StorageError::Backend(e.to_string()))?;
+
+        Ok(Body::from_stream(result.into_stream()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self.store(key).delete(&Path::from(key)).await {
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(StorageError::Backend(e.to_string())),
+        }
+    }
+
+    fn url(&self, key: &str) -> Option<String> {
+        public_url(&self.public_url, key)
+    }
+}
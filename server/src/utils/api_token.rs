@@ -0,0 +1,39 @@
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::{model::ApiTokenScope, utils::token::{generate_token, hash_token}};
+
+/// Every personal API token starts with this, which is how `middleware::auth` tells them apart from JWTs.
+pub const API_TOKEN_PREFIX: &str = "rm_pat_";
+/// Length of the token prefix stored in plain text so users can recognise their tokens.
+pub const DISPLAY_PREFIX_LENGTH: usize = 12;
+
+/// The token a request was authenticated with, inserted into the request extensions by `middleware::auth`.
+#[derive(Debug, Clone)]
+pub struct ApiTokenAuth {
+    pub token_id: Uuid,
+    pub scopes: Vec<ApiTokenScope>,
+}
+
+/// Generates a new personal API token. Only its hash is stored.
+pub fn generate_api_token() -> String {
+    format!("{}{}", API_TOKEN_PREFIX, generate_token())
+}
+
+/// Looks up an unexpired token by its hash and records that it was used.
+/// Returns the owner's id along with the token, or `None` if the token is unknown or expired.
+pub async fn authenticate_api_token(
+    db: &Pool<Postgres>,
+    token: &str,
+) -> Result<Option<(Uuid, ApiTokenAuth)>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"UPDATE api_tokens SET last_used_at = NOW()
+        WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > NOW())
+        RETURNING token_id, user_id, scopes AS "scopes: Vec<ApiTokenScope>""#,
+        hash_token(token)
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(|row| (row.user_id, ApiTokenAuth { token_id: row.token_id, scopes: row.scopes })))
+}
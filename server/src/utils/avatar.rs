@@ -0,0 +1,52 @@
+use std::io::Cursor;
+
+use image::{imageops::FilterType, ImageFormat, ImageReader, Limits};
+use uuid::Uuid;
+
+/// Square sizes, in pixels, every avatar is resized to. The largest is the one stored in `users.photo`.
+pub const AVATAR_SIZES: [u32; 3] = [64, 128, 256];
+/// Formats accepted for upload, whatever content type the client claims.
+const ACCEPTED_FORMATS: [ImageFormat; 4] = [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::WebP, ImageFormat::Gif];
+/// Largest width or height accepted, so a small file can't decode into a huge image.
+const MAX_DIMENSION: u32 = 8192;
+
+#[derive(Debug)]
+pub enum AvatarError {
+    /// The upload isn't one of the accepted image formats
+    UnsupportedFormat,
+    /// The upload claims to be an image but can't be decoded
+    Invalid(String),
+}
+
+/// Storage key of the avatar resized to `size`.
+pub fn avatar_key(user_id: Uuid, size: u32) -> String {
+    format!("avatars/{}/{}.png", user_id, size)
+}
+
+/// Detects the image format from the file's magic bytes, then decodes it and resizes it to
+/// every size in `AVATAR_SIZES`, cropped to a square. Returns PNG-encoded images in the same order.
+///
+/// Decoding is CPU bound, call it from `tokio::task::spawn_blocking`.
+pub fn resize_avatar(bytes: &[u8]) -> Result<Vec<(u32, Vec<u8>)>, AvatarError> {
+    let format = image::guess_format(bytes)
+        .ok()
+        .filter(|format| ACCEPTED_FORMATS.contains(format))
+        .ok_or(AvatarError::UnsupportedFormat)?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+    let image = reader.decode().map_err(|e| AvatarError::Invalid(e.to_string()))?;
+
+    AVATAR_SIZES.iter().map(|&size| {
+        let mut encoded = Vec::new();
+        image.resize_to_fill(size, size, FilterType::Lanczos3)
+            .write_to(&mut Cursor::new(&mut encoded), ImageFormat::Png)
+            .map_err(|e| AvatarError::Invalid(e.to_string()))?;
+
+        Ok((size, encoded))
+    }).collect()
+}
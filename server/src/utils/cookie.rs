@@ -0,0 +1,26 @@
+use axum_extra::extract::cookie::Cookie;
+
+use crate::config::CookieConfig;
+
+/// A session cookie with the attributes from `CookieConfig`.
+/// Every cookie but `logged_in`, which the client checks, is hidden from scripts.
+pub fn session_cookie(config: &CookieConfig, name: &'static str, value: String, max_age: time::Duration) -> Cookie<'static> {
+    let mut cookie = Cookie::build((name, value))
+        .path("/")
+        .max_age(max_age)
+        .same_site(config.same_site.into())
+        .secure(config.secure)
+        .http_only(name != "logged_in")
+        .build();
+
+    if let Some(domain) = &config.domain {
+        cookie.set_domain(domain.clone());
+    }
+
+    cookie
+}
+
+/// A cookie that makes the browser delete the session cookie `name`.
+pub fn removal_cookie(config: &CookieConfig, name: &'static str) -> Cookie<'static> {
+    session_cookie(config, name, String::new(), time::Duration::hours(-1))
+}
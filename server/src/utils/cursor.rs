@@ -0,0 +1,61 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+/// Page size used when the client doesn't ask for one.
+pub const DEFAULT_PAGE_LIMIT: i64 = 20;
+/// Largest page size a client can ask for.
+pub const MAX_PAGE_LIMIT: i64 = 50;
+
+/// Query string of cursor paginated endpoints. `cursor` is the `next_cursor` of the previous page.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    /// `next_cursor` of the previous page, left out for the first page
+    pub cursor: Option<String>,
+    /// Page size, 20 by default and at most 50
+    pub limit: Option<i64>,
+}
+
+impl PageQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT)
+    }
+}
+
+/// Position in a list ordered newest first by timestamp, with the id breaking ties.
+/// Opaque to clients, who only pass back what they were given.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor {
+    pub at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.at.timestamp_micros(), self.id))
+    }
+
+    /// Returns `None` for anything that isn't a cursor we handed out.
+    pub fn decode(cursor: &str) -> Option<Cursor> {
+        let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        let (micros, id) = decoded.split_once(':')?;
+
+        Some(Cursor {
+            at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: Uuid::parse_str(id).ok()?,
+        })
+    }
+}
+
+/// Splits off the extra row fetched past `limit` and returns the cursor of the next page if there is one.
+pub fn next_page<T>(rows: &mut Vec<T>, limit: i64, cursor: impl Fn(&T) -> Cursor) -> Option<String> {
+    if rows.len() as i64 <= limit {
+        return None;
+    }
+
+    rows.truncate(limit as usize);
+    rows.last().map(|row| cursor(row).encode())
+}
@@ -0,0 +1,17 @@
+use std::io::{Cursor, Write};
+
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+/// Packs the given files into a deflated ZIP archive held in memory.
+/// Each entry is a file name and its contents.
+pub fn zip_files(files: &[(&str, Vec<u8>)]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, contents) in files {
+        zip.start_file(*name, options)?;
+        zip.write_all(contents)?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
@@ -1,24 +1,94 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, Error as PasswordHashError, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version
+};
 use bcrypt;
-use common::schema::feedback::ErrorResponse;
-
-// Hashes a string value, used for hashing passwords
-pub fn hash(s: &String) -> Result<String, ErrorResponse>{
-    let hashed_password = bcrypt::hash(s, 4)
-        .map_err(|e| {
-            let error_response = ErrorResponse {
-                status: "fail".to_string(),
-                message: e.to_string(),
-            };
-            
-            error_response
-        })?;
+
+use crate::{config::PasswordConfig, error::AppError};
+
+fn hash_error(message: String) -> AppError {
+    AppError::Internal(format!("Password hashing failed: {}", message))
+}
+
+// Builds an Argon2id hasher from the configured parameters
+fn argon2(config: &PasswordConfig) -> Result<Argon2<'static>, AppError> {
+    let params = Params::new(config.memory_kib, config.iterations, config.parallelism, None)
+        .map_err(|e| hash_error(e.to_string()))?;
+
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
+
+// Legacy hashes were created with bcrypt before Argon2id became the default
+fn is_bcrypt(hash: &str) -> bool {
+    hash.starts_with("$2a$") || hash.starts_with("$2b$") || hash.starts_with("$2y$")
+}
+
+// Hashes a string value with Argon2id, used for hashing passwords.
+// Hashing is slow on purpose, so it runs on the blocking thread pool instead of holding up other requests.
+pub async fn hash(s: &str, config: &PasswordConfig) -> Result<String, AppError> {
+    let (s, config) = (s.to_string(), config.clone());
+
+    tokio::task::spawn_blocking(move || hash_blocking(&s, &config))
+        .await
+        .map_err(|e| hash_error(e.to_string()))?
+}
+
+fn hash_blocking(s: &str, config: &PasswordConfig) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    let hashed_password = argon2(config)?
+        .hash_password(s.as_bytes(), &salt)
+        .map_err(|e| hash_error(e.to_string()))?
+        .to_string();
 
     Ok(hashed_password)
 }
 
-// Verifies a string value against a hashed value
-pub fn verify(password: &str, hash: &str) -> bool {
-    let parshed_hash = bcrypt::verify(password, hash).unwrap();
+// Verifies a string value against a hashed value, accepting both Argon2 and legacy bcrypt hashes.
+// Returns an error only if the stored hash is malformed. Runs on the blocking thread pool, like `hash`.
+pub async fn verify(password: &str, hash: &str) -> Result<bool, AppError> {
+    let (password, hash) = (password.to_string(), hash.to_string());
+
+    tokio::task::spawn_blocking(move || verify_blocking(&password, &hash))
+        .await
+        .map_err(|e| hash_error(e.to_string()))?
+}
+
+fn verify_blocking(password: &str, hash: &str) -> Result<bool, AppError> {
+    if is_bcrypt(hash) {
+        return bcrypt::verify(password, hash).map_err(|e| hash_error(e.to_string()));
+    }
+
+    let parsed_hash = PasswordHash::new(hash).map_err(|e| hash_error(e.to_string()))?;
 
-    parshed_hash
-}
\ No newline at end of file
+    // The parameters are read from the hash itself, so older Argon2 hashes keep verifying
+    match Argon2::default().verify_password(password.as_bytes(), &parsed_hash) {
+        Ok(()) => Ok(true),
+        Err(PasswordHashError::Password) => Ok(false),
+        Err(e) => Err(hash_error(e.to_string())),
+    }
+}
+
+// Whether a stored hash was created with a different algorithm or parameters than currently configured
+pub fn needs_rehash(hash: &str, config: &PasswordConfig) -> bool {
+    if is_bcrypt(hash) {
+        return true;
+    }
+
+    let Ok(parsed_hash) = PasswordHash::new(hash) else {
+        return true;
+    };
+
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident() {
+        return true;
+    }
+
+    match Params::try_from(&parsed_hash) {
+        Ok(params) => {
+            params.m_cost() != config.memory_kib
+                || params.t_cost() != config.iterations
+                || params.p_cost() != config.parallelism
+        }
+        Err(_) => true,
+    }
+}
@@ -1,27 +1,23 @@
 use std::sync::Arc;
-use chrono::{
-    prelude::*,
-    Duration
-};
+use chrono::prelude::*;
 
 use axum::{
-    async_trait, body::Body, extract::{FromRef, FromRequestParts, Request, State}, http::{header, request::Parts, HeaderMap, StatusCode}, middleware::Next, response::IntoResponse, Json, RequestPartsExt
+    async_trait, extract::{FromRef, FromRequestParts}, http::request::Parts, RequestPartsExt
 };
 
 use axum_extra::{
-    extract::cookie::CookieJar, headers::{authorization::Bearer, Authorization}, TypedHeader
+    headers::{authorization::Bearer, Authorization}, TypedHeader
 };
-use jsonwebtoken::{decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use jsonwebtoken::{decode, decode_header, encode, DecodingKey, Header, Validation};
 use serde::{Serialize, Deserialize};
-use serde_json::{ Value, json };
 
 use crate::{
+    config::JwtConfig,
     model::Users,
-    utils::api_error::ApiError,
-    AppState,
-    config::Config
+    error::AppError,
+    utils::keys::JwtKeys,
+    AppState
 };
-use common::schema::feedback::ErrorResponse;
 use uuid::Uuid;
 
 #[derive(Debug, Serialize)]
@@ -53,23 +49,6 @@ pub enum AuthError {
     TokenCreation
 }
 
-impl From<AuthError> for ApiError {
-    fn from(err: AuthError) -> Self {
-        let (status_code, message) = match err {
-            AuthError::InvalidToken => (StatusCode::BAD_REQUEST, "Invalid token"),
-            AuthError::WrongCredentials => (StatusCode::UNAUTHORIZED, "Wrong credentials"),
-            AuthError::MissingCredentials => (StatusCode::BAD_REQUEST, "Missing credentials"),
-            AuthError::TokenCreation => (StatusCode::INTERNAL_SERVER_ERROR, "Failed to create token"),
-        };
-
-        ApiError {
-            status_code,
-            message: message.to_owned(),
-            error_code: None
-        }
-    }
-}
-
 /// [JWT Claims]
 /// [RFC7519](https://datatracker.ietf.org/doc/html/rfc7519#section-4)
 /// ToDo: implement role based validation: is_role(admin)
@@ -108,6 +87,26 @@ pub struct RefreshClaims {
     // pub role: String
 }
 
+/// Audience of the short-lived token handed out between the password and 2FA steps of a login.
+/// Access and refresh tokens carry no audience, so validation rejects a challenge token used in their place.
+pub const CHALLENGE_AUDIENCE: &str = "2fa-challenge";
+/// Lifetime of a 2FA challenge token in seconds.
+const CHALLENGE_MAXAGE: i64 = 300;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChallengeClaims {
+    /// The "exp" (expiration time) claim identifies the expiration time on or after which the JWT MUST NOT be accepted for processing.
+    pub exp: usize,
+    /// The "iat" (issued at) claim identifies the time at which the JWT was issued.
+    pub iat: usize,
+    /// The "sub" (subject) claim identifies the principal that is the subject of the JWT.
+    pub sub: String,
+    /// The "jti" (JWT ID) claim provides a unique identifier for the JWT.
+    pub jti: String,
+    /// The "aud" (audience) claim, always `CHALLENGE_AUDIENCE`.
+    pub aud: String,
+}
+
 pub trait ClaimsMethod {
     // fn validate_role(&self) -> Result<(), AuthError>;
     fn get_sub(&self) -> &str;
@@ -182,7 +181,7 @@ where
     Arc<AppState>: FromRef<S>,
     S: Send + Sync
 {
-    type Rejection = ApiError;
+    type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         decode_token_from_request_parts::<S, AccessClaims>(parts, state).await
@@ -195,7 +194,7 @@ where
     Arc<AppState>: FromRef<S>,
     S: Send + Sync
 {
-    type Rejection = ApiError;
+    type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         decode_token_from_request_parts::<S, RefreshClaims>(parts, state).await
@@ -205,7 +204,7 @@ where
 async fn decode_token_from_request_parts<S, T>(
     parts: &mut Parts,
     state: &S
-) -> Result<T, ApiError>
+) -> Result<T, AppError>
 where
     Arc<AppState>: FromRef<S>,
     S: Send + Sync,
@@ -220,25 +219,45 @@ where
         })?;
 
     // decode the token
-    let claims = decode_token::<T>(bearer.token())?;
+    let app_state = Arc::<AppState>::from_ref(state);
+    let claims = decode_token::<T>(&app_state.keys, bearer.token())?;
 
     Ok(claims)
 }
 
+/// Picks the verification key by the token's `kid`, so tokens signed with a key that has since
+/// been rotated out of active use stay valid until they expire.
+fn validation_for<'a>(keys: &'a JwtKeys, token: &str) -> Result<(&'a DecodingKey, Validation), AuthError> {
+    let header = decode_header(token).map_err(|_| AuthError::InvalidToken)?;
+    let key = keys.get(header.kid.as_deref()).ok_or(AuthError::InvalidToken)?;
+
+    // The algorithm comes from our key, never from the token header
+    Ok((key.decoding_key(), Validation::new(key.algorithm)))
+}
+
 pub fn decode_token<T: for<'de> Deserialize<'de>>(
+    keys: &JwtKeys,
     token: &str
 ) -> Result<T, AuthError> {
-    let config = Config::init();
-
-    let mut validation = Validation::new(Algorithm::HS256);
+    let (decoding_key, validation) = validation_for(keys, token)?;
     // validation.leeway = config.jwt_validation_leeway as u64;
 
-    let token_data = decode::<T>(token, &DecodingKey::from_secret(config.jwt_secret.as_ref()), &validation)
+    let token_data = decode::<T>(token, decoding_key, &validation)
         .map_err(|_| AuthError::WrongCredentials)?;
 
     Ok(token_data.claims)
 }
 
+/// Signs claims with the active key, naming it in the `kid` header.
+fn sign<T: Serialize>(keys: &JwtKeys, claims: &T) -> String {
+    let key = keys.active();
+
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
+
+    encode(&header, claims, key.encoding_key()).unwrap()
+}
+
 pub struct JwtTokens {
     pub access_token: String,
     pub refresh_token: String,
@@ -259,16 +278,14 @@ pub struct JwtTokens {
 //         .map_err(|_| { StatusCode::INTERNAL_SERVER_ERROR })
 // }
 
-pub fn generate_tokens(user: Users) -> JwtTokens {
-    let config = Config::init();
-
+pub fn generate_tokens(keys: &JwtKeys, config: &JwtConfig, user: Users) -> JwtTokens {
     let now = Utc::now();
     let iat = now.timestamp() as usize;
     let sub = user.user_id.to_string();
 
     let access_token_id = Uuid::new_v4().to_string();
     let refresh_token_id = Uuid::new_v4().to_string();
-    let access_token_exp = (now + Duration::seconds(config.jwt_maxage.into())).timestamp() as usize;
+    let access_token_exp = (now.timestamp() + config.access_token_maxage) as usize;
 
     let access_token_claims = AccessClaims {
         iat,
@@ -281,22 +298,13 @@ pub fn generate_tokens(user: Users) -> JwtTokens {
         sub,
         jti: refresh_token_id,
         iat,
-        exp: (now + Duration::seconds(config.jwt_maxage.into())).timestamp() as usize,
+        exp: (now.timestamp() + config.refresh_token_maxage) as usize,
         prf: access_token_id,
         pex: access_token_exp
     };
 
-    let access_token = encode(
-        &Header::default(),
-        &access_token_claims,
-        &EncodingKey::from_secret(config.jwt_secret.as_ref())
-    ).unwrap();
-
-    let refresh_token = encode(
-        &Header::default(),
-        &refresh_claims,
-        &EncodingKey::from_secret(config.jwt_secret.as_ref())
-    ).unwrap();
+    let access_token = sign(keys, &access_token_claims);
+    let refresh_token = sign(keys, &refresh_claims);
 
     JwtTokens {
         access_token,
@@ -304,6 +312,30 @@ pub fn generate_tokens(user: Users) -> JwtTokens {
     }
 }
 
+/// Issues the challenge token returned by login when the user still has to enter a 2FA code.
+pub fn generate_challenge_token(keys: &JwtKeys, user: &Users) -> String {
+    let now = Utc::now();
+    let claims = ChallengeClaims {
+        iat: now.timestamp() as usize,
+        exp: (now.timestamp() + CHALLENGE_MAXAGE) as usize,
+        sub: user.user_id.to_string(),
+        jti: Uuid::new_v4().to_string(),
+        aud: CHALLENGE_AUDIENCE.to_string(),
+    };
+
+    sign(keys, &claims)
+}
+
+pub fn decode_challenge_token(keys: &JwtKeys, token: &str) -> Result<ChallengeClaims, AuthError> {
+    let (decoding_key, mut validation) = validation_for(keys, token)?;
+    validation.set_audience(&[CHALLENGE_AUDIENCE]);
+
+    let token_data = decode::<ChallengeClaims>(token, decoding_key, &validation)
+        .map_err(|_| AuthError::InvalidToken)?;
+
+    Ok(token_data.claims)
+}
+
 // pub fn decode_jwt(headers: &HeaderMap) -> Result<Option<TokenData<AccessClaims>>, (StatusCode, Json<Value>)> {
 //     // Retrieve the token from the map of request headers
 //     let token_header = headers.get("Authorization");
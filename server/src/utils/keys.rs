@@ -0,0 +1,186 @@
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::pkcs8::DecodePrivateKey;
+use jsonwebtoken::{
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType
+    },
+    Algorithm, DecodingKey, EncodingKey
+};
+use rsa::{pkcs1::DecodeRsaPrivateKey, traits::PublicKeyParts, RsaPrivateKey};
+
+use crate::config::JwtConfig;
+
+/// Key id given to the shared `JWT_SECRET`. Tokens issued before key ids were introduced
+/// carry no `kid` and are verified with this key.
+pub const SECRET_KID: &str = "hs256";
+
+/// A single key tokens can be signed and verified with.
+pub struct JwtKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    /// Public half of the key as published in the JWKS, `None` for the shared secret
+    jwk: Option<Jwk>,
+}
+
+impl JwtKey {
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    pub fn decoding_key(&self) -> &DecodingKey {
+        &self.decoding_key
+    }
+
+    fn from_secret(secret: &str) -> JwtKey {
+        JwtKey {
+            kid: SECRET_KID.to_string(),
+            algorithm: Algorithm::HS256,
+            encoding_key: EncodingKey::from_secret(secret.as_ref()),
+            decoding_key: DecodingKey::from_secret(secret.as_ref()),
+            jwk: None,
+        }
+    }
+
+    /// Loads an RSA (PKCS#1 or PKCS#8) or Ed25519 (PKCS#8) private key from PEM.
+    fn from_pem(kid: &str, pem: &str) -> Result<JwtKey, String> {
+        let rsa_key = RsaPrivateKey::from_pkcs1_pem(pem).ok()
+            .or_else(|| <RsaPrivateKey as rsa::pkcs8::DecodePrivateKey>::from_pkcs8_pem(pem).ok());
+
+        if let Some(rsa_key) = rsa_key {
+            let n = URL_SAFE_NO_PAD.encode(rsa_key.n().to_bytes_be());
+            let e = URL_SAFE_NO_PAD.encode(rsa_key.e().to_bytes_be());
+
+            return Ok(JwtKey {
+                kid: kid.to_string(),
+                algorithm: Algorithm::RS256,
+                encoding_key: EncodingKey::from_rsa_pem(pem.as_bytes()).map_err(|e| e.to_string())?,
+                decoding_key: DecodingKey::from_rsa_components(&n, &e).map_err(|e| e.to_string())?,
+                jwk: Some(public_jwk(kid, KeyAlgorithm::RS256, AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n,
+                    e,
+                }))),
+            });
+        }
+
+        let ed_key = ed25519_dalek::SigningKey::from_pkcs8_pem(pem)
+            .map_err(|_| format!("Key {} is not an RSA or Ed25519 private key", kid))?;
+        let x = URL_SAFE_NO_PAD.encode(ed_key.verifying_key().as_bytes());
+
+        Ok(JwtKey {
+            kid: kid.to_string(),
+            algorithm: Algorithm::EdDSA,
+            encoding_key: EncodingKey::from_ed_pem(pem.as_bytes()).map_err(|e| e.to_string())?,
+            decoding_key: DecodingKey::from_ed_components(&x).map_err(|e| e.to_string())?,
+            jwk: Some(public_jwk(kid, KeyAlgorithm::EdDSA, AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x,
+            }))),
+        })
+    }
+}
+
+fn public_jwk(kid: &str, algorithm: KeyAlgorithm, algorithm_parameters: AlgorithmParameters) -> Jwk {
+    Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: algorithm_parameters,
+    }
+}
+
+/// Every key tokens are currently accepted from, keyed by `kid`.
+///
+/// New tokens are signed with the active key only. To rotate, add a new key file and make it
+/// active, then delete the old file once the tokens it signed have expired (`JWT_REFRESH_MAXAGE`).
+pub struct JwtKeys {
+    active_kid: String,
+    keys: HashMap<String, JwtKey>,
+}
+
+impl fmt::Debug for JwtKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut kids = self.keys.keys().collect::<Vec<_>>();
+        kids.sort();
+
+        f.debug_struct("JwtKeys")
+            .field("active_kid", &self.active_kid)
+            .field("kids", &kids)
+            .finish()
+    }
+}
+
+impl JwtKeys {
+    /// Loads every `<kid>.pem` private key in `JWT_KEYS_DIR`, plus `JWT_SECRET` if it is set.
+    ///
+    /// The active key is `JWT_ACTIVE_KID`, or else the last PEM key by file name, so naming key
+    /// files by date rotates to the newest one. Without any PEM keys tokens are signed with the secret.
+    pub fn from_config(config: &JwtConfig) -> Result<JwtKeys, String> {
+        let mut keys = HashMap::new();
+
+        let dir = Path::new(&config.keys_dir);
+        if dir.is_dir() {
+            let entries = fs::read_dir(dir)
+                .map_err(|e| format!("Failed to read {}: {}", config.keys_dir, e))?;
+
+            for entry in entries {
+                let path = entry.map_err(|e| e.to_string())?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("pem") {
+                    continue;
+                }
+
+                let kid = path.file_stem().and_then(|stem| stem.to_str())
+                    .ok_or_else(|| format!("Invalid key file name {}", path.display()))?;
+                let pem = fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+                keys.insert(kid.to_string(), JwtKey::from_pem(kid, &pem)?);
+            }
+        }
+
+        let newest_kid = keys.keys().max().cloned();
+
+        if !config.secret.is_empty() {
+            keys.insert(SECRET_KID.to_string(), JwtKey::from_secret(&config.secret));
+        }
+
+        let active_kid = config.active_kid.clone()
+            .or(newest_kid)
+            .unwrap_or_else(|| SECRET_KID.to_string());
+
+        if !keys.contains_key(&active_kid) {
+            return Err(format!("Active JWT key {} not found, set JWT_SECRET or add keys to {}", active_kid, config.keys_dir));
+        }
+
+        Ok(JwtKeys { active_kid, keys })
+    }
+
+    /// The key new tokens are signed with.
+    pub fn active(&self) -> &JwtKey {
+        &self.keys[&self.active_kid]
+    }
+
+    /// Looks up the key a token was signed with by the `kid` in its header.
+    pub fn get(&self, kid: Option<&str>) -> Option<&JwtKey> {
+        self.keys.get(kid.unwrap_or(SECRET_KID))
+    }
+
+    /// The public keys, for other services to verify tokens with. The shared secret is never published.
+    pub fn jwks(&self) -> JwkSet {
+        let mut keys = self.keys.values()
+            .filter_map(|key| key.jwk.clone())
+            .collect::<Vec<_>>();
+        keys.sort_by(|a, b| a.common.key_id.cmp(&b.common.key_id));
+
+        JwkSet { keys }
+    }
+}
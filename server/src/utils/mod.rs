@@ -1,3 +1,13 @@
 pub mod hash;
 pub mod jwt;
-pub mod api_error;
\ No newline at end of file
+pub mod keys;
+pub mod token;
+pub mod api_token;
+pub mod totp;
+pub mod avatar;
+pub mod cursor;
+pub mod privacy;
+pub mod export;
+pub mod validated_json;
+pub mod cookie;
+pub mod stream;
\ No newline at end of file
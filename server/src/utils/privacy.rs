@@ -0,0 +1,33 @@
+use common::schema::social::Visibility;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::model::Users;
+
+/// Whether `viewer` may see the part of `owner_id`'s activity guarded by `visibility`.
+/// Logged out viewers only see what is public, and users always see their own activity.
+/// Anyone can follow anyone, so `Followers` is not a private setting, only `Private` is.
+pub async fn can_view(
+    db: &Pool<Postgres>,
+    visibility: Visibility,
+    owner_id: Uuid,
+    viewer: Option<&Users>,
+) -> Result<bool, sqlx::Error> {
+    if viewer.is_some_and(|viewer| viewer.user_id == owner_id) {
+        return Ok(true);
+    }
+
+    match (visibility, viewer) {
+        (Visibility::Public, _) => Ok(true),
+        (Visibility::Followers, Some(viewer)) => {
+            sqlx::query_scalar!(
+                r#"SELECT EXISTS(SELECT 1 FROM follows WHERE follower_id = $1 AND followee_id = $2) AS "following!""#,
+                viewer.user_id,
+                owner_id
+            )
+            .fetch_one(db)
+            .await
+        }
+        _ => Ok(false),
+    }
+}
@@ -0,0 +1,117 @@
+//! Serves stored blobs the way media elements expect, with `Range` requests so they can seek
+//! and validators so they can cache.
+
+use std::ops::Range;
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response}
+};
+
+use crate::{error::AppError, storage::BlobStore};
+
+/// What part of the blob a request asks for.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ByteRange {
+    Full,
+    Partial(Range<u64>),
+    /// Starts after the end of the blob, answered with 416
+    Unsatisfiable,
+}
+
+/// Parses a `Range` header against a blob of `size` bytes.
+///
+/// Only a single range in bytes is supported. Anything else, including malformed headers, is
+/// answered with the whole blob, which RFC 9110 allows.
+pub fn parse_range(header: &str, size: u64) -> ByteRange {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return ByteRange::Full;
+    };
+    let Some((start, end)) = spec.trim().split_once('-').filter(|_| !spec.contains(',')) else {
+        return ByteRange::Full;
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        // `bytes=-500` is the last 500 bytes
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(suffix) => size.saturating_sub(suffix)..size,
+            Err(_) => return ByteRange::Full,
+        },
+        (start, "") => match start.parse::<u64>() {
+            Ok(start) => start..size,
+            Err(_) => return ByteRange::Full,
+        },
+        (start, end) => match (start.parse::<u64>(), end.parse::<u64>()) {
+            (Ok(start), Ok(end)) if start <= end => start..(end + 1).min(size),
+            _ => return ByteRange::Full,
+        },
+    };
+
+    if range.start >= size {
+        return ByteRange::Unsatisfiable;
+    }
+
+    ByteRange::Partial(range)
+}
+
+/// Responds with the blob stored under `key`, or the part of it the `Range` header asks for.
+///
+/// The `ETag` changes whenever the blob is replaced, so `If-None-Match` is answered with 304 and
+/// `If-Range` with the whole blob if it changed since the client fetched the first part.
+pub async fn serve_blob(
+    storage: &dyn BlobStore,
+    key: &str,
+    content_type: &str,
+    cache_control: &str,
+    headers: &HeaderMap,
+) -> Result<Response, AppError> {
+    let meta = storage
+        .head(key)
+        .await?
+        .ok_or_else(|| AppError::Internal(format!("Blob {} is missing from storage", key)))?;
+    let etag = format!("\"{:x}-{:x}\"", meta.size, meta.last_modified.timestamp_millis());
+
+    let header_str = |name| headers.get(name).and_then(|value: &HeaderValue| value.to_str().ok());
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::ETAG, HeaderValue::from_str(&etag).unwrap());
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_str(cache_control).unwrap());
+
+    if header_str(header::IF_NONE_MATCH).is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*")) {
+        return Ok((StatusCode::NOT_MODIFIED, response_headers).into_response());
+    }
+
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    response_headers.insert(
+        header::LAST_MODIFIED,
+        HeaderValue::from_str(&meta.last_modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string()).unwrap(),
+    );
+
+    // A range of an older version of the blob is useless, send all of the current one instead
+    let range = match (header_str(header::RANGE), header_str(header::IF_RANGE)) {
+        (Some(range), None) => parse_range(range, meta.size),
+        (Some(range), Some(if_range)) if if_range == etag => parse_range(range, meta.size),
+        _ => ByteRange::Full,
+    };
+
+    let (status, range) = match range {
+        ByteRange::Full => (StatusCode::OK, 0..meta.size),
+        ByteRange::Partial(range) => {
+            let content_range = format!("bytes {}-{}/{}", range.start, range.end - 1, meta.size);
+            response_headers.insert(header::CONTENT_RANGE, HeaderValue::from_str(&content_range).unwrap());
+            (StatusCode::PARTIAL_CONTENT, range)
+        }
+        ByteRange::Unsatisfiable => {
+            let content_range = format!("bytes */{}", meta.size);
+            response_headers.insert(header::CONTENT_RANGE, HeaderValue::from_str(&content_range).unwrap());
+            return Ok((StatusCode::RANGE_NOT_SATISFIABLE, response_headers).into_response());
+        }
+    };
+
+    response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_str(content_type).unwrap());
+    response_headers.insert(header::CONTENT_LENGTH, (range.end - range.start).into());
+    let body = if range.is_empty() { Body::empty() } else { storage.get_range(key, range).await? };
+
+    Ok((status, response_headers, body).into_response())
+}
@@ -0,0 +1,78 @@
+use chrono::{Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::model::TokenPurpose;
+
+/// Generates a random, URL-safe token to be sent to the user.
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(48)
+        .map(char::from)
+        .collect()
+}
+
+/// Hashes a token so only the digest is ever stored in the database.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Creates a new single-use token for the user, replacing any unused token with the same purpose.
+/// Returns the raw token, which is only ever handed to the user.
+pub async fn issue_token(
+    db: &Pool<Postgres>,
+    user_id: Uuid,
+    purpose: TokenPurpose,
+    ttl: Duration,
+) -> Result<String, sqlx::Error> {
+    let token = generate_token();
+
+    sqlx::query!(
+        "DELETE FROM user_tokens WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL",
+        user_id,
+        purpose.clone() as TokenPurpose
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO user_tokens (token_id, user_id, purpose, token_hash, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        purpose as TokenPurpose,
+        hash_token(&token),
+        Utc::now() + ttl
+    )
+    .execute(db)
+    .await?;
+
+    Ok(token)
+}
+
+/// Marks a token as used and returns the owning user's id.
+/// Returns `None` if the token is unknown, expired or has already been used.
+pub async fn consume_token(
+    db: &Pool<Postgres>,
+    token: &str,
+    purpose: TokenPurpose,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    let user_id = sqlx::query_scalar!(
+        r#"
+        UPDATE user_tokens SET used_at = NOW()
+        WHERE token_hash = $1 AND purpose = $2 AND used_at IS NULL AND expires_at > NOW()
+        RETURNING user_id
+        "#,
+        hash_token(token),
+        purpose as TokenPurpose
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(user_id)
+}
@@ -0,0 +1,66 @@
+use chrono::Utc;
+use rand::{distributions::Alphanumeric, Rng};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+/// Issuer shown in authenticator apps.
+const ISSUER: &str = "Rusty Melody";
+/// Length of a TOTP time step in seconds.
+const STEP_SECONDS: u64 = 30;
+/// Number of recovery codes handed out at a time.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Generates a new random base32-encoded TOTP secret.
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+fn totp(secret: &str, account: &str) -> Result<TOTP, String> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| format!("Invalid TOTP secret: {:?}", e))?;
+
+    TOTP::new(Algorithm::SHA1, 6, 1, STEP_SECONDS, secret, Some(ISSUER.to_string()), account.to_string())
+        .map_err(|e| format!("Invalid TOTP configuration: {}", e))
+}
+
+/// The `otpauth://` URI an authenticator app uses to add the account.
+pub fn otpauth_uri(secret: &str, account: &str) -> Result<String, String> {
+    Ok(totp(secret, account)?.get_url())
+}
+
+/// Checks a code against the current time step, allowing one step of clock skew either way.
+/// Codes from `last_step` or earlier are rejected so a code can't be used twice.
+/// Returns the matched time step, which should be stored as the new `last_step`.
+pub fn verify_code(secret: &str, account: &str, code: &str, last_step: Option<i64>) -> Result<Option<i64>, String> {
+    let totp = totp(secret, account)?;
+    let code = code.trim();
+    let current_step = Utc::now().timestamp() / STEP_SECONDS as i64;
+
+    let matched_step = (current_step - 1..=current_step + 1)
+        .filter(|step| !matches!(last_step, Some(last_step) if *step <= last_step))
+        .find(|step| totp.generate(*step as u64 * STEP_SECONDS) == code);
+
+    Ok(matched_step)
+}
+
+/// Generates a fresh set of single-use recovery codes, formatted as `xxxxx-xxxxx`.
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code: String = (&mut rng)
+                .sample_iter(&Alphanumeric)
+                .take(10)
+                .map(|c| char::from(c).to_ascii_lowercase())
+                .collect();
+
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Normalizes user-entered recovery codes so case and the dash don't matter before hashing.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.trim().to_ascii_lowercase().replace('-', "")
+}
@@ -0,0 +1,31 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    Json
+};
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+use crate::error::AppError;
+
+/// Like `Json`, but also runs the body's `Validate` rules before the handler sees it.
+/// Failures are rejected as `AppError::Validation`, which lists the messages by field
+/// so the client can show them next to its inputs. Use it for every JSON body.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        value.validate()?;
+
+        Ok(ValidatedJson(value))
+    }
+}
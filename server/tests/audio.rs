@@ -0,0 +1,272 @@
+mod support;
+
+use std::f32::consts::PI;
+
+use common::schema::error::ErrorCode;
+use reqwest::{header, StatusCode};
+use server::{config::StorageConfig, storage};
+use support::{factories::{create_admin, create_album, create_artist, create_song, create_user}, TestApp};
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// A 16-bit mono WAV of an A (440 Hz) with a click on every beat at `bpm`.
+fn click_track(bpm: f32, seconds: u32) -> Vec<u8> {
+    let beat = (60.0 / bpm * SAMPLE_RATE as f32) as usize;
+    let click = SAMPLE_RATE as usize / 50;
+    // A fixed seed keeps the noise of the clicks the same on every run
+    let mut seed = 1u32;
+
+    let samples = (0..(SAMPLE_RATE * seconds) as usize).map(|i| {
+        let tone = 0.3 * (2.0 * PI * 440.0 * i as f32 / SAMPLE_RATE as f32).sin();
+        let since_beat = i % beat;
+        let noise = if since_beat < click {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let white = (seed >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0;
+            0.5 * white * (1.0 - since_beat as f32 / click as f32)
+        } else {
+            0.0
+        };
+
+        ((tone + noise).clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+    });
+
+    let data = samples.flat_map(i16::to_le_bytes).collect::<Vec<_>>();
+    let mut wav = Vec::with_capacity(44 + data.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, mono
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&data);
+
+    wav
+}
+
+#[tokio::test]
+async fn uploaded_audio_is_analyzed() {
+    let app = TestApp::spawn().await;
+    let artist = create_artist(&app, "The Metronomes").await;
+    let album = create_album(&app, artist, "Tick").await;
+    let song = create_song(&app, album, "Tock").await;
+
+    let client = app.client();
+    let user = create_admin(&app, "ada").await;
+    client.login(&user.credentials()).await.expect("Failed to log in");
+
+    let features = client
+        .upload_song_audio(song, "tock.wav", "audio/wav", click_track(120.0, 20))
+        .await
+        .expect("Failed to upload audio");
+    assert_eq!(features.duration, 20);
+    let tempo = features.tempo.expect("No tempo found");
+    assert!((tempo - 120.0).abs() < 2.0, "Expected 120 BPM, got {}", tempo);
+    assert_eq!(features.key, Some(9));
+    assert!(features.loudness > -20.0 && features.loudness < 0.0, "Unexpected loudness {}", features.loudness);
+    assert!(features.speechiness < 0.33, "Unexpected speechiness {}", features.speechiness);
+
+    let (audio_key, stored_tempo): (Option<String>, Option<f32>) =
+        sqlx::query_as("SELECT audio_key, tempo FROM songs WHERE song_id = $1")
+            .bind(song)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(audio_key, Some(format!("audio/{}.wav", song)));
+    assert_eq!(stored_tempo, Some(tempo));
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn only_the_uploader_can_replace_audio() {
+    let app = TestApp::spawn().await;
+    let artist = create_artist(&app, "The Metronomes").await;
+    let album = create_album(&app, artist, "Tick").await;
+    let song = create_song(&app, album, "Tock").await;
+
+    let uploader = app.client();
+    uploader.login(&create_admin(&app, "ada").await.credentials()).await.expect("Failed to log in");
+    let other = app.client();
+    other.login(&create_admin(&app, "grace").await.credentials()).await.expect("Failed to log in");
+
+    let error = uploader
+        .upload_song_audio(song, "tock.txt", "text/plain", b"not audio at all".to_vec())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::UnsupportedMediaType);
+
+    uploader
+        .upload_song_audio(song, "tock.wav", "audio/wav", click_track(90.0, 5))
+        .await
+        .expect("Failed to upload audio");
+
+    let error = other
+        .upload_song_audio(song, "tock.wav", "audio/wav", click_track(90.0, 5))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::Forbidden);
+
+    uploader
+        .upload_song_audio(song, "tock.wav", "audio/wav", click_track(90.0, 5))
+        .await
+        .expect("Failed to replace audio");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn only_admins_can_upload_audio_for_songs_they_did_not_add() {
+    let app = TestApp::spawn().await;
+    let artist = create_artist(&app, "The Metronomes").await;
+    let album = create_album(&app, artist, "Tick").await;
+    let catalog_song = create_song(&app, album, "Tock").await;
+    let own_song = create_song(&app, album, "Tick").await;
+
+    let user = create_user(&app, "ada").await;
+    sqlx::query("UPDATE songs SET created_by = $1 WHERE song_id = $2")
+        .bind(user.user_id)
+        .bind(own_song)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    let client = app.client();
+    client.login(&user.credentials()).await.expect("Failed to log in");
+
+    let error = client
+        .upload_song_audio(catalog_song, "tock.wav", "audio/wav", click_track(90.0, 5))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::Forbidden);
+    let (uploaded_by,): (Option<uuid::Uuid>,) = sqlx::query_as("SELECT audio_uploaded_by FROM songs WHERE song_id = $1")
+        .bind(catalog_song)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(uploaded_by, None);
+
+    client
+        .upload_song_audio(own_song, "tick.wav", "audio/wav", click_track(90.0, 5))
+        .await
+        .expect("Failed to upload audio");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn only_avatars_are_served_from_local_storage() {
+    let app = TestApp::spawn().await;
+    let artist = create_artist(&app, "The Metronomes").await;
+    let album = create_album(&app, artist, "Tick").await;
+    let song = create_song(&app, album, "Tock").await;
+
+    let client = app.client();
+    client.login(&create_admin(&app, "ada").await.credentials()).await.expect("Failed to log in");
+    client.upload_song_audio(song, "tock.wav", "audio/wav", click_track(90.0, 5)).await.expect("Failed to upload audio");
+
+    let avatars = std::path::Path::new(&app.config.storage.dir).join("avatars/ada");
+    std::fs::create_dir_all(&avatars).unwrap();
+    std::fs::write(avatars.join("64.png"), b"not really a png").unwrap();
+
+    // Without logging in, so audio has to go through the stream endpoint
+    let http = reqwest::Client::new();
+    let status = |key: String| {
+        let http = http.clone();
+        let url = format!("{}/uploads/{}", app.address, key);
+        async move { http.get(url).send().await.unwrap().status() }
+    };
+    assert_eq!(status("avatars/ada/64.png".to_string()).await, StatusCode::OK);
+    assert_eq!(status(format!("audio/{}.wav", song)).await, StatusCode::NOT_FOUND);
+    assert_eq!(status(format!("previews/{}.wav", song)).await, StatusCode::NOT_FOUND);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn only_avatars_have_a_public_url() {
+    let app = TestApp::spawn().await;
+
+    // Building the S3 store doesn't talk to the endpoint, so this needs no bucket
+    let s3 = StorageConfig {
+        backend: "s3".to_string(),
+        s3_bucket: Some("melody-public".to_string()),
+        s3_private_bucket: Some("melody-private".to_string()),
+        s3_endpoint: Some("http://localhost:9000".to_string()),
+        s3_access_key_id: Some("melody".to_string()),
+        s3_secret_access_key: Some("melody".to_string()),
+        ..app.config.storage.clone()
+    };
+
+    for config in [app.config.storage.clone(), s3] {
+        let store = storage::from_config(&config).expect("Failed to set up storage");
+        assert!(store.url("avatars/ada/64.png").is_some(), "{} avatar", config.backend);
+        assert_eq!(store.url("audio/1.wav"), None, "{} audio", config.backend);
+        assert_eq!(store.url("previews/1.wav"), None, "{} preview", config.backend);
+    }
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn audio_is_streamed_in_ranges() {
+    let app = TestApp::spawn().await;
+    let artist = create_artist(&app, "The Metronomes").await;
+    let album = create_album(&app, artist, "Tick").await;
+    let song = create_song(&app, album, "Tock").await;
+
+    let user = create_admin(&app, "ada").await;
+    let client = app.client();
+    client.login(&user.credentials()).await.expect("Failed to log in");
+    let wav = click_track(120.0, 40);
+    client.upload_song_audio(song, "tock.wav", "audio/wav", wav.clone()).await.expect("Failed to upload audio");
+
+    // A media element requests the stream itself, with the session cookies
+    let http = reqwest::Client::builder().cookie_store(true).build().unwrap();
+    let stream_url = client.song_stream_url(song);
+    assert_eq!(http.get(&stream_url).send().await.unwrap().status(), StatusCode::UNAUTHORIZED);
+    http.post(format!("{}/api/v1/auth/login", app.address))
+        .json(&user.credentials())
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .expect("Failed to log in");
+
+    let response = http.get(&stream_url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "audio/wav");
+    assert_eq!(response.headers()[header::ACCEPT_RANGES], "bytes");
+    let etag = response.headers()[header::ETAG].clone();
+    assert_eq!(response.bytes().await.unwrap(), wav);
+
+    let response = http.get(&stream_url).header(header::RANGE, "bytes=100-199").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.headers()[header::CONTENT_RANGE], format!("bytes 100-199/{}", wav.len()).as_str());
+    assert_eq!(response.bytes().await.unwrap(), wav[100..200]);
+
+    let response = http.get(&stream_url).header(header::RANGE, "bytes=-10").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.bytes().await.unwrap(), wav[wav.len() - 10..]);
+
+    let response = http.get(&stream_url).header(header::RANGE, format!("bytes={}-", wav.len())).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers()[header::CONTENT_RANGE], format!("bytes */{}", wav.len()).as_str());
+
+    let response = http.get(&stream_url).header(header::IF_NONE_MATCH, etag).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    // Previews need no login
+    let response = reqwest::get(client.song_preview_url(song)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "audio/wav");
+    let preview = server::audio::decode(response.bytes().await.unwrap().to_vec()).expect("Invalid preview");
+    assert_eq!(preview.duration.round(), 30.0);
+
+    app.cleanup().await;
+}
@@ -0,0 +1,129 @@
+mod support;
+
+use support::{factories::{create_album, create_artist, create_song, create_user, PASSWORD}, link_token, TestApp};
+use common::schema::{
+    error::ErrorCode,
+    user::{LoginResponse, LoginUserSchema, SignupUserSchema, VerifyEmailSchema}
+};
+
+#[tokio::test]
+async fn register_login_refresh_and_logout() {
+    let app = TestApp::spawn().await;
+    let client = app.client();
+
+    let user = client
+        .register(&SignupUserSchema {
+            name: "Ada Lovelace".to_string(),
+            email: "ada@example.com".to_string(),
+            password: PASSWORD.to_string(),
+            password_confirm: PASSWORD.to_string(),
+            username: "ada".to_string(),
+            ..Default::default()
+        })
+        .await
+        .expect("Failed to register");
+    assert_eq!(user.username, "ada");
+
+    let credentials = LoginUserSchema { email: "ada@example.com".to_string(), password: PASSWORD.to_string() };
+    let error = client.login(&credentials).await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::EmailNotVerified);
+
+    let email = app.mailer.last_to("ada@example.com").expect("No verification email sent");
+    client.verify_email(&VerifyEmailSchema { token: link_token(&email) }).await.expect("Failed to verify email");
+
+    let login = client.login(&credentials).await.expect("Failed to log in");
+    assert!(matches!(login, LoginResponse::Success(_)));
+    assert_eq!(client.user_info().await.expect("Failed to get user info").user_id, user.user_id);
+
+    client.refresh().await.expect("Failed to refresh");
+    assert_eq!(client.user_info().await.expect("Failed to get user info after refreshing").user_id, user.user_id);
+
+    client.logout().await.expect("Failed to log out");
+    let error = client.user_info().await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::Unauthenticated);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn login_rejects_wrong_password() {
+    let app = TestApp::spawn().await;
+    let user = create_user(&app, "grace").await;
+
+    let error = app.client().login(&LoginUserSchema { password: "Wrong-Password-1".to_string(), ..user.credentials() }).await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::InvalidCredentials);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn login_responds_with_json_and_sets_cookies() {
+    let app = TestApp::spawn().await;
+    let user = create_user(&app, "grace").await;
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/v1/auth/login", app.address))
+        .json(&user.credentials())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(response.headers()[reqwest::header::CONTENT_TYPE], "application/json");
+    assert_eq!(response.headers().get_all(reqwest::header::SET_COOKIE).iter().count(), 3);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn liking_songs_requires_a_session() {
+    let app = TestApp::spawn().await;
+    let client = app.client();
+
+    let error = client.user_info().await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::Unauthenticated);
+
+    let artist = create_artist(&app, "The Analytical Engines").await;
+    let album = create_album(&app, artist, "Notes").await;
+    let song = create_song(&app, album, "Note G").await;
+    let error = client.like_song(song).await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::Unauthenticated);
+
+    let user = create_user(&app, "grace").await;
+    client.login(&user.credentials()).await.expect("Failed to log in");
+    client.like_song(song).await.expect("Failed to like song");
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn rate_limit_ignores_forged_client_ips() {
+    let app = TestApp::spawn().await;
+    let http = reqwest::Client::new();
+    let refresh = format!("{}/api/v1/auth/refresh", app.address);
+
+    // Without a trusted proxy every request counts against the connection's address
+    for attempt in 0..app.config.rate_limit.auth.burst {
+        let response = http.post(&refresh).header("X-Real-IP", format!("10.0.0.{}", attempt)).send().await.unwrap();
+        assert_ne!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    let response = http.post(&refresh).header("X-Real-IP", "10.0.1.1").send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn unversioned_docs_urls_redirect_to_v1() {
+    let app = TestApp::spawn().await;
+    let http = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none()).build().unwrap();
+
+    for (old, new) in [("/api/openapi.json", "/api/v1/openapi.json"), ("/api/docs", "/api/v1/docs")] {
+        let response = http.get(format!("{}{}", app.address, old)).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(response.headers()[reqwest::header::LOCATION], new);
+        assert_eq!(response.headers()["Deprecation"], "true");
+    }
+
+    app.cleanup().await;
+}
@@ -0,0 +1,89 @@
+mod support;
+
+use common::schema::{error::ErrorCode, platform::Platform};
+use server::catalog::{dataset::Format, import};
+use support::{factories::{create_album, create_artist, create_song}, TestApp};
+
+const DATASET: &str = "\
+track_name,artists,album_name,duration_ms,track_genre,tempo,key
+Comedy,Gen Hoshino,Comedy,230666,pop,87.917,1
+Ghost - Acoustic,Ben Woodward,Ghost (Acoustic),149610,hip-hop,77.489,-1
+comedy!,Gen  Hoshino,Comedy,230666,pop,87.917,1
+Unknown Genre,Someone,Album,1000,acoustic,,
+";
+
+#[tokio::test]
+async fn import_deduplicates_and_rejects_unknown_genres() {
+    let app = TestApp::spawn().await;
+    let path = std::env::temp_dir().join(format!("{}.csv", uuid::Uuid::new_v4()));
+    std::fs::write(&path, DATASET).unwrap();
+
+    let report = import(&app.db, &path, Format::Csv, false).await.expect("Failed to import");
+    assert_eq!((report.songs, report.artists, report.albums, report.duplicates), (2, 2, 2, 1));
+    assert_eq!(report.rejected.len(), 1);
+    assert_eq!(report.rejected[0].row, 4);
+
+    let genres = sqlx::query_scalar::<_, String>("SELECT genre::text FROM songs ORDER BY title")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(genres, ["Pop", "Hip-Hop"]);
+
+    let again = import(&app.db, &path, Format::Csv, false).await.expect("Failed to import again");
+    assert_eq!((again.songs, again.duplicates), (0, 3));
+
+    std::fs::remove_file(&path).unwrap();
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn songs_artists_and_albums_can_be_looked_up() {
+    let app = TestApp::spawn().await;
+    let artist = create_artist(&app, "The Metronomes").await;
+    let first = create_album(&app, artist, "Tick").await;
+    let second = create_album(&app, artist, "Tock").await;
+    let song = create_song(&app, first, "Allegro").await;
+    let close = create_song(&app, first, "Andante").await;
+    let far = create_song(&app, second, "Largo").await;
+
+    for (song_id, tempo) in [(song, 120.0f32), (close, 124.0), (far, 60.0)] {
+        sqlx::query("UPDATE songs SET tempo = $2 WHERE song_id = $1")
+            .bind(song_id)
+            .bind(tempo)
+            .execute(&app.db)
+            .await
+            .unwrap();
+    }
+    sqlx::query("UPDATE songs SET external_url = ARRAY['https://open.spotify.com/track/1', 'https://example.com/allegro'] WHERE song_id = $1")
+        .bind(song)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let client = app.client();
+    let details = client.song(song).await.expect("Failed to fetch song");
+    assert_eq!(details.song.title, "Allegro");
+    assert_eq!(details.song.artist, "The Metronomes");
+    assert_eq!(details.song.album_id, first);
+    assert_eq!(details.features.tempo, Some(120.0));
+    let platforms = details.links.iter().map(|link| link.platform.clone()).collect::<Vec<_>>();
+    assert_eq!(platforms, [Some(Platform::Spotify), None]);
+    let similar = details.similar.iter().map(|song| song.song_id).collect::<Vec<_>>();
+    assert_eq!(similar, [close, far]);
+
+    let details = client.artist(artist).await.expect("Failed to fetch artist");
+    assert_eq!(details.artist.name, "The Metronomes");
+    assert_eq!(details.genres, ["Pop"]);
+    assert_eq!(details.albums.len(), 2);
+    assert_eq!(details.top_songs.len(), 3);
+
+    let details = client.album(first).await.expect("Failed to fetch album");
+    assert_eq!(details.album.track_count, 2);
+    let tracks = details.tracks.iter().map(|song| song.title.as_str()).collect::<Vec<_>>();
+    assert_eq!(tracks, ["Allegro", "Andante"]);
+
+    let error = client.song(uuid::Uuid::new_v4()).await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::NotFound);
+
+    app.cleanup().await;
+}
@@ -0,0 +1,117 @@
+mod support;
+
+use common::schema::{
+    api_token::{ApiTokenScope, CreateApiTokenSchema},
+    recommendation::{RecommendationFeedback, RecommendationReason}
+};
+use support::{factories::{create_album, create_artist, create_song, create_user}, TestApp};
+
+#[tokio::test]
+async fn recommendations_follow_likes_and_feedback() {
+    let app = TestApp::spawn().await;
+    let metronomes = create_artist(&app, "The Metronomes").await;
+    let tick = create_album(&app, metronomes, "Tick").await;
+    let liked = create_song(&app, tick, "Allegro").await;
+    let andante = create_song(&app, tick, "Andante").await;
+    let largo = create_song(&app, tick, "Largo").await;
+    let quartet = create_artist(&app, "The Quartet").await;
+    let strings = create_album(&app, quartet, "Strings").await;
+    let fugue = create_song(&app, strings, "Fugue").await;
+    sqlx::query("UPDATE songs SET genre = 'Jazz' WHERE song_id = $1")
+        .bind(fugue)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let user = create_user(&app, "ada").await;
+    let client = app.client();
+    client.login(&user.credentials()).await.expect("Failed to log in");
+    client.like_song(liked).await.expect("Failed to like song");
+
+    // Someone else listening to the unrelated song makes it popular
+    let other = app.client();
+    other.login(&create_user(&app, "grace").await.credentials()).await.expect("Failed to log in");
+    other.like_song(fugue).await.expect("Failed to like song");
+
+    let recommendations = client.recommendations(10).await.expect("Failed to fetch recommendations");
+    let songs = recommendations.iter().map(|recommendation| recommendation.song.song_id).collect::<Vec<_>>();
+    assert_eq!(songs, [andante, largo, fugue]);
+    assert_eq!(
+        recommendations[0].reason,
+        RecommendationReason::SimilarTo { song_id: liked, title: "Allegro".to_string() }
+    );
+    assert_eq!(recommendations[2].reason, RecommendationReason::Popular);
+    assert!(recommendations.windows(2).all(|pair| pair[0].match_score >= pair[1].match_score));
+
+    client.send_recommendation_feedback(andante, RecommendationFeedback::Hide).await.expect("Failed to send feedback");
+    client.send_recommendation_feedback(fugue, RecommendationFeedback::NotNow).await.expect("Failed to send feedback");
+    let recommendations = client.recommendations(10).await.expect("Failed to fetch recommendations");
+    let songs = recommendations.iter().map(|recommendation| recommendation.song.song_id).collect::<Vec<_>>();
+    assert_eq!(songs, [largo]);
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recommendations WHERE user_id = $1")
+        .bind(user.user_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(stored, 1);
+
+    client.clear_recommendation_feedback(fugue).await.expect("Failed to clear feedback");
+    let recommendations = client.recommendations(10).await.expect("Failed to fetch recommendations");
+    assert!(recommendations.iter().any(|recommendation| recommendation.song.song_id == fugue));
+
+    app.cleanup().await;
+}
+
+#[tokio::test]
+async fn recommendations_are_stored_once_per_user() {
+    let app = TestApp::spawn().await;
+    let metronomes = create_artist(&app, "The Metronomes").await;
+    let tick = create_album(&app, metronomes, "Tick").await;
+    let liked = create_song(&app, tick, "Allegro").await;
+    for title in ["Andante", "Largo", "Presto"] {
+        create_song(&app, tick, title).await;
+    }
+
+    let user = create_user(&app, "ada").await;
+    let client = app.client();
+    client.login(&user.credentials()).await.expect("Failed to log in");
+    client.like_song(liked).await.expect("Failed to like song");
+
+    let stored = || async {
+        sqlx::query_as::<_, (i64, i64)>(
+            "SELECT COUNT(*), COUNT(DISTINCT song_id) FROM recommendations WHERE user_id = $1"
+        )
+        .bind(user.user_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+    };
+
+    let requests = tokio::join!(
+        client.recommendations(10),
+        client.recommendations(10),
+        client.recommendations(10),
+        client.recommendations(10),
+    );
+    for recommendations in <[_; 4]>::from(requests) {
+        assert_eq!(recommendations.expect("Failed to fetch recommendations").len(), 3);
+    }
+    assert_eq!(stored().await, (3, 3));
+
+    // A read-only token gets recommendations without replacing the stored ones
+    let token = client
+        .create_api_token(&CreateApiTokenSchema {
+            name: "Read only".to_string(),
+            scopes: vec![ApiTokenScope::Read],
+            expires_in_days: None,
+        })
+        .await
+        .expect("Failed to create token")
+        .token;
+    let read_only = app.client().with_bearer_token(&token);
+    assert_eq!(read_only.recommendations(1).await.expect("Failed to fetch recommendations").len(), 1);
+    assert_eq!(stored().await, (3, 3));
+
+    app.cleanup().await;
+}
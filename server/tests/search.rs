@@ -0,0 +1,71 @@
+mod support;
+
+use support::{factories::{create_album, create_artist, create_song, create_user}, TestApp};
+use uuid::Uuid;
+
+/// A playlist owned by `user_id`, public or not.
+async fn create_playlist(app: &TestApp, user_id: Uuid, name: &str, is_public: bool) -> Uuid {
+    let playlist_id = Uuid::new_v4();
+
+    sqlx::query("INSERT INTO playlists (playlist_id, user_id, name, is_public) VALUES ($1, $2, $3, $4)")
+        .bind(playlist_id)
+        .bind(user_id)
+        .bind(name)
+        .bind(is_public)
+        .execute(&app.db)
+        .await
+        .expect("Failed to create playlist");
+
+    playlist_id
+}
+
+#[tokio::test]
+async fn search_groups_matches_best_first() {
+    let app = TestApp::spawn().await;
+    let nights = create_artist(&app, "Night Shift").await;
+    let album = create_album(&app, nights, "After Night").await;
+    let exact = create_song(&app, album, "night").await;
+    let prefix = create_song(&app, album, "Nightfall").await;
+    let contains = create_song(&app, album, "Midnight").await;
+    let other = create_artist(&app, "Daybreak").await;
+    let sunrise = create_album(&app, other, "Sunrise").await;
+    create_song(&app, sunrise, "Morning").await;
+
+    let ada = create_user(&app, "ada").await;
+    let shared = create_playlist(&app, ada.user_id, "Late night drives", true).await;
+    create_playlist(&app, ada.user_id, "Night notes", false).await;
+    let grace = create_user(&app, "grace").await;
+    create_playlist(&app, grace.user_id, "Night owls", true).await;
+    sqlx::query("UPDATE users SET profile_visibility = 'PRIVATE' WHERE user_id = $1")
+        .bind(grace.user_id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let client = app.client();
+    let results = client.search("  NIGHT ", 20).await.expect("Failed to search");
+    let songs = results.songs.iter().map(|song| song.song_id).collect::<Vec<_>>();
+    assert_eq!(songs, [exact, prefix, contains]);
+    assert_eq!(results.artists.iter().map(|artist| artist.artist_id).collect::<Vec<_>>(), [nights]);
+    assert_eq!(results.albums.iter().map(|album| album.album_id).collect::<Vec<_>>(), [album]);
+    assert_eq!(results.albums[0].track_count, 3);
+    // Private playlists and those of users with a private profile aren't found
+    let playlists = results.playlists.iter().map(|playlist| playlist.playlist_id).collect::<Vec<_>>();
+    assert_eq!(playlists, [shared]);
+    assert_eq!(results.playlists[0].owner.username, "ada");
+
+    // Songs also match by artist
+    let results = client.search("daybreak", 20).await.expect("Failed to search");
+    assert_eq!(results.songs.len(), 1);
+    assert_eq!(results.songs[0].title, "Morning");
+
+    let suggestions = client.search_suggestions("night").await.expect("Failed to fetch suggestions");
+    assert_eq!(suggestions.songs.len(), 3);
+    assert_eq!(suggestions.songs[0].song_id, exact);
+
+    // Wildcards match themselves
+    assert!(client.search("%", 20).await.expect("Failed to search").is_empty());
+    assert!(client.search_suggestions("").await.expect("Failed to fetch suggestions").is_empty());
+
+    app.cleanup().await;
+}
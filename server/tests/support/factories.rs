@@ -0,0 +1,136 @@
+//! Inserts fixtures straight into the test database, for tests about something other than creating them.
+
+use common::schema::user::LoginUserSchema;
+use server::utils::hash::hash;
+use uuid::Uuid;
+
+use super::TestApp;
+
+pub const PASSWORD: &str = "Correct-Horse-Battery-9";
+
+pub struct TestUser {
+    pub user_id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub password: String,
+}
+
+impl TestUser {
+    /// What the user logs in with.
+    pub fn credentials(&self) -> LoginUserSchema {
+        LoginUserSchema { email: self.email.clone(), password: self.password.clone() }
+    }
+}
+
+/// A verified user who can log in with `PASSWORD`.
+pub async fn create_user(app: &TestApp, username: &str) -> TestUser {
+    let user = TestUser {
+        user_id: Uuid::new_v4(),
+        username: username.to_string(),
+        email: format!("{}@example.com", username),
+        password: PASSWORD.to_string(),
+    };
+
+    sqlx::query(
+        "INSERT INTO users (user_id, name, username, email, password, verified) VALUES ($1, $2, $3, $4, $5, TRUE)",
+    )
+    .bind(user.user_id)
+    .bind(username)
+    .bind(&user.username)
+    .bind(&user.email)
+    .bind(hash(&user.password, &app.config.password).await.unwrap())
+    .execute(&app.db)
+    .await
+    .expect("Failed to create user");
+
+    user
+}
+
+/// A user who can upload audio for any song.
+pub async fn create_admin(app: &TestApp, username: &str) -> TestUser {
+    let user = create_user(app, username).await;
+
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE user_id = $1")
+        .bind(user.user_id)
+        .execute(&app.db)
+        .await
+        .expect("Failed to make user an admin");
+
+    user
+}
+
+pub async fn create_artist(app: &TestApp, name: &str) -> Uuid {
+    let artist_id = Uuid::new_v4();
+
+    sqlx::query(
+        "INSERT INTO artists (artist_id, name, genres, albums, tracks) VALUES ($1, $2, ARRAY['Pop']::genre[], '{}', '{}')",
+    )
+    .bind(artist_id)
+    .bind(name)
+    .execute(&app.db)
+    .await
+    .expect("Failed to create artist");
+
+    artist_id
+}
+
+/// An album by `artist_id`, added to the artist's albums.
+pub async fn create_album(app: &TestApp, artist_id: Uuid, title: &str) -> Uuid {
+    let album_id = Uuid::new_v4();
+
+    sqlx::query(
+        "INSERT INTO albums (album_id, title, artist_id, release_date, genre, tracks) VALUES ($1, $2, $3, NOW(), 'Pop', '{}')",
+    )
+    .bind(album_id)
+    .bind(title)
+    .bind(artist_id)
+    .execute(&app.db)
+    .await
+    .expect("Failed to create album");
+
+    sqlx::query("UPDATE artists SET albums = array_append(albums, $1) WHERE artist_id = $2")
+        .bind(album_id)
+        .bind(artist_id)
+        .execute(&app.db)
+        .await
+        .expect("Failed to add album to artist");
+
+    album_id
+}
+
+/// A three minute song on `album_id`, by the album's artist and added to both of their tracks.
+pub async fn create_song(app: &TestApp, album_id: Uuid, title: &str) -> Uuid {
+    let song_id = Uuid::new_v4();
+
+    let (artist_id,): (Uuid,) = sqlx::query_as("SELECT artist_id FROM albums WHERE album_id = $1")
+        .bind(album_id)
+        .fetch_one(&app.db)
+        .await
+        .expect("Album not found");
+
+    sqlx::query(
+        "INSERT INTO songs (song_id, title, artist_id, album_id, duration, genre, external_url) VALUES ($1, $2, $3, $4, 180, 'Pop', '{}')",
+    )
+    .bind(song_id)
+    .bind(title)
+    .bind(artist_id)
+    .bind(album_id)
+    .execute(&app.db)
+    .await
+    .expect("Failed to create song");
+
+    sqlx::query("UPDATE albums SET tracks = array_append(tracks, $1) WHERE album_id = $2")
+        .bind(song_id)
+        .bind(album_id)
+        .execute(&app.db)
+        .await
+        .expect("Failed to add song to album");
+    sqlx::query("UPDATE artists SET tracks = array_append(tracks, $1) WHERE artist_id = $2")
+        .bind(song_id)
+        .bind(artist_id)
+        .execute(&app.db)
+        .await
+        .expect("Failed to add song to artist");
+
+    song_id
+}
@@ -0,0 +1,142 @@
+//! Shared by the integration tests: runs the app in-process against a database of its own.
+//!
+//! `DATABASE_URL` must point at a Postgres server the tests can create databases on. Every
+//! `TestApp` creates and migrates a new `melody_test_<uuid>` database, dropped again by `cleanup`.
+//! Databases of failed tests are left behind to look at.
+
+#![allow(dead_code)]
+
+pub mod factories;
+
+use std::{net::SocketAddr, sync::{Arc, Mutex}};
+
+use api_client::{ApiClient, ReqwestTransport};
+use axum::async_trait;
+use server::{
+    app,
+    config::{Config, PasswordConfig},
+    mailer::{Email, Mailer, MailerError},
+    storage,
+    utils::keys::JwtKeys,
+    AppState
+};
+use sqlx::{postgres::PgPoolOptions, Connection, Executor, PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Keeps every email the app sends instead of delivering it.
+#[derive(Debug, Default)]
+pub struct RecordingMailer {
+    sent: Mutex<Vec<Email>>,
+}
+
+#[async_trait]
+impl Mailer for RecordingMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        self.sent.lock().unwrap().push(email);
+
+        Ok(())
+    }
+}
+
+impl RecordingMailer {
+    /// The last email sent to `to`.
+    pub fn last_to(&self, to: &str) -> Option<Email> {
+        self.sent.lock().unwrap().iter().rev().find(|email| email.to == to).cloned()
+    }
+}
+
+pub struct TestApp {
+    /// Where the app is listening, e.g. `http://127.0.0.1:41234`
+    pub address: String,
+    pub db: PgPool,
+    pub config: Config,
+    pub mailer: Arc<RecordingMailer>,
+    database_name: String,
+}
+
+impl TestApp {
+    /// Creates and migrates a database, then serves the app on a random port.
+    pub async fn spawn() -> TestApp {
+        let server_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set to run the integration tests");
+        let database_name = format!("melody_test_{}", Uuid::new_v4().simple());
+
+        let mut connection = PgConnection::connect(&server_url).await.expect("Failed to connect to Postgres");
+        connection
+            .execute(format!(r#"CREATE DATABASE "{}""#, database_name).as_str())
+            .await
+            .expect("Failed to create the test database");
+
+        let mut config = Config::default();
+        config.database.url = database_url(&server_url, &database_name);
+        config.jwt.secret = "integration-test-secret".to_string();
+        // No PEM keys, tokens are signed with the secret
+        config.jwt.keys_dir = temp_dir(&database_name, "keys");
+        config.cookies.secure = false;
+        config.storage.dir = temp_dir(&database_name, "uploads");
+        // Hashing with the production cost would make every registration and login take a while
+        config.password = PasswordConfig { memory_kib: 8, iterations: 1, parallelism: 1 };
+
+        let db = PgPoolOptions::new()
+            .max_connections(config.database.max_connections)
+            .connect(&config.database.url)
+            .await
+            .expect("Failed to connect to the test database");
+        sqlx::migrate!("./migrations").run(&db).await.expect("Failed to run migrations");
+
+        let keys = JwtKeys::from_config(&config.jwt).expect("Failed to load JWT keys");
+        let storage = storage::from_config(&config.storage).expect("Failed to set up storage");
+        let mailer = Arc::new(RecordingMailer::default());
+        let state = AppState::new(db.clone(), config.clone(), mailer.clone(), keys, storage);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app(state).into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+        });
+
+        TestApp { address, db, config, mailer, database_name }
+    }
+
+    /// A client with a cookie store of its own, so each one is a separate browser session.
+    pub fn client(&self) -> ApiClient<ReqwestTransport> {
+        ApiClient::new(&self.address, ReqwestTransport::new())
+    }
+
+    /// Drops the test database.
+    pub async fn cleanup(self) {
+        self.db.close().await;
+
+        let server_url = std::env::var("DATABASE_URL").unwrap();
+        let mut connection = PgConnection::connect(&server_url).await.expect("Failed to connect to Postgres");
+        connection
+            .execute(format!(r#"DROP DATABASE IF EXISTS "{}" WITH (FORCE)"#, self.database_name).as_str())
+            .await
+            .expect("Failed to drop the test database");
+
+        let _ = std::fs::remove_dir_all(std::env::temp_dir().join(&self.database_name));
+    }
+}
+
+/// `server_url` with its database replaced by `database_name`.
+fn database_url(server_url: &str, database_name: &str) -> String {
+    let (without_query, query) = match server_url.split_once('?') {
+        Some((url, query)) => (url, format!("?{}", query)),
+        None => (server_url, String::new()),
+    };
+    let server = without_query.rsplit_once('/').map(|(server, _)| server).unwrap_or(without_query);
+
+    format!("{}/{}{}", server, database_name, query)
+}
+
+fn temp_dir(database_name: &str, name: &str) -> String {
+    std::env::temp_dir().join(database_name).join(name).to_string_lossy().into_owned()
+}
+
+/// The `token` query parameter of the first link in an email.
+pub fn link_token(email: &Email) -> String {
+    email
+        .body
+        .split_whitespace()
+        .find_map(|word| word.split_once("token=").map(|(_, token)| token.to_string()))
+        .expect("No link with a token in the email")
+}